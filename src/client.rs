@@ -1,9 +1,10 @@
 use anyhow::{Context, Result, anyhow};
 use base64::{Engine as _, engine::general_purpose::URL_SAFE_NO_PAD};
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime};
 use log::debug;
 use reqwest::Client;
-use serde::Deserialize;
 use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
 use std::sync::{Arc, Mutex};
@@ -479,7 +480,7 @@ struct PulseListResponse {
     pub filters: Option<PulseFilters>,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct UserResponse {
     pub id: u64,
     pub username: String,
@@ -514,7 +515,7 @@ pub struct UserResponse {
     pub last_pulse: Option<LastPulse>,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct LastPulse {
     pub date: String,
     pub keys: Option<u64>,
@@ -530,7 +531,7 @@ pub struct LastPulse {
     pub distance_miles: Option<f64>,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct UserTotals {
     pub keys: Option<u64>,
     pub clicks: Option<u64>,
@@ -546,7 +547,7 @@ pub struct UserTotals {
     pub distance_miles: Option<f64>,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct UserRanks {
     pub keys: u64,
     pub clicks: u64,
@@ -557,7 +558,7 @@ pub struct UserRanks {
     pub distance: u64,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct PulseResponse {
     pub id: u64,
     pub date: String,
@@ -578,6 +579,30 @@ pub struct PulseResponse {
     pub client_version: Option<String>,
 }
 
+/// Pulse dates come as `2023-01-01 12:00:00` in local time, or RFC 3339.
+const PULSE_DATE_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+impl PulseResponse {
+    /// When the pulse was sent, in local time.
+    pub fn timestamp(&self) -> Option<DateTime<Local>> {
+        match NaiveDateTime::parse_from_str(&self.date, PULSE_DATE_FORMAT) {
+            Ok(dt) => dt.and_local_timezone(Local).latest(),
+            Err(_) => DateTime::parse_from_rfc3339(&self.date)
+                .ok()
+                .map(|dt| dt.with_timezone(&Local)),
+        }
+    }
+
+    /// The day the pulse was sent, as written in its date.
+    pub fn day(&self) -> Option<NaiveDate> {
+        NaiveDateTime::parse_from_str(&self.date, PULSE_DATE_FORMAT)
+            .map(|dt| dt.date())
+            .or_else(|_| DateTime::parse_from_rfc3339(&self.date).map(|dt| dt.date_naive()))
+            .or_else(|_| NaiveDate::parse_from_str(&self.date, "%Y-%m-%d"))
+            .ok()
+    }
+}
+
 #[derive(Debug, Deserialize)]
 #[allow(dead_code)]
 pub struct ComputerFilters {
@@ -595,7 +620,7 @@ pub struct ComputerListResponse {
     pub filters: Option<ComputerFilters>,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct ComputerResponse {
     pub id: u64,
     pub name: String,
@@ -611,7 +636,7 @@ pub struct ComputerResponse {
     pub hardware: Option<Value>,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct ComputerTotals {
     pub keys: u64,
     pub clicks: u64,
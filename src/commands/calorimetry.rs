@@ -80,6 +80,51 @@ impl SwitchProfile {
     }
 }
 
+/// A mouse button switch, for click work on the Kinetic page.
+#[derive(Debug, Clone)]
+pub struct MouseSwitchProfile {
+    pub name: String,
+    pub force_newtons: f64,
+    pub travel_meters: f64,
+}
+
+impl Default for MouseSwitchProfile {
+    fn default() -> Self {
+        Self::omron_d2fc()
+    }
+}
+
+impl MouseSwitchProfile {
+    pub fn new(name: &str, force_g: f64, travel_mm: f64) -> Self {
+        Self {
+            name: name.to_string(),
+            force_newtons: force_g * 0.00980665,
+            travel_meters: travel_mm / 1000.0,
+        }
+    }
+
+    pub fn omron_d2fc() -> Self {
+        Self::new("Omron D2FC-F-7N", 75.0, 0.6)
+    }
+
+    pub fn kailh_gm8() -> Self {
+        Self::new("Kailh GM 8.0", 70.0, 0.6)
+    }
+
+    pub fn huano_pink_dot() -> Self {
+        Self::new("Huano Blue Shell Pink Dot", 80.0, 0.6)
+    }
+
+    pub fn optical() -> Self {
+        Self::new("Optical Switch", 65.0, 0.5) // Approx
+    }
+
+    /// Work for one click in Joules.
+    pub fn work_per_click(&self) -> f64 {
+        self.force_newtons * self.travel_meters
+    }
+}
+
 pub struct EnergyStats {
     #[allow(dead_code)]
     pub total_keys: f64,
@@ -67,7 +67,7 @@ pub fn render_tui(f: &mut Frame, app: &App, area: Rect) {
 
         // Sort computers by keys (descending)
         let mut comps: Vec<_> = app.computers.iter().collect();
-        comps.sort_by(|a, b| b.totals.keys.cmp(&a.totals.keys));
+        comps.sort_by_key(|c| std::cmp::Reverse(c.totals.keys));
 
         for comp in comps {
             rows.push(Row::new(vec![
@@ -0,0 +1,395 @@
+use crate::client::{PulseResponse, WhatpulseClient};
+use crate::db::Database;
+use crate::tui::period_utils::{is_date_in_period, parse_period_string};
+use anyhow::{Context, Result};
+use chrono::NaiveDate;
+use serde_json::{Map, Value, json};
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, Copy, PartialEq, clap::ValueEnum)]
+pub enum ExportDataset {
+    /// Input and bandwidth totals
+    Keys,
+    /// Per-application input and bandwidth
+    Apps,
+    /// Per-interface bandwidth
+    Network,
+    /// Clicks, scrolls, distance and clicks per button
+    Mouse,
+    /// Per-key press counts
+    Heatmap,
+    /// Pulse history (Web API)
+    Pulses,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, clap::ValueEnum)]
+pub enum ExportFormat {
+    #[default]
+    Csv,
+    Json,
+    Ndjson,
+}
+
+/// A flat table of records. Columns keep first-seen order so CSV headers are
+/// stable even when later rows introduce new fields (e.g. extra mouse buttons).
+#[derive(Debug, Default, PartialEq)]
+pub struct Table {
+    pub columns: Vec<String>,
+    pub rows: Vec<Map<String, Value>>,
+}
+
+impl Table {
+    pub fn push(&mut self, fields: Vec<(&str, Value)>) {
+        let mut row = Map::new();
+        for (name, value) in fields {
+            if !self.columns.iter().any(|c| c == name) {
+                self.columns.push(name.to_string());
+            }
+            row.insert(name.to_string(), value);
+        }
+        self.rows.push(row);
+    }
+
+    pub fn write(&self, format: ExportFormat, out: &mut dyn Write) -> Result<()> {
+        match format {
+            ExportFormat::Csv => {
+                let header: Vec<String> = self.columns.iter().map(|c| csv_field(c)).collect();
+                writeln!(out, "{}", header.join(","))?;
+                for row in &self.rows {
+                    let fields: Vec<String> = self
+                        .columns
+                        .iter()
+                        .map(|c| match row.get(c) {
+                            None | Some(Value::Null) => String::new(),
+                            Some(Value::String(s)) => csv_field(s),
+                            Some(v) => v.to_string(),
+                        })
+                        .collect();
+                    writeln!(out, "{}", fields.join(","))?;
+                }
+            }
+            ExportFormat::Json => {
+                serde_json::to_writer_pretty(&mut *out, &self.rows)?;
+                writeln!(out)?;
+            }
+            ExportFormat::Ndjson => {
+                for row in &self.rows {
+                    serde_json::to_writer(&mut *out, row)?;
+                    writeln!(out)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+pub(crate) fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+/// Rounds MB figures so exports don't carry float noise like `12.300000000000001`.
+fn mb(value: f64) -> Value {
+    json!((value * 1000.0).round() / 1000.0)
+}
+
+fn with_day<'a>(day: Option<&str>, mut fields: Vec<(&'a str, Value)>) -> Vec<(&'a str, Value)> {
+    if let Some(day) = day {
+        fields.insert(0, ("day", json!(day)));
+    }
+    fields
+}
+
+/// Appends one dataset's rows for `period` to `table`, prefixing each row with
+/// `day` when exporting per-day.
+fn append_db_rows(
+    table: &mut Table,
+    db: &Database,
+    dataset: ExportDataset,
+    period: &str,
+    day: Option<&str>,
+) -> Result<()> {
+    match dataset {
+        ExportDataset::Keys => {
+            let totals = db.get_daily_totals(period)?;
+            let sum = |f: fn(&crate::db::DailyTotals) -> f64| totals.iter().map(f).sum::<f64>();
+            table.push(with_day(
+                day,
+                vec![
+                    ("keys", json!(totals.iter().map(|t| t.keys).sum::<u64>())),
+                    (
+                        "clicks",
+                        json!(totals.iter().map(|t| t.clicks).sum::<u64>()),
+                    ),
+                    (
+                        "scrolls",
+                        json!(totals.iter().map(|t| t.scrolls).sum::<u64>()),
+                    ),
+                    ("download_mb", mb(sum(|t| t.download_mb))),
+                    ("upload_mb", mb(sum(|t| t.upload_mb))),
+                ],
+            ));
+        }
+        ExportDataset::Apps => {
+            for app in db.get_app_stats(period)? {
+                table.push(with_day(
+                    day,
+                    vec![
+                        ("name", json!(app.name)),
+                        ("keys", json!(app.keys)),
+                        ("clicks", json!(app.clicks)),
+                        ("scrolls", json!(app.scrolls)),
+                        ("download_mb", mb(app.download_mb)),
+                        ("upload_mb", mb(app.upload_mb)),
+                    ],
+                ));
+            }
+        }
+        ExportDataset::Network => {
+            for iface in db.get_network_stats(period)? {
+                table.push(with_day(
+                    day,
+                    vec![
+                        ("interface", json!(iface.interface)),
+                        ("download_mb", mb(iface.download_mb)),
+                        ("upload_mb", mb(iface.upload_mb)),
+                    ],
+                ));
+            }
+        }
+        ExportDataset::Mouse => {
+            let stats = db.get_mouse_stats(period)?;
+            let buttons: Vec<(String, u64)> = stats
+                .clicks_by_button
+                .into_iter()
+                .collect::<BTreeMap<i64, u64>>()
+                .into_iter()
+                .map(|(button, count)| (format!("button_{}", button), count))
+                .collect();
+            let mut fields = with_day(
+                day,
+                vec![
+                    ("clicks", json!(stats.clicks)),
+                    ("scrolls", json!(stats.scrolls)),
+                    ("distance_meters", json!(stats.distance_meters)),
+                ],
+            );
+            fields.extend(
+                buttons
+                    .iter()
+                    .map(|(name, count)| (name.as_str(), json!(count))),
+            );
+            table.push(fields);
+        }
+        ExportDataset::Heatmap => {
+            let mut keys: Vec<(String, u64)> = db.get_heatmap_stats(period)?.into_iter().collect();
+            keys.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+            for (key, count) in keys {
+                table.push(with_day(
+                    day,
+                    vec![("key", json!(key)), ("count", json!(count))],
+                ));
+            }
+        }
+        ExportDataset::Pulses => anyhow::bail!("Pulses are not stored in the local database"),
+    }
+    Ok(())
+}
+
+/// Builds a table from the local database. With `daily`, the dataset is
+/// queried once per active day in the period (`custom:D:D`).
+pub fn db_table(db: &Database, dataset: ExportDataset, period: &str, daily: bool) -> Result<Table> {
+    let mut table = Table::default();
+    if daily {
+        for totals in db.get_daily_totals(period)? {
+            let day_period = format!("custom:{}:{}", totals.day, totals.day);
+            append_db_rows(&mut table, db, dataset, &day_period, Some(&totals.day))?;
+        }
+    } else {
+        append_db_rows(&mut table, db, dataset, period, None)?;
+    }
+    Ok(table)
+}
+
+/// Builds a table from pulses, one row per pulse or per day when `daily`.
+pub fn pulses_table(
+    pulses: &[PulseResponse],
+    include: impl Fn(NaiveDate) -> bool,
+    daily: bool,
+) -> Table {
+    let mut table = Table::default();
+    let selected = pulses
+        .iter()
+        .filter_map(|p| p.day().map(|d| (d, p)))
+        .filter(|(d, _)| include(*d));
+
+    if daily {
+        let mut days: BTreeMap<NaiveDate, [f64; 6]> = BTreeMap::new();
+        for (date, p) in selected {
+            let day = days.entry(date).or_default();
+            day[0] += p.keys.unwrap_or(0) as f64;
+            day[1] += p.clicks.unwrap_or(0) as f64;
+            day[2] += p.scrolls.unwrap_or(0) as f64;
+            day[3] += p.download_mb.unwrap_or(0.0);
+            day[4] += p.upload_mb.unwrap_or(0.0);
+            day[5] += p.uptime_seconds.unwrap_or(0) as f64;
+        }
+        for (date, [keys, clicks, scrolls, down, up, uptime]) in days {
+            table.push(vec![
+                ("day", json!(date.to_string())),
+                ("keys", json!(keys as u64)),
+                ("clicks", json!(clicks as u64)),
+                ("scrolls", json!(scrolls as u64)),
+                ("download_mb", mb(down)),
+                ("upload_mb", mb(up)),
+                ("uptime_seconds", json!(uptime as u64)),
+            ]);
+        }
+    } else {
+        let mut selected: Vec<_> = selected.collect();
+        selected.sort_by(|a, b| a.1.date.cmp(&b.1.date));
+        for (_, p) in selected {
+            table.push(vec![
+                ("id", json!(p.id)),
+                ("date", json!(p.date)),
+                ("keys", json!(p.keys)),
+                ("clicks", json!(p.clicks)),
+                ("scrolls", json!(p.scrolls)),
+                ("download_mb", json!(p.download_mb)),
+                ("upload_mb", json!(p.upload_mb)),
+                ("uptime_seconds", json!(p.uptime_seconds)),
+                ("distance_miles", json!(p.distance_miles)),
+                ("auto_pulse", json!(p.auto_pulse)),
+                ("client_version", json!(p.client_version)),
+            ]);
+        }
+    }
+    table
+}
+
+pub async fn execute(
+    client: &WhatpulseClient,
+    dataset: ExportDataset,
+    period: &str,
+    format: ExportFormat,
+    out: Option<PathBuf>,
+    daily: bool,
+) -> Result<()> {
+    let (time_period, date_picker) = parse_period_string(period)?;
+
+    let table = if dataset == ExportDataset::Pulses {
+        if client.is_local() {
+            anyhow::bail!("Exporting pulses requires an API key (WHATPULSE_API_KEY)");
+        }
+        let pulses = client.get_pulses().await?;
+        pulses_table(
+            &pulses,
+            |d| is_date_in_period(d, time_period, &date_picker),
+            daily,
+        )
+    } else {
+        let db = Database::new()?;
+        db_table(&db, dataset, period, daily)?
+    };
+
+    match out {
+        Some(path) => {
+            let file = std::fs::File::create(&path)
+                .with_context(|| format!("Failed to create {}", path.display()))?;
+            let mut writer = std::io::BufWriter::new(file);
+            table.write(format, &mut writer)?;
+            writer.flush()?;
+            eprintln!("Wrote {} rows to {}", table.rows.len(), path.display());
+        }
+        None => {
+            let stdout = std::io::stdout();
+            table.write(format, &mut stdout.lock())?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(table: &Table, format: ExportFormat) -> String {
+        let mut buf = Vec::new();
+        table.write(format, &mut buf).unwrap();
+        String::from_utf8(buf).unwrap()
+    }
+
+    #[test]
+    fn test_csv_escapes_and_fills_missing_columns() {
+        let mut table = Table::default();
+        table.push(vec![
+            ("name", json!("Code, \"Insiders\"")),
+            ("keys", json!(3)),
+        ]);
+        table.push(vec![("name", json!("zsh")), ("button_2", json!(1))]);
+
+        assert_eq!(
+            render(&table, ExportFormat::Csv),
+            "name,keys,button_2\n\"Code, \"\"Insiders\"\"\",3,\nzsh,,1\n"
+        );
+    }
+
+    #[test]
+    fn test_ndjson_writes_one_object_per_line() {
+        let mut table = Table::default();
+        table.push(vec![("key", json!("A")), ("count", json!(5))]);
+        table.push(vec![("key", json!("B")), ("count", json!(2))]);
+
+        let out = render(&table, ExportFormat::Ndjson);
+        let lines: Vec<Value> = out
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert_eq!(
+            lines,
+            vec![
+                json!({"key": "A", "count": 5}),
+                json!({"key": "B", "count": 2})
+            ]
+        );
+    }
+
+    #[test]
+    fn test_pulses_aggregate_per_day() {
+        let pulse = |id, date: &str, keys| PulseResponse {
+            id,
+            date: date.to_string(),
+            keys: Some(keys),
+            clicks: None,
+            download_mb: Some(1.5),
+            upload_mb: None,
+            uptime_seconds: None,
+            scrolls: None,
+            distance_miles: None,
+            auto_pulse: None,
+            client_version: None,
+        };
+        let pulses = vec![
+            pulse(1, "2025-01-02 09:00:00", 10),
+            pulse(2, "2025-01-02 18:00:00", 5),
+            pulse(3, "2025-01-01 12:00:00", 7),
+            pulse(4, "2025-01-01T20:00:00+00:00", 1),
+        ];
+
+        let table = pulses_table(&pulses, |_| true, true);
+        assert_eq!(table.rows.len(), 2);
+        assert_eq!(table.rows[0]["day"], json!("2025-01-01"));
+        assert_eq!(table.rows[0]["keys"], json!(8));
+        assert_eq!(table.rows[1]["keys"], json!(15));
+        assert_eq!(table.rows[1]["download_mb"], json!(3.0));
+
+        let table = pulses_table(&pulses, |d| d.to_string() == "2025-01-01", false);
+        assert_eq!(table.rows.len(), 2);
+        assert_eq!(table.rows[0]["id"], json!(3));
+    }
+}
@@ -0,0 +1,461 @@
+use crate::client::{PulseResponse, UserTotals, WhatpulseClient};
+use crate::db::{DailyTotals, Database};
+use anyhow::Result;
+use chrono::{Datelike, Local, NaiveDate};
+use std::collections::BTreeMap;
+
+/// Days of history used for the linear and seasonal fits.
+const FIT_DAYS: usize = 90;
+/// Window for the moving-average model.
+const MOVING_AVERAGE_DAYS: usize = 7;
+/// ETAs further out than this are reported as "not within 10 years".
+pub const MAX_HORIZON_DAYS: usize = 3650;
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, clap::ValueEnum)]
+pub enum ForecastMetric {
+    #[default]
+    Keys,
+    Clicks,
+    Scrolls,
+    Download,
+    Upload,
+    Uptime,
+}
+
+impl ForecastMetric {
+    pub fn label(self) -> &'static str {
+        match self {
+            ForecastMetric::Keys => "Keys",
+            ForecastMetric::Clicks => "Clicks",
+            ForecastMetric::Scrolls => "Scrolls",
+            ForecastMetric::Download => "Download",
+            ForecastMetric::Upload => "Upload",
+            ForecastMetric::Uptime => "Uptime",
+        }
+    }
+
+    pub fn unit(self) -> &'static str {
+        match self {
+            ForecastMetric::Keys | ForecastMetric::Clicks | ForecastMetric::Scrolls => "",
+            ForecastMetric::Download | ForecastMetric::Upload => " MB",
+            ForecastMetric::Uptime => " h",
+        }
+    }
+
+    fn pulse_value(self, pulse: &PulseResponse) -> f64 {
+        match self {
+            ForecastMetric::Keys => pulse.keys.unwrap_or(0) as f64,
+            ForecastMetric::Clicks => pulse.clicks.unwrap_or(0) as f64,
+            ForecastMetric::Scrolls => pulse.scrolls.unwrap_or(0) as f64,
+            ForecastMetric::Download => pulse.download_mb.unwrap_or(0.0),
+            ForecastMetric::Upload => pulse.upload_mb.unwrap_or(0.0),
+            ForecastMetric::Uptime => pulse.uptime_seconds.unwrap_or(0) as f64 / 3600.0,
+        }
+    }
+
+    fn daily_value(self, totals: &DailyTotals) -> f64 {
+        match self {
+            ForecastMetric::Keys => totals.keys as f64,
+            ForecastMetric::Clicks => totals.clicks as f64,
+            ForecastMetric::Scrolls => totals.scrolls as f64,
+            ForecastMetric::Download => totals.download_mb,
+            ForecastMetric::Upload => totals.upload_mb,
+            // The local database does not keep uptime per day.
+            ForecastMetric::Uptime => 0.0,
+        }
+    }
+
+    pub fn current_total(self, totals: &UserTotals) -> f64 {
+        match self {
+            ForecastMetric::Keys => totals.keys.unwrap_or(0) as f64,
+            ForecastMetric::Clicks => totals.clicks.unwrap_or(0) as f64,
+            ForecastMetric::Scrolls => totals.scrolls as f64,
+            ForecastMetric::Download => totals.download_mb.unwrap_or(0.0),
+            ForecastMetric::Upload => totals.upload_mb.unwrap_or(0.0),
+            ForecastMetric::Uptime => totals.uptime_seconds.unwrap_or(0) as f64 / 3600.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, clap::ValueEnum)]
+pub enum ForecastMethod {
+    /// Flat projection of the last 7 days' average
+    MovingAverage,
+    /// Least-squares trend line
+    Linear,
+    /// Trend line scaled by day-of-week factors
+    #[default]
+    Seasonal,
+}
+
+impl ForecastMethod {
+    pub fn next(self) -> Self {
+        match self {
+            ForecastMethod::MovingAverage => ForecastMethod::Linear,
+            ForecastMethod::Linear => ForecastMethod::Seasonal,
+            ForecastMethod::Seasonal => ForecastMethod::MovingAverage,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ForecastMethod::MovingAverage => "7-day moving average",
+            ForecastMethod::Linear => "Linear trend",
+            ForecastMethod::Seasonal => "Seasonal trend",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MilestoneEta {
+    Reached,
+    On(NaiveDate, usize),
+    NotWithinHorizon,
+}
+
+/// Builds a gap-free daily series (missing days are zero) ending yesterday,
+/// since today's numbers are still incomplete.
+pub fn build_series(points: &BTreeMap<NaiveDate, f64>, today: NaiveDate) -> Vec<(NaiveDate, f64)> {
+    let Some(&first) = points.keys().next() else {
+        return Vec::new();
+    };
+    let Some(last) = today.pred_opt() else {
+        return Vec::new();
+    };
+
+    let mut series = Vec::new();
+    let mut day = first;
+    while day <= last {
+        series.push((day, points.get(&day).copied().unwrap_or(0.0)));
+        day = match day.succ_opt() {
+            Some(d) => d,
+            None => break,
+        };
+    }
+    series
+}
+
+pub fn series_from_pulses(
+    pulses: &[PulseResponse],
+    metric: ForecastMetric,
+    today: NaiveDate,
+) -> Vec<(NaiveDate, f64)> {
+    let mut points = BTreeMap::new();
+    for pulse in pulses {
+        let Some(date) = pulse.day() else {
+            continue;
+        };
+        *points.entry(date).or_insert(0.0) += metric.pulse_value(pulse);
+    }
+    build_series(&points, today)
+}
+
+pub fn series_from_daily_totals(
+    rows: &[DailyTotals],
+    metric: ForecastMetric,
+    today: NaiveDate,
+) -> Vec<(NaiveDate, f64)> {
+    let mut points = BTreeMap::new();
+    for row in rows {
+        if let Ok(date) = NaiveDate::parse_from_str(&row.day, "%Y-%m-%d") {
+            *points.entry(date).or_insert(0.0) += metric.daily_value(row);
+        }
+    }
+    build_series(&points, today)
+}
+
+fn linear_fit(values: &[f64]) -> (f64, f64) {
+    let n = values.len() as f64;
+    if values.len() < 2 {
+        return (values.first().copied().unwrap_or(0.0), 0.0);
+    }
+    let mean_t = (n - 1.0) / 2.0;
+    let mean_y = values.iter().sum::<f64>() / n;
+    let mut num = 0.0;
+    let mut den = 0.0;
+    for (t, y) in values.iter().enumerate() {
+        let dt = t as f64 - mean_t;
+        num += dt * (y - mean_y);
+        den += dt * dt;
+    }
+    let slope = if den > 0.0 { num / den } else { 0.0 };
+    (mean_y - slope * mean_t, slope)
+}
+
+/// Day-of-week multipliers (Mon = 0) relative to the overall mean.
+fn weekday_factors(series: &[(NaiveDate, f64)]) -> [f64; 7] {
+    let mut sums = [0.0; 7];
+    let mut counts = [0usize; 7];
+    for (date, value) in series {
+        let idx = date.weekday().num_days_from_monday() as usize;
+        sums[idx] += value;
+        counts[idx] += 1;
+    }
+    let mean = series.iter().map(|(_, v)| v).sum::<f64>() / series.len().max(1) as f64;
+    let mut factors = [1.0; 7];
+    if mean > 0.0 {
+        for i in 0..7 {
+            if counts[i] > 0 {
+                factors[i] = sums[i] / counts[i] as f64 / mean;
+            }
+        }
+    }
+    factors
+}
+
+/// Projects daily values for the `horizon` days following the series.
+pub fn project(
+    series: &[(NaiveDate, f64)],
+    method: ForecastMethod,
+    horizon: usize,
+) -> Vec<(NaiveDate, f64)> {
+    let Some(&(last_day, _)) = series.last() else {
+        return Vec::new();
+    };
+    let recent = &series[series.len().saturating_sub(FIT_DAYS)..];
+    let values: Vec<f64> = recent.iter().map(|(_, v)| *v).collect();
+
+    let factors = if method == ForecastMethod::Seasonal {
+        weekday_factors(recent)
+    } else {
+        [1.0; 7]
+    };
+    let factor = |date: NaiveDate| factors[date.weekday().num_days_from_monday() as usize];
+
+    let (intercept, slope) = match method {
+        ForecastMethod::MovingAverage => {
+            let window = &values[values.len().saturating_sub(MOVING_AVERAGE_DAYS)..];
+            (window.iter().sum::<f64>() / window.len().max(1) as f64, 0.0)
+        }
+        ForecastMethod::Linear => linear_fit(&values),
+        ForecastMethod::Seasonal => {
+            let deseasonalized: Vec<f64> = recent
+                .iter()
+                .map(|(d, v)| {
+                    let f = factor(*d);
+                    if f > 0.0 { v / f } else { *v }
+                })
+                .collect();
+            linear_fit(&deseasonalized)
+        }
+    };
+    // The moving average is a level, not a line anchored at the window start.
+    let origin = if method == ForecastMethod::MovingAverage {
+        0.0
+    } else {
+        values.len() as f64
+    };
+
+    let mut out = Vec::with_capacity(horizon);
+    let mut day = last_day;
+    for k in 0..horizon {
+        day = match day.succ_opt() {
+            Some(d) => d,
+            None => break,
+        };
+        let trend = intercept + slope * (origin + k as f64);
+        out.push((day, (trend * factor(day)).max(0.0)));
+    }
+    out
+}
+
+/// When the cumulative projection first covers the gap to `target`.
+pub fn milestone_eta(
+    projection: &[(NaiveDate, f64)],
+    current_total: f64,
+    target: f64,
+) -> MilestoneEta {
+    if current_total >= target {
+        return MilestoneEta::Reached;
+    }
+    let mut cumulative = current_total;
+    for (i, (date, value)) in projection.iter().enumerate() {
+        cumulative += value;
+        if cumulative >= target {
+            return MilestoneEta::On(*date, i + 1);
+        }
+    }
+    MilestoneEta::NotWithinHorizon
+}
+
+/// Next "round" milestones above the current total: the next leading-digit
+/// step and the next power of ten (e.g. 3,186,900 -> 4,000,000 and 10,000,000).
+pub fn default_milestones(current_total: u64) -> Vec<u64> {
+    let magnitude = 10u64.pow((current_total.max(1) as f64).log10().floor() as u32);
+    let next_step = (current_total / magnitude + 1) * magnitude;
+    let next_power = magnitude.saturating_mul(10);
+    let mut milestones = vec![next_step, next_power];
+    milestones.dedup();
+    milestones
+}
+
+pub fn format_eta(eta: &MilestoneEta) -> String {
+    match eta {
+        MilestoneEta::Reached => "reached".to_string(),
+        MilestoneEta::On(date, days) => format!("{} (in {} days)", date, days),
+        MilestoneEta::NotWithinHorizon => "not within 10 years".to_string(),
+    }
+}
+
+pub async fn execute(
+    client: &WhatpulseClient,
+    metric: ForecastMetric,
+    method: ForecastMethod,
+    target: Option<u64>,
+) -> Result<()> {
+    let today = Local::now().date_naive();
+    let user = client.get_user().await?;
+    let current_total = metric.current_total(&user.totals);
+
+    let mut series = Vec::new();
+    let mut source = "pulse history";
+    if !client.is_local() {
+        series = series_from_pulses(&client.get_pulses().await?, metric, today);
+    }
+    if series.is_empty() {
+        source = "local database";
+        let rows = Database::new()?.get_daily_totals("all")?;
+        series = series_from_daily_totals(&rows, metric, today);
+    }
+    if series.is_empty() {
+        anyhow::bail!("No daily history available to fit a forecast");
+    }
+
+    let projection = project(&series, method, MAX_HORIZON_DAYS);
+    let last_week: f64 = series
+        .iter()
+        .rev()
+        .take(MOVING_AVERAGE_DAYS)
+        .map(|(_, v)| v)
+        .sum::<f64>()
+        / series.len().min(MOVING_AVERAGE_DAYS) as f64;
+    let next_30: f64 = projection.iter().take(30).map(|(_, v)| v).sum();
+    let unit = metric.unit();
+
+    println!(
+        "\nForecast: {} ({}, {} days of {})",
+        metric.label(),
+        method.label(),
+        series.len().min(FIT_DAYS),
+        source
+    );
+    println!("──────────────────────────");
+    println!("Current total:     {:.0}{}", current_total, unit);
+    println!("Last 7 days avg:   {:.0}{}/day", last_week, unit);
+    println!("Next 30 days:      +{:.0}{}", next_30, unit);
+    println!("──────────────────────────");
+
+    let targets = match target {
+        Some(t) => vec![t],
+        None => default_milestones(current_total as u64),
+    };
+    for t in targets {
+        let eta = milestone_eta(&projection, current_total, t as f64);
+        println!("{}{}: {}", t, unit, format_eta(&eta));
+    }
+
+    Ok(())
+}
+
+/// ETA lines for the Overview page from a [`project`]ion over
+/// [`MAX_HORIZON_DAYS`], in the same order as `milestones`.
+pub fn overview_etas(
+    projection: &[(NaiveDate, f64)],
+    current_total: u64,
+    milestones: &[u64],
+) -> (f64, Vec<(u64, MilestoneEta)>) {
+    let per_day = projection.iter().take(30).map(|(_, v)| v).sum::<f64>()
+        / projection.len().clamp(1, 30) as f64;
+    let etas = milestones
+        .iter()
+        .map(|&m| (m, milestone_eta(projection, current_total as f64, m as f64)))
+        .collect();
+    (per_day, etas)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Days;
+
+    fn days_ago(today: NaiveDate, n: u64) -> NaiveDate {
+        today.checked_sub_days(Days::new(n)).unwrap()
+    }
+
+    fn flat_series(today: NaiveDate, days: u64, value: f64) -> Vec<(NaiveDate, f64)> {
+        let mut points = BTreeMap::new();
+        for n in 1..=days {
+            points.insert(days_ago(today, n), value);
+        }
+        build_series(&points, today)
+    }
+
+    #[test]
+    fn test_build_series_fills_gaps_and_skips_today() {
+        let today = NaiveDate::from_ymd_opt(2024, 5, 10).unwrap();
+        let mut points = BTreeMap::new();
+        points.insert(days_ago(today, 3), 5.0);
+        points.insert(today, 99.0);
+        let series = build_series(&points, today);
+        assert_eq!(series.len(), 3);
+        assert_eq!(series[0], (days_ago(today, 3), 5.0));
+        assert_eq!(series[2], (days_ago(today, 1), 0.0));
+    }
+
+    #[test]
+    fn test_projection_methods_on_flat_history() {
+        let today = NaiveDate::from_ymd_opt(2024, 5, 10).unwrap();
+        let series = flat_series(today, 28, 1000.0);
+        for method in [
+            ForecastMethod::MovingAverage,
+            ForecastMethod::Linear,
+            ForecastMethod::Seasonal,
+        ] {
+            let projection = project(&series, method, 10);
+            assert_eq!(projection.len(), 10);
+            assert_eq!(projection[0].0, today);
+            assert!(projection.iter().all(|(_, v)| (v - 1000.0).abs() < 1e-6));
+        }
+    }
+
+    #[test]
+    fn test_linear_projection_follows_trend() {
+        let today = NaiveDate::from_ymd_opt(2024, 5, 10).unwrap();
+        let mut points = BTreeMap::new();
+        for n in 1..=10u64 {
+            // 100, 200, ... 1000 with yesterday being the largest.
+            points.insert(days_ago(today, n), ((11 - n) * 100) as f64);
+        }
+        let series = build_series(&points, today);
+        let projection = project(&series, ForecastMethod::Linear, 2);
+        assert!((projection[0].1 - 1100.0).abs() < 1e-6);
+        assert!((projection[1].1 - 1200.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_milestone_eta() {
+        let today = NaiveDate::from_ymd_opt(2024, 5, 10).unwrap();
+        let series = flat_series(today, 14, 1000.0);
+        let projection = project(&series, ForecastMethod::MovingAverage, 100);
+
+        assert_eq!(
+            milestone_eta(&projection, 10_000.0, 5_000.0),
+            MilestoneEta::Reached
+        );
+        assert_eq!(
+            milestone_eta(&projection, 10_000.0, 12_500.0),
+            MilestoneEta::On(today + Days::new(2), 3)
+        );
+        assert_eq!(
+            milestone_eta(&projection, 0.0, 1e9),
+            MilestoneEta::NotWithinHorizon
+        );
+    }
+
+    #[test]
+    fn test_default_milestones() {
+        assert_eq!(default_milestones(3_186_900), vec![4_000_000, 10_000_000]);
+        assert_eq!(default_milestones(9_500_000), vec![10_000_000]);
+        assert_eq!(default_milestones(0), vec![1, 10]);
+    }
+}
@@ -0,0 +1,267 @@
+//! `wtfpulse heatmap`: the Keyboard page's heatmap as an SVG, PNG or HTML
+//! file, for sharing outside the terminal.
+
+use crate::commands::keyboard::geometry::Geometry;
+use crate::commands::keyboard::layouts::{KEY_HEIGHT, KeyParams, KeyboardLayout};
+use crate::commands::keyboard::{KeySummary, get_color, group_thousands, key_count, summarize};
+use crate::db::Database;
+use crate::tui::period_utils::{get_display_period, parse_period_string};
+use anyhow::{Context, Result, bail};
+use ratatui::style::Color;
+use std::collections::HashMap;
+use std::path::Path;
+
+mod font;
+mod png;
+mod svg;
+
+/// Pixels per terminal cell across and down. A 1u key is four cells wide
+/// and [`KEY_HEIGHT`] rows tall, so it comes out close to square.
+const CELL_WIDTH: u32 = 14;
+const CELL_HEIGHT: u32 = 18;
+/// Space between keys, and around the image.
+const KEY_GAP: u32 = 3;
+const MARGIN: u32 = 24;
+const TITLE_HEIGHT: u32 = 44;
+const LEGEND_HEIGHT: u32 = 48;
+const LEGEND_WIDTH: u32 = 320;
+
+pub const BACKGROUND: (u8, u8, u8) = (16, 16, 24);
+pub const TEXT: (u8, u8, u8) = (230, 230, 230);
+pub const DIM_TEXT: (u8, u8, u8) = (140, 140, 150);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    Svg,
+    Png,
+    Html,
+}
+
+impl OutputFormat {
+    fn from_path(path: &Path) -> Result<Self> {
+        let ext = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(str::to_lowercase);
+        match ext.as_deref() {
+            Some("svg") => Ok(Self::Svg),
+            Some("png") => Ok(Self::Png),
+            Some("html") | Some("htm") => Ok(Self::Html),
+            _ => bail!(
+                "Can't tell the format of {}; use a .svg, .png or .html file name",
+                path.display()
+            ),
+        }
+    }
+}
+
+/// A key as drawn, in pixels.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SceneKey {
+    pub label: String,
+    pub json_key: String,
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+    pub count: u64,
+    pub color: (u8, u8, u8),
+}
+
+impl SceneKey {
+    /// Black or white, whichever reads better on the key's color.
+    pub fn text_color(&self) -> (u8, u8, u8) {
+        let (r, g, b) = self.color;
+        let luma = 0.299 * r as f64 + 0.587 * g as f64 + 0.114 * b as f64;
+        if luma > 150.0 {
+            (0, 0, 0)
+        } else {
+            (255, 255, 255)
+        }
+    }
+}
+
+/// Everything the SVG and PNG writers draw.
+#[derive(Debug, Clone)]
+pub struct Scene {
+    pub title: String,
+    pub subtitle: String,
+    pub keys: Vec<SceneKey>,
+    pub width: u32,
+    pub height: u32,
+    pub max: u64,
+    /// Most pressed keys, drawn or not.
+    pub summary: KeySummary,
+    /// Top-left corner of the legend's color bar.
+    pub legend_x: u32,
+    pub legend_y: u32,
+}
+
+impl Scene {
+    pub fn new(
+        keys: &[KeyParams],
+        data: &HashMap<String, u64>,
+        title: String,
+        subtitle: String,
+    ) -> Self {
+        let max = data.values().max().copied().unwrap_or(0);
+        let top = MARGIN + TITLE_HEIGHT;
+        let scene_keys: Vec<SceneKey> = keys
+            .iter()
+            .map(|key| {
+                let count = key_count(data, key);
+                SceneKey {
+                    label: key.label.clone(),
+                    json_key: key.json_key.clone(),
+                    x: MARGIN + key.x as u32 * CELL_WIDTH + KEY_GAP / 2,
+                    y: top + key.y as u32 * CELL_HEIGHT + KEY_GAP / 2,
+                    width: (key.width as u32 * CELL_WIDTH).saturating_sub(KEY_GAP),
+                    height: (KEY_HEIGHT as u32 * CELL_HEIGHT).saturating_sub(KEY_GAP),
+                    count,
+                    color: rgb(get_color(count, max)),
+                }
+            })
+            .collect();
+
+        let board_width = keys.iter().map(|k| k.x + k.width).max().unwrap_or(0) as u32;
+        let board_height = keys.iter().map(|k| k.y + KEY_HEIGHT).max().unwrap_or(0) as u32;
+        let width = (board_width * CELL_WIDTH).max(LEGEND_WIDTH + 200) + 2 * MARGIN;
+        let legend_y = top + board_height * CELL_HEIGHT + MARGIN;
+        Self {
+            title,
+            subtitle,
+            keys: scene_keys,
+            width,
+            height: legend_y + LEGEND_HEIGHT + MARGIN / 2,
+            max,
+            summary: summarize(data, 20),
+            legend_x: MARGIN,
+            legend_y,
+        }
+    }
+
+    /// Colors along the legend bar, `steps` samples from zero to the most
+    /// pressed key, spaced as the key colors are (logarithmically).
+    pub fn legend_colors(&self, steps: u32) -> Vec<(u8, u8, u8)> {
+        (0..steps)
+            .map(|i| {
+                let t = i as f64 / (steps - 1).max(1) as f64;
+                let count = if i == 0 {
+                    0
+                } else {
+                    (self.max as f64).powf(t).round().max(1.0) as u64
+                };
+                rgb(get_color(count, self.max))
+            })
+            .collect()
+    }
+}
+
+fn rgb(color: Color) -> (u8, u8, u8) {
+    match color {
+        Color::Rgb(r, g, b) => (r, g, b),
+        _ => (128, 128, 128),
+    }
+}
+
+/// `1.2k`-style count that fits on a key.
+pub fn compact_count(count: u64) -> String {
+    match count {
+        0..=9_999 => count.to_string(),
+        10_000..=999_999 => format!("{:.0}k", count as f64 / 1_000.0),
+        _ if count < 10_000_000 => format!("{:.1}M", count as f64 / 1_000_000.0),
+        _ => format!("{:.0}M", count as f64 / 1_000_000.0),
+    }
+}
+
+pub fn execute(layout: &str, period: &str, board: &str, out: &Path) -> Result<()> {
+    let format = OutputFormat::from_path(out)?;
+    let layout = KeyboardLayout::find(layout).with_context(|| {
+        let ids: Vec<String> = KeyboardLayout::all().iter().map(|l| l.id()).collect();
+        format!("Unknown layout {}; try one of: {}", layout, ids.join(", "))
+    })?;
+    let geometry = Geometry::find(board).with_context(|| {
+        let names: Vec<&str> = Geometry::PRESETS.iter().map(|(name, _)| *name).collect();
+        format!("Unknown board {}; try one of: {}", board, names.join(", "))
+    })?;
+    let (time_period, _) = parse_period_string(period)?;
+
+    let data = Database::new()
+        .and_then(|db| db.get_heatmap_stats(period))
+        .context("Reading key counts from the local database")?;
+    let keys = layout.get_keys(geometry);
+    let total: u64 = data.values().sum();
+    let title = format!("{} on {}", layout, geometry.name());
+    let subtitle = format!(
+        "{}: {} keys",
+        get_display_period(time_period),
+        group_thousands(total)
+    );
+    let scene = Scene::new(&keys, &data, title, subtitle);
+
+    let bytes = match format {
+        OutputFormat::Svg => svg::render(&scene).into_bytes(),
+        OutputFormat::Html => svg::render_html(&scene).into_bytes(),
+        OutputFormat::Png => png::render(&scene)?,
+    };
+    std::fs::write(out, bytes).with_context(|| format!("Failed to write {}", out.display()))?;
+    println!(
+        "Wrote {} ({}x{}, {} keys, most pressed {}).",
+        out.display(),
+        scene.width,
+        scene.height,
+        group_thousands(total),
+        group_thousands(scene.max)
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    pub(super) fn sample_scene() -> Scene {
+        let keys = KeyboardLayout::Qwerty.get_keys(Geometry::default());
+        let data: HashMap<String, u64> = [("E", 12_345), ("SPACE", 40_000), ("SHIFT", 700)]
+            .into_iter()
+            .map(|(k, c)| (k.to_string(), c))
+            .collect();
+        Scene::new(&keys, &data, "US QWERTY on ANSI".into(), "All Time".into())
+    }
+
+    #[test]
+    fn test_scene_places_and_colors_keys() {
+        let scene = sample_scene();
+        let find = |name: &str| scene.keys.iter().find(|k| k.json_key == name).unwrap();
+
+        let q = find("Q");
+        assert_eq!(q.x, MARGIN + 6 * CELL_WIDTH + 1);
+        assert_eq!(q.width, 4 * CELL_WIDTH - KEY_GAP);
+        assert_eq!(q.count, 0);
+        assert_eq!(find("SPACE").color, (255, 50, 50));
+        // Sideless Shift counts light both Shift keys.
+        assert_eq!(find("LEFTSHIFT").count, 700);
+        assert_eq!(find("RIGHTSHIFT").count, 700);
+
+        let right = scene.keys.iter().map(|k| k.x + k.width).max().unwrap();
+        let bottom = scene.keys.iter().map(|k| k.y + k.height).max().unwrap();
+        assert!(right + MARGIN <= scene.width);
+        assert!(bottom < scene.legend_y);
+
+        let legend = scene.legend_colors(5);
+        assert_eq!(legend[0], (20, 20, 50));
+        assert_eq!(legend[4], (255, 50, 50));
+    }
+
+    #[test]
+    fn test_formats_and_counts() {
+        assert_eq!(
+            OutputFormat::from_path(Path::new("heat.PNG")).unwrap(),
+            OutputFormat::Png
+        );
+        assert!(OutputFormat::from_path(Path::new("heat.jpg")).is_err());
+        assert_eq!(compact_count(999), "999");
+        assert_eq!(compact_count(12_345), "12k");
+        assert_eq!(compact_count(1_234_567), "1.2M");
+    }
+}
@@ -0,0 +1,165 @@
+use super::{BACKGROUND, DIM_TEXT, LEGEND_WIDTH, MARGIN, Scene, TEXT, compact_count, font};
+use anyhow::{Context, Result};
+
+type Rgb = (u8, u8, u8);
+
+/// An RGB image being drawn.
+struct Canvas {
+    width: u32,
+    height: u32,
+    pixels: Vec<u8>,
+}
+
+impl Canvas {
+    fn new(width: u32, height: u32, background: Rgb) -> Self {
+        let pixels = [background.0, background.1, background.2].repeat((width * height) as usize);
+        Self {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    fn set(&mut self, x: u32, y: u32, (r, g, b): Rgb) {
+        if x < self.width && y < self.height {
+            let i = ((y * self.width + x) * 3) as usize;
+            self.pixels[i..i + 3].copy_from_slice(&[r, g, b]);
+        }
+    }
+
+    fn fill_rect(&mut self, x: u32, y: u32, width: u32, height: u32, color: Rgb) {
+        for py in y..y + height {
+            for px in x..x + width {
+                self.set(px, py, color);
+            }
+        }
+    }
+
+    /// A key: a rectangle with its corner pixels left out.
+    fn fill_key(&mut self, x: u32, y: u32, width: u32, height: u32, color: Rgb) {
+        if width == 0 {
+            return;
+        }
+        self.fill_rect(x + 1, y, width.saturating_sub(2), height, color);
+        self.fill_rect(x, y + 1, 1, height.saturating_sub(2), color);
+        self.fill_rect(x + width - 1, y + 1, 1, height.saturating_sub(2), color);
+    }
+
+    fn text(&mut self, x: u32, y: u32, text: &str, scale: u32, color: Rgb) {
+        for (i, c) in text.chars().enumerate() {
+            let left = x + i as u32 * font::ADVANCE * scale;
+            for (col, bits) in font::glyph(c).iter().enumerate() {
+                for row in 0..font::HEIGHT {
+                    if bits & (1 << row) != 0 {
+                        let px = left + col as u32 * scale;
+                        self.fill_rect(px, y + row * scale, scale, scale, color);
+                    }
+                }
+            }
+        }
+    }
+
+    fn encode(&self) -> Result<Vec<u8>> {
+        let mut bytes = Vec::new();
+        let mut encoder = ::png::Encoder::new(&mut bytes, self.width, self.height);
+        encoder.set_color(::png::ColorType::Rgb);
+        encoder.set_depth(::png::BitDepth::Eight);
+        let mut writer = encoder.write_header().context("Encoding PNG")?;
+        writer
+            .write_image_data(&self.pixels)
+            .context("Encoding PNG")?;
+        writer.finish().context("Encoding PNG")?;
+        Ok(bytes)
+    }
+}
+
+/// The largest scale (2 or 1) at which `text` fits in `width` pixels,
+/// cutting the text short if it doesn't fit even at 1.
+fn fit(text: &str, width: u32) -> (String, u32) {
+    if font::width(text, 2) <= width {
+        return (text.to_string(), 2);
+    }
+    let fits = ((width + 1) / font::ADVANCE) as usize;
+    (text.chars().take(fits).collect(), 1)
+}
+
+/// The scene as a PNG image. Text is drawn with a built-in ASCII font.
+pub fn render(scene: &Scene) -> Result<Vec<u8>> {
+    let mut canvas = Canvas::new(scene.width, scene.height, BACKGROUND);
+    canvas.text(MARGIN, MARGIN, &scene.title, 2, TEXT);
+    canvas.text(MARGIN, MARGIN + 24, &scene.subtitle, 1, DIM_TEXT);
+
+    for key in &scene.keys {
+        canvas.fill_key(key.x, key.y, key.width, key.height, key.color);
+        let fg = key.text_color();
+        let (label, scale) = fit(&key.label, key.width.saturating_sub(4));
+        let label_x = key.x + (key.width - font::width(&label, scale)) / 2;
+        let label_y = key.y + key.height / 2 - font::HEIGHT * scale + 2;
+        canvas.text(label_x, label_y, &label, scale, fg);
+        if key.count > 0 {
+            let count = compact_count(key.count);
+            let count_x = key.x + key.width.saturating_sub(font::width(&count, 1)) / 2;
+            canvas.text(count_x, key.y + key.height - 11, &count, 1, fg);
+        }
+    }
+
+    canvas.text(
+        scene.legend_x,
+        scene.legend_y,
+        "Presses per key (log scale)",
+        1,
+        DIM_TEXT,
+    );
+    let bar_y = scene.legend_y + 16;
+    for (i, color) in scene.legend_colors(LEGEND_WIDTH).into_iter().enumerate() {
+        canvas.fill_rect(scene.legend_x + i as u32, bar_y, 1, 14, color);
+    }
+    canvas.text(scene.legend_x, bar_y + 20, "0", 1, DIM_TEXT);
+    let max = crate::commands::keyboard::group_thousands(scene.max);
+    let max_x = scene.legend_x + LEGEND_WIDTH - font::width(&max, 1);
+    canvas.text(max_x, bar_y + 20, &max, 1, DIM_TEXT);
+
+    canvas.encode()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_png_decodes_with_key_colors() {
+        let scene = super::super::tests::sample_scene();
+        let bytes = render(&scene).unwrap();
+
+        let mut reader = ::png::Decoder::new(Cursor::new(bytes)).read_info().unwrap();
+        let mut pixels = vec![0; reader.output_buffer_size().unwrap()];
+        let info = reader.next_frame(&mut pixels).unwrap();
+        assert_eq!((info.width, info.height), (scene.width, scene.height));
+        assert_eq!(info.color_type, ::png::ColorType::Rgb);
+
+        let pixel = |x: u32, y: u32| {
+            let i = ((y * info.width + x) * 3) as usize;
+            (pixels[i], pixels[i + 1], pixels[i + 2])
+        };
+        let space = scene.keys.iter().find(|k| k.json_key == "SPACE").unwrap();
+        assert_eq!(pixel(space.x + 2, space.y + 2), (255, 50, 50));
+        assert_eq!(pixel(0, 0), BACKGROUND);
+    }
+
+    #[test]
+    fn test_zero_width_keys_draw_nothing() {
+        let keys = [crate::commands::keyboard::layouts::KeyParams::new(
+            "A", "A", 0, 0, 0,
+        )];
+        let scene = Scene::new(&keys, &Default::default(), "T".into(), "S".into());
+        assert_eq!(scene.keys[0].width, 0);
+        render(&scene).unwrap();
+    }
+
+    #[test]
+    fn test_labels_shrink_to_fit() {
+        assert_eq!(fit("Q", 40), ("Q".to_string(), 2));
+        assert_eq!(fit("Backspace", 40), ("Backsp".to_string(), 1));
+    }
+}
@@ -0,0 +1,193 @@
+use super::{BACKGROUND, DIM_TEXT, Scene, TEXT, compact_count};
+use std::fmt::Write;
+
+fn hex((r, g, b): (u8, u8, u8)) -> String {
+    format!("#{:02x}{:02x}{:02x}", r, g, b)
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// The scene as a standalone SVG document.
+pub fn render(scene: &Scene) -> String {
+    let mut svg = String::new();
+    // Writing to a String can't fail.
+    let _ = writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}" font-family="DejaVu Sans Mono, Menlo, Consolas, monospace">"#,
+        w = scene.width,
+        h = scene.height
+    );
+    let _ = writeln!(
+        svg,
+        r#"<rect width="100%" height="100%" fill="{}"/>"#,
+        hex(BACKGROUND)
+    );
+    let _ = writeln!(
+        svg,
+        r#"<text x="{}" y="{}" font-size="18" font-weight="bold" fill="{}">{}</text>"#,
+        super::MARGIN,
+        super::MARGIN + 14,
+        hex(TEXT),
+        escape(&scene.title)
+    );
+    let _ = writeln!(
+        svg,
+        r#"<text x="{}" y="{}" font-size="13" fill="{}">{}</text>"#,
+        super::MARGIN,
+        super::MARGIN + 32,
+        hex(DIM_TEXT),
+        escape(&scene.subtitle)
+    );
+
+    for key in &scene.keys {
+        let fg = hex(key.text_color());
+        let _ = writeln!(
+            svg,
+            r#"<g><title>{} {}</title><rect x="{}" y="{}" width="{}" height="{}" rx="4" fill="{}"/>"#,
+            escape(&key.json_key),
+            key.count,
+            key.x,
+            key.y,
+            key.width,
+            key.height,
+            hex(key.color)
+        );
+        let cx = key.x + key.width / 2;
+        let _ = writeln!(
+            svg,
+            r#"<text x="{}" y="{}" font-size="12" text-anchor="middle" fill="{}">{}</text>"#,
+            cx,
+            key.y + key.height / 2 - 2,
+            fg,
+            escape(&key.label)
+        );
+        if key.count > 0 {
+            let _ = writeln!(
+                svg,
+                r#"<text x="{}" y="{}" font-size="10" text-anchor="middle" fill="{}" opacity="0.85">{}</text>"#,
+                cx,
+                key.y + key.height - 7,
+                fg,
+                compact_count(key.count)
+            );
+        }
+        svg.push_str("</g>\n");
+    }
+
+    // Legend: the gradient with zero and the maximum at its ends.
+    svg.push_str("<defs><linearGradient id=\"legend\">");
+    let stops = scene.legend_colors(11);
+    for (i, color) in stops.iter().enumerate() {
+        let _ = write!(
+            svg,
+            r#"<stop offset="{}%" stop-color="{}"/>"#,
+            i * 100 / (stops.len() - 1),
+            hex(*color)
+        );
+    }
+    svg.push_str("</linearGradient></defs>\n");
+    let bar_y = scene.legend_y + 16;
+    let _ = writeln!(
+        svg,
+        r#"<text x="{}" y="{}" font-size="12" fill="{}">Presses per key (log scale)</text>"#,
+        scene.legend_x,
+        scene.legend_y + 10,
+        hex(DIM_TEXT)
+    );
+    let _ = writeln!(
+        svg,
+        r#"<rect x="{}" y="{}" width="{}" height="14" rx="2" fill="url(#legend)"/>"#,
+        scene.legend_x,
+        bar_y,
+        super::LEGEND_WIDTH
+    );
+    let _ = writeln!(
+        svg,
+        r#"<text x="{}" y="{}" font-size="12" fill="{}">0</text>"#,
+        scene.legend_x,
+        bar_y + 28,
+        hex(DIM_TEXT)
+    );
+    let _ = writeln!(
+        svg,
+        r#"<text x="{}" y="{}" font-size="12" text-anchor="end" fill="{}">{}</text>"#,
+        scene.legend_x + super::LEGEND_WIDTH,
+        bar_y + 28,
+        hex(DIM_TEXT),
+        crate::commands::keyboard::group_thousands(scene.max)
+    );
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// An HTML page with the SVG inline and a table of the most pressed keys.
+pub fn render_html(scene: &Scene) -> String {
+    let summary = &scene.summary;
+    let mut rows = String::new();
+    for (key, count) in &summary.top {
+        let _ = writeln!(
+            rows,
+            "<tr><td>{}</td><td>{}</td><td>{:.1}%</td></tr>",
+            escape(key),
+            crate::commands::keyboard::group_thousands(*count),
+            *count as f64 / summary.total.max(1) as f64 * 100.0
+        );
+    }
+
+    format!(
+        r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>{title}</title>
+<style>
+body {{ background: {bg}; color: {fg}; font-family: monospace; margin: 24px; }}
+table {{ border-collapse: collapse; margin-top: 16px; }}
+td, th {{ padding: 2px 12px; text-align: right; }}
+td:first-child, th:first-child {{ text-align: left; }}
+</style>
+</head>
+<body>
+{svg}<table>
+<tr><th>Key</th><th>Presses</th><th>Share</th></tr>
+{rows}</table>
+<p>{subtitle}.</p>
+</body>
+</html>
+"#,
+        title = escape(&scene.title),
+        bg = hex(BACKGROUND),
+        fg = hex(TEXT),
+        svg = render(scene),
+        rows = rows,
+        subtitle = escape(&scene.subtitle),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_svg_has_keys_counts_and_legend() {
+        let scene = super::super::tests::sample_scene();
+        let svg = render(&scene);
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.trim_end().ends_with("</svg>"));
+        assert!(svg.contains(">12k</text>"));
+        assert!(svg.contains("<title>SPACE 40000</title>"));
+        assert!(svg.contains("fill=\"#ff3232\""));
+        assert!(svg.contains(">40,000</text>"));
+        // Labels are escaped.
+        assert!(!svg.contains("><</text>"));
+
+        let html = render_html(&scene);
+        assert!(html.contains("<tr><td>SPACE</td><td>40,000</td><td>75.4%</td></tr>"));
+        assert!(html.contains("<tr><td>SHIFT</td><td>700</td>"));
+    }
+}
@@ -0,0 +1,365 @@
+//! How key counts load the fingers of a layout.
+//!
+//! Counts are per key name as WhatPulse records them (the character typed),
+//! so the same counts can be placed on any layout: each name lands on
+//! whichever key of that layout carries it. Only main-block keys with a
+//! [`Touch`] are counted. Row jumps, same-finger use and hand alternation
+//! are estimated from single-key frequencies, as if consecutive keys were
+//! independent.
+
+use super::geometry::{Finger, Geometry, Hand, Row, Touch};
+use super::layouts::{KEY_HEIGHT, KeyParams, KeyboardLayout};
+use std::collections::{HashMap, HashSet};
+use strum::IntoEnumIterator;
+
+/// Relative cost of a press by row; home keys are the cheapest.
+fn row_effort(touch: &Touch) -> f64 {
+    match touch.row {
+        Row::Home if touch.home => 1.0,
+        Row::Home => 1.3,
+        Row::Top => 1.3,
+        Row::Bottom => 1.5,
+        Row::Number => 2.0,
+        Row::Modifier => 1.0,
+    }
+}
+
+fn finger_effort(finger: Finger) -> f64 {
+    match finger {
+        Finger::LeftPinky | Finger::RightPinky => 1.5,
+        Finger::LeftRing | Finger::RightRing => 1.2,
+        _ => 1.0,
+    }
+}
+
+/// Millimetres per key unit on a standard board.
+pub const MM_PER_UNIT: f64 = 19.05;
+
+/// A key's touch assignment, centre in key units, and its count.
+#[derive(Debug, Clone)]
+pub struct Placed {
+    pub touch: Touch,
+    pub x: f64,
+    pub y: f64,
+    pub count: u64,
+}
+
+/// Touch assignment of each main-block key name in `keys`.
+pub fn touches(keys: &[KeyParams]) -> HashMap<String, Touch> {
+    let mut fallback = None;
+    let mut touches = HashMap::new();
+    for key in keys {
+        if touches.contains_key(&key.json_key) {
+            continue;
+        }
+        // Fixed boards have no touch data; assume a standard board.
+        let touch = key.touch.or_else(|| {
+            fallback
+                .get_or_insert_with(|| KeyboardLayout::Qwerty.get_keys(Geometry::default()))
+                .iter()
+                .find(|k| k.json_key == key.json_key)
+                .and_then(|k| k.touch)
+        });
+        if let Some(touch) = touch {
+            touches.insert(key.json_key.clone(), touch);
+        }
+    }
+    touches
+}
+
+/// Main-block keys of `keys` with their counts, one per key name. Keys
+/// drawn in two cells (ISO Enter, split space bars) count once.
+pub fn place(keys: &[KeyParams], counts: &HashMap<String, u64>) -> Vec<Placed> {
+    let touches = touches(keys);
+    let mut seen = HashSet::new();
+    let mut placed = Vec::new();
+    for key in keys {
+        if !seen.insert(key.json_key.as_str()) {
+            continue;
+        }
+        let Some(touch) = touches.get(&key.json_key) else {
+            continue;
+        };
+        placed.push(Placed {
+            touch: *touch,
+            x: (key.x as f64 + key.width as f64 / 2.0) / CELLS_PER_UNIT,
+            y: key.y as f64 / KEY_HEIGHT as f64,
+            count: presses(counts, &key.json_key),
+        });
+    }
+    placed
+}
+
+/// Presses of `name`. Counts from Qt key codes have no side (`SHIFT`);
+/// those go to the left key only, so they aren't counted twice.
+fn presses(counts: &HashMap<String, u64>, name: &str) -> u64 {
+    counts
+        .get(name)
+        .or_else(|| {
+            crate::key_mapping::sideless(name)
+                .filter(|_| name.starts_with("LEFT"))
+                .and_then(|k| counts.get(k))
+        })
+        .copied()
+        .unwrap_or(0)
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LoadReport {
+    /// Presses on main-block keys.
+    pub total: u64,
+    /// Per finger, in [`Finger`] order.
+    pub fingers: Vec<(Finger, u64)>,
+    /// Share of non-thumb presses made by the left hand.
+    pub left_hand: f64,
+    /// Share of non-thumb presses on the home row.
+    pub home_row: f64,
+    /// Estimated share of consecutive same-hand presses that skip over the
+    /// home row (top to bottom, number row to home or bottom).
+    pub row_jumps: f64,
+    /// Average effort per press; 1.0 would be every press on a home key
+    /// with an index or middle finger.
+    pub effort: f64,
+    /// Average distance a finger moves from its home key and back, in key
+    /// units per press.
+    pub travel: f64,
+    /// Estimated share of consecutive non-thumb presses by the same finger
+    /// on different keys.
+    pub same_finger: f64,
+    /// Estimated share of consecutive non-thumb presses that switch hands.
+    pub alternation: f64,
+}
+
+impl LoadReport {
+    pub fn finger_share(&self, finger: Finger) -> f64 {
+        let count = self
+            .fingers
+            .iter()
+            .find(|(f, _)| *f == finger)
+            .map_or(0, |(_, c)| *c);
+        share(count, self.total)
+    }
+}
+
+/// Cells per key unit across, as drawn by [`Geometry`].
+const CELLS_PER_UNIT: f64 = 4.0;
+
+fn share(part: u64, total: u64) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        part as f64 / total as f64
+    }
+}
+
+pub fn analyze(keys: &[KeyParams], counts: &HashMap<String, u64>) -> LoadReport {
+    let placed = place(keys, counts);
+    let total: u64 = placed.iter().map(|p| p.count).sum();
+
+    let fingers = Finger::iter()
+        .map(|finger| {
+            let count = placed
+                .iter()
+                .filter(|p| p.touch.finger == finger)
+                .map(|p| p.count)
+                .sum();
+            (finger, count)
+        })
+        .collect();
+
+    let handed: Vec<&Placed> = placed
+        .iter()
+        .filter(|p| p.touch.finger.hand().is_some())
+        .collect();
+    let handed_total: u64 = handed.iter().map(|p| p.count).sum();
+    let left: u64 = handed
+        .iter()
+        .filter(|p| p.touch.finger.hand() == Some(Hand::Left))
+        .map(|p| p.count)
+        .sum();
+    let home: u64 = handed
+        .iter()
+        .filter(|p| p.touch.row == Row::Home)
+        .map(|p| p.count)
+        .sum();
+
+    // Character rows only, as indices 0 (number) to 3 (bottom).
+    let row_index = |row: Row| match row {
+        Row::Number => Some(0i32),
+        Row::Top => Some(1),
+        Row::Home => Some(2),
+        Row::Bottom => Some(3),
+        Row::Modifier => None,
+    };
+    let mut row_jumps = 0.0;
+    if handed_total > 0 {
+        for a in &handed {
+            for b in &handed {
+                let (Some(ra), Some(rb)) = (row_index(a.touch.row), row_index(b.touch.row)) else {
+                    continue;
+                };
+                if a.touch.finger.hand() == b.touch.finger.hand() && (ra - rb).abs() >= 2 {
+                    row_jumps += share(a.count, handed_total) * share(b.count, handed_total);
+                }
+            }
+        }
+    }
+
+    // Pairs of different keys typed by the same finger.
+    let mut same_finger = 0.0;
+    for finger in Finger::iter().filter(|f| f.hand().is_some()) {
+        let shares: Vec<f64> = handed
+            .iter()
+            .filter(|p| p.touch.finger == finger)
+            .map(|p| share(p.count, handed_total))
+            .collect();
+        let finger_share: f64 = shares.iter().sum();
+        same_finger += finger_share * finger_share - shares.iter().map(|s| s * s).sum::<f64>();
+    }
+    let left_hand = share(left, handed_total);
+    let alternation = if handed_total > 0 {
+        2.0 * left_hand * (1.0 - left_hand)
+    } else {
+        0.0
+    };
+
+    let travel = if total == 0 {
+        0.0
+    } else {
+        let home_of = |finger: Finger| {
+            placed
+                .iter()
+                .find(|p| p.touch.finger == finger && p.touch.home)
+                .map(|p| (p.x, p.y))
+        };
+        let homes: HashMap<Finger, (f64, f64)> = Finger::iter()
+            .filter_map(|f| home_of(f).map(|home| (f, home)))
+            .collect();
+        placed
+            .iter()
+            .filter_map(|p| {
+                let (hx, hy) = homes.get(&p.touch.finger)?;
+                Some(p.count as f64 * 2.0 * (p.x - hx).hypot(p.y - hy))
+            })
+            .sum::<f64>()
+            / total as f64
+    };
+
+    let effort = if total == 0 {
+        0.0
+    } else {
+        placed
+            .iter()
+            .map(|p| p.count as f64 * row_effort(&p.touch) * finger_effort(p.touch.finger))
+            .sum::<f64>()
+            / total as f64
+    };
+
+    LoadReport {
+        total,
+        fingers,
+        left_hand,
+        home_row: share(home, handed_total),
+        row_jumps,
+        effort,
+        travel,
+        same_finger,
+        alternation,
+    }
+}
+
+/// Counts for a candidate layout, matched by character: each key of the
+/// current layout hands its count to the candidate's key with the same
+/// character. Returns the counts and the total left without a key.
+pub fn remap(
+    counts: &HashMap<String, u64>,
+    current: &[KeyParams],
+    candidate: &[KeyParams],
+) -> (HashMap<String, u64>, u64) {
+    let targets: HashSet<&str> = candidate.iter().map(|k| k.json_key.as_str()).collect();
+    let mut remapped = HashMap::new();
+    let mut unmapped = 0;
+    let mut seen = HashSet::new();
+    for key in current {
+        if !seen.insert(key.json_key.as_str()) {
+            continue;
+        }
+        let count = presses(counts, &key.json_key);
+        if targets.contains(key.json_key.as_str()) {
+            remapped.insert(key.json_key.clone(), count);
+        } else {
+            unmapped += count;
+        }
+    }
+    (remapped, unmapped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn counts(pairs: &[(&str, u64)]) -> HashMap<String, u64> {
+        pairs.iter().map(|(k, c)| (k.to_string(), *c)).collect()
+    }
+
+    #[test]
+    fn test_same_counts_on_two_layouts() {
+        let data = counts(&[("E", 60), ("T", 30), ("SPACE", 10), ("F13", 99)]);
+        let qwerty = analyze(&KeyboardLayout::Qwerty.get_keys(Geometry::default()), &data);
+        let colemak = analyze(
+            &KeyboardLayout::Colemak.get_keys(Geometry::default()),
+            &data,
+        );
+
+        assert_eq!(qwerty.total, 100);
+        assert!((qwerty.finger_share(Finger::LeftMiddle) - 0.6).abs() < 1e-9);
+        assert!((qwerty.finger_share(Finger::Thumb) - 0.1).abs() < 1e-9);
+        assert_eq!(qwerty.left_hand, 1.0);
+        assert_eq!(qwerty.home_row, 0.0);
+
+        // Colemak has E under the right middle finger and T on the home row.
+        assert!((colemak.finger_share(Finger::RightMiddle) - 0.6).abs() < 1e-9);
+        assert!((colemak.left_hand - 1.0 / 3.0).abs() < 1e-9);
+        assert_eq!(colemak.home_row, 1.0);
+        assert!(colemak.effort < qwerty.effort);
+    }
+
+    #[test]
+    fn test_iso_key_leaves_comma_alone() {
+        let iso = Geometry::PRESETS[1].1;
+        assert_eq!(Geometry::PRESETS[1].0, "ISO");
+        for layout in [KeyboardLayout::Qwerty, KeyboardLayout::Qwertz] {
+            let report = analyze(&layout.get_keys(iso), &counts(&[("COMMA", 100)]));
+            assert_eq!(report.finger_share(Finger::RightMiddle), 1.0, "{layout}");
+            assert_eq!(report.finger_share(Finger::LeftPinky), 0.0, "{layout}");
+        }
+    }
+
+    #[test]
+    fn test_sideless_modifiers_count_once() {
+        let keys = KeyboardLayout::Qwerty.get_keys(Geometry::default());
+        let report = analyze(&keys, &counts(&[("E", 60), ("SHIFT", 40)]));
+        assert_eq!(report.total, 100);
+        assert!((report.finger_share(Finger::LeftPinky) - 0.4).abs() < 1e-9);
+        assert_eq!(report.finger_share(Finger::RightPinky), 0.0);
+
+        // Sided counts are used as they are.
+        let sided = counts(&[("LEFTSHIFT", 10), ("RIGHTSHIFT", 30), ("SHIFT", 99)]);
+        assert_eq!(analyze(&keys, &sided).total, 40);
+
+        let (remapped, _) = remap(&counts(&[("SHIFT", 40)]), &keys, &keys);
+        assert_eq!(remapped["LEFTSHIFT"], 40);
+        assert_eq!(remapped["RIGHTSHIFT"], 0);
+    }
+
+    #[test]
+    fn test_row_jumps_need_the_same_hand() {
+        let keys = KeyboardLayout::Qwerty.get_keys(Geometry::default());
+        // E (top) and C (bottom) are both left hand: half of all pairs jump.
+        let jumps = analyze(&keys, &counts(&[("E", 50), ("C", 50)]));
+        assert!((jumps.row_jumps - 0.5).abs() < 1e-9);
+        // E and M alternate hands; no jumps.
+        let none = analyze(&keys, &counts(&[("E", 50), ("M", 50)]));
+        assert_eq!(none.row_jumps, 0.0);
+    }
+}
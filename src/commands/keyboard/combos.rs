@@ -0,0 +1,262 @@
+//! Key sequences and shortcuts seen this session.
+//!
+//! The database and the realtime feed only keep per-key counts, so order
+//! has to be recovered as keys arrive. Keys typed into the TUI come one at
+//! a time with their modifiers. The realtime feed sends running totals
+//! every couple of seconds; an update that adds a single key (Shift aside)
+//! extends the sequence, and one that adds a single key under Ctrl, Alt or
+//! Win is a shortcut. Anything busier can't be ordered and ends the
+//! sequence, so feed bigrams come from slow or sparse typing.
+
+use super::MODIFIER_KEYS;
+use super::analysis;
+use super::geometry::Finger;
+use super::layouts::KeyParams;
+use std::collections::HashMap;
+
+/// Modifiers that make a shortcut, in display order.
+const SHORTCUT_MODIFIERS: [&str; 4] = ["Ctrl", "Alt", "Win", "Shift"];
+
+/// Display name of a modifier key, `None` for keys that change what is
+/// typed rather than make a shortcut (AltGr, Caps Lock).
+fn modifier_name(key: &str) -> Option<&'static str> {
+    match key {
+        "CONTROL" | "LEFTCONTROL" | "RIGHTCONTROL" => Some("Ctrl"),
+        "ALT" | "LEFTALT" | "RIGHTALT" => Some("Alt"),
+        "META" | "LEFTWINDOWS" | "RIGHTWINDOWS" => Some("Win"),
+        "SHIFT" | "LEFTSHIFT" | "RIGHTSHIFT" => Some("Shift"),
+        _ => None,
+    }
+}
+
+/// `Ctrl+Shift+T` from modifier display names and a key name.
+fn shortcut_name(modifiers: &[&str], key: &str) -> String {
+    let mut parts: Vec<&str> = SHORTCUT_MODIFIERS
+        .iter()
+        .copied()
+        .filter(|m| modifiers.contains(m))
+        .collect();
+    parts.push(key);
+    parts.join("+")
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ComboStats {
+    pub bigrams: HashMap<(String, String), u64>,
+    pub shortcuts: HashMap<String, u64>,
+    /// Presses placed in order, from the feed and from the TUI.
+    pub ordered: u64,
+    /// Feed presses in updates too busy to order.
+    pub unordered: u64,
+    last: Option<String>,
+    /// Running totals from the last feed update.
+    totals: Option<HashMap<String, u64>>,
+    /// Keys typed into the TUI since the last feed update, which the feed
+    /// will report again. Modifiers go by display name, either side.
+    echo: HashMap<String, u64>,
+}
+
+impl ComboStats {
+    /// A key typed into the TUI with the names of the modifiers held.
+    pub fn record_key(&mut self, key: &str, modifiers: &[&str]) {
+        if self.totals.is_some() {
+            *self.echo.entry(key.to_string()).or_insert(0) += 1;
+            for modifier in modifiers {
+                *self.echo.entry(modifier.to_string()).or_insert(0) += 1;
+            }
+        }
+        self.press(key, modifiers);
+    }
+
+    /// Running key totals from the realtime feed.
+    pub fn record_totals(&mut self, totals: &HashMap<String, u64>) {
+        let Some(previous) = self.totals.replace(totals.clone()) else {
+            return;
+        };
+        let mut echo = std::mem::take(&mut self.echo);
+
+        let mut modifiers = Vec::new();
+        let mut presses: Vec<(&str, u64)> = Vec::new();
+        for (key, count) in totals {
+            // Totals restart after a pulse; a key that went down can't be
+            // told apart from new presses.
+            let Some(delta) = count.checked_sub(previous.get(key).copied().unwrap_or(0)) else {
+                self.last = None;
+                return;
+            };
+            let name = modifier_name(key);
+            let echoed = echo.get_mut(name.unwrap_or(key)).map_or(0, |echoed| {
+                let seen = (*echoed).min(delta);
+                *echoed -= seen;
+                seen
+            });
+            let delta = delta - echoed;
+            if delta == 0 {
+                continue;
+            }
+            match name {
+                Some(name) => modifiers.push(name),
+                None if MODIFIER_KEYS.contains(&key.as_str()) => {}
+                None => presses.push((key, delta)),
+            }
+        }
+
+        match presses.as_slice() {
+            [] => {}
+            [(key, 1)] => {
+                let key = key.to_string();
+                self.press(&key, &modifiers);
+            }
+            _ => {
+                self.unordered += presses.iter().map(|(_, n)| n).sum::<u64>();
+                self.last = None;
+            }
+        }
+    }
+
+    fn press(&mut self, key: &str, modifiers: &[&str]) {
+        self.ordered += 1;
+        if modifiers.iter().any(|m| *m != "Shift") {
+            *self
+                .shortcuts
+                .entry(shortcut_name(modifiers, key))
+                .or_insert(0) += 1;
+            self.last = None;
+            return;
+        }
+        if let Some(last) = self.last.replace(key.to_string()) {
+            *self.bigrams.entry((last, key.to_string())).or_insert(0) += 1;
+        }
+    }
+
+    pub fn total_bigrams(&self) -> u64 {
+        self.bigrams.values().sum()
+    }
+
+    /// Most typed bigrams, most first.
+    pub fn top_bigrams(&self, n: usize) -> Vec<(&(String, String), u64)> {
+        top(self.bigrams.iter().map(|(k, v)| (k, *v)), n)
+    }
+
+    /// Bigrams of two different keys typed by the same finger of one hand
+    /// on `keys`, most first.
+    pub fn same_finger(
+        &self,
+        keys: &[KeyParams],
+        n: usize,
+    ) -> Vec<(&(String, String), Finger, u64)> {
+        let touches = analysis::touches(keys);
+        let mut found: Vec<_> = self
+            .bigrams
+            .iter()
+            .filter(|((a, b), _)| a != b)
+            .filter_map(|(pair, count)| {
+                let first = touches.get(&pair.0)?.finger;
+                let second = touches.get(&pair.1)?.finger;
+                (first == second && first.hand().is_some()).then_some((pair, first, *count))
+            })
+            .collect();
+        found.sort_by(|a, b| b.2.cmp(&a.2).then_with(|| a.0.cmp(b.0)));
+        found.truncate(n);
+        found
+    }
+
+    /// Most used shortcuts, most first.
+    pub fn top_shortcuts(&self, n: usize) -> Vec<(&String, u64)> {
+        top(self.shortcuts.iter().map(|(k, v)| (k, *v)), n)
+    }
+}
+
+fn top<K: Ord>(items: impl Iterator<Item = (K, u64)>, n: usize) -> Vec<(K, u64)> {
+    let mut items: Vec<(K, u64)> = items.collect();
+    items.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    items.truncate(n);
+    items
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::keyboard::geometry::Geometry;
+    use crate::commands::keyboard::layouts::KeyboardLayout;
+
+    fn totals(pairs: &[(&str, u64)]) -> HashMap<String, u64> {
+        pairs.iter().map(|(k, c)| (k.to_string(), *c)).collect()
+    }
+
+    fn pair(a: &str, b: &str) -> (String, String) {
+        (a.to_string(), b.to_string())
+    }
+
+    #[test]
+    fn test_feed_updates_with_one_key_are_ordered() {
+        let mut combos = ComboStats::default();
+        combos.record_totals(&totals(&[("E", 10)]));
+        combos.record_totals(&totals(&[("E", 11)]));
+        combos.record_totals(&totals(&[("E", 11), ("D", 1), ("LEFTSHIFT", 1)]));
+        // Ctrl+C breaks the sequence.
+        combos.record_totals(&totals(&[
+            ("E", 11),
+            ("D", 1),
+            ("LEFTSHIFT", 1),
+            ("C", 1),
+            ("LEFTCONTROL", 1),
+        ]));
+        combos.record_totals(&totals(&[
+            ("E", 12),
+            ("D", 1),
+            ("LEFTSHIFT", 1),
+            ("C", 1),
+            ("LEFTCONTROL", 1),
+        ]));
+        assert_eq!(combos.bigrams.get(&pair("E", "D")), Some(&1));
+        assert_eq!(combos.total_bigrams(), 1);
+        assert_eq!(combos.shortcuts.get("Ctrl+C"), Some(&1));
+
+        // Too busy to order, then a reset after a pulse.
+        combos.record_totals(&totals(&[("E", 14), ("D", 2)]));
+        combos.record_totals(&totals(&[("E", 1)]));
+        combos.record_totals(&totals(&[("E", 2)]));
+        assert_eq!(combos.unordered, 3);
+        assert_eq!(combos.total_bigrams(), 1);
+    }
+
+    #[test]
+    fn test_tui_keys_are_not_counted_twice() {
+        let mut combos = ComboStats::default();
+        combos.record_totals(&totals(&[]));
+        combos.record_key("D", &[]);
+        combos.record_key("E", &[]);
+        combos.record_key("S", &["Ctrl", "Shift"]);
+        combos.record_totals(&totals(&[
+            ("D", 1),
+            ("E", 1),
+            ("S", 1),
+            ("LEFTCONTROL", 1),
+            ("LEFTSHIFT", 1),
+        ]));
+        assert_eq!(combos.bigrams.get(&pair("D", "E")), Some(&1));
+        assert_eq!(combos.shortcuts.get("Ctrl+Shift+S"), Some(&1));
+        assert_eq!(combos.ordered, 3);
+        assert_eq!(combos.unordered, 0);
+
+        // D and E are both the left middle finger on QWERTY; E then R
+        // switches fingers.
+        combos.record_key("E", &[]);
+        combos.record_key("D", &[]);
+        combos.record_key("R", &[]);
+        let keys = KeyboardLayout::Qwerty.get_keys(Geometry::default());
+        let same: Vec<_> = combos
+            .same_finger(&keys, 5)
+            .into_iter()
+            .map(|(pair, finger, _)| (pair.clone(), finger))
+            .collect();
+        assert_eq!(
+            same,
+            [
+                (pair("D", "E"), Finger::LeftMiddle),
+                (pair("E", "D"), Finger::LeftMiddle)
+            ]
+        );
+    }
+}
@@ -0,0 +1,705 @@
+//! Physical keyboards that a logical layout is drawn on.
+//!
+//! A layout `map` only says which character sits in each position; the
+//! [`Geometry`] decides where those positions are and adds the fixed keys
+//! around them. Maps are read by length:
+//!
+//! - 47 characters: number row (13), top row (12), the key after the top
+//!   row (ANSI `\`, ISO `#`), home row (11), bottom row (10).
+//! - 48 characters: as above with the ISO key left of Z before the bottom row.
+//! - 49 characters: as 48, plus the ABNT2/JIS key right of the bottom row.
+//!
+//! Other lengths are read as 47, so shorter maps just leave keys out.
+
+use super::layouts::{KEY_HEIGHT, KeyParams, get_api_key_from_char};
+use strum::EnumIter;
+
+/// Width of one key unit in cells.
+const U: u16 = 4;
+/// Gap between the main block and the blocks to its right.
+const BLOCK_GAP: u16 = 2;
+
+/// Character positions of a layout `map`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Slots {
+    pub number: Vec<char>,
+    pub top: Vec<char>,
+    /// ANSI backslash (end of the top row) or ISO hash (end of the home row).
+    pub extra: Option<char>,
+    pub home: Vec<char>,
+    /// The ISO key between left shift and Z.
+    pub iso: Option<char>,
+    pub bottom: Vec<char>,
+    /// The ABNT2 `/` or JIS `ろ` key between the bottom row and right shift.
+    pub abnt: Option<char>,
+}
+
+impl Slots {
+    pub fn parse(map: &str) -> Self {
+        let chars: Vec<char> = map.chars().collect();
+        let range = |start: usize, len: usize| -> Vec<char> {
+            chars.iter().skip(start).take(len).copied().collect()
+        };
+        let iso = matches!(chars.len(), 48 | 49);
+        let bottom_start = if iso { 38 } else { 37 };
+        Self {
+            number: range(0, 13),
+            top: range(13, 12),
+            extra: chars.get(25).copied(),
+            home: range(26, 11),
+            iso: if iso { chars.get(37).copied() } else { None },
+            bottom: range(bottom_start, 10),
+            abnt: if chars.len() == 49 {
+                chars.get(48).copied()
+            } else {
+                None
+            },
+        }
+    }
+}
+
+/// The shape of the main block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Board {
+    #[default]
+    Ansi,
+    Iso,
+    Jis,
+    Abnt2,
+    Ortholinear,
+    Split,
+}
+
+impl Board {
+    pub fn label(self) -> &'static str {
+        match self {
+            Board::Ansi => "ANSI",
+            Board::Iso => "ISO",
+            Board::Jis => "JIS",
+            Board::Abnt2 => "ABNT2",
+            Board::Ortholinear => "Ortholinear",
+            Board::Split => "Split",
+        }
+    }
+}
+
+/// A main block plus the optional blocks around it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Geometry {
+    pub board: Board,
+    /// Esc and F1-F12 above the main block, with Print/Scroll/Pause over
+    /// the navigation cluster.
+    pub function_row: bool,
+    /// Insert/Delete block and arrows.
+    pub nav_cluster: bool,
+    /// Numeric keypad, with media keys over it when there is a function row.
+    pub numpad: bool,
+}
+
+impl Default for Geometry {
+    /// The compact ANSI board used before geometries existed.
+    fn default() -> Self {
+        Self::PRESETS[0].1
+    }
+}
+
+impl Geometry {
+    const fn new(board: Board, function_row: bool, nav_cluster: bool, numpad: bool) -> Self {
+        Self {
+            board,
+            function_row,
+            nav_cluster,
+            numpad,
+        }
+    }
+
+    /// Named form factors, in the order `g` cycles through them.
+    pub const PRESETS: [(&'static str, Geometry); 9] = [
+        ("ANSI", Geometry::new(Board::Ansi, false, true, false)),
+        ("ISO", Geometry::new(Board::Iso, false, true, false)),
+        ("JIS", Geometry::new(Board::Jis, false, true, false)),
+        ("ABNT2", Geometry::new(Board::Abnt2, false, true, false)),
+        ("60%", Geometry::new(Board::Ansi, false, false, false)),
+        ("TKL", Geometry::new(Board::Ansi, true, true, false)),
+        ("Full-size", Geometry::new(Board::Ansi, true, true, true)),
+        (
+            "Ortholinear",
+            Geometry::new(Board::Ortholinear, false, false, false),
+        ),
+        ("Split", Geometry::new(Board::Split, false, false, false)),
+    ];
+
+    pub fn name(&self) -> String {
+        if let Some((name, _)) = Self::PRESETS.iter().find(|(_, g)| g == self) {
+            return name.to_string();
+        }
+        let mut name = self.board.label().to_string();
+        if self.function_row {
+            name.push_str(" +F");
+        }
+        if !self.nav_cluster {
+            name.push_str(" -Nav");
+        }
+        if self.numpad {
+            name.push_str(" +Num");
+        }
+        name
+    }
+
+    /// The preset with this name, ignoring case.
+    pub fn find(name: &str) -> Option<Self> {
+        Self::PRESETS
+            .iter()
+            .find(|(preset, _)| preset.eq_ignore_ascii_case(name))
+            .map(|(_, geometry)| *geometry)
+    }
+
+    /// The next preset, or the first one for a customised geometry.
+    pub fn next(self) -> Self {
+        let index = Self::PRESETS.iter().position(|(_, g)| *g == self);
+        match index {
+            Some(i) => Self::PRESETS[(i + 1) % Self::PRESETS.len()].1,
+            None => Self::PRESETS[0].1,
+        }
+    }
+
+    /// All keys for a layout `map` on this geometry.
+    pub fn keys(&self, map: &str) -> Vec<KeyParams> {
+        let slots = Slots::parse(map);
+        let mut keys = Vec::new();
+        let top = if self.function_row { KEY_HEIGHT + 1 } else { 0 };
+
+        let width = match self.board {
+            Board::Ortholinear | Board::Split => grid_block(&mut keys, self.board, &slots),
+            board => staggered_block(&mut keys, board, &slots),
+        };
+        for key in &mut keys {
+            key.y += top;
+            if key.touch.is_none() {
+                key.touch = fixed_touch(&key.json_key);
+            }
+        }
+
+        let nav_x = width + BLOCK_GAP;
+        if self.function_row {
+            function_row(&mut keys, width, self.nav_cluster.then_some(nav_x));
+        }
+        if self.nav_cluster {
+            nav_cluster(&mut keys, nav_x, top);
+        }
+        if self.numpad {
+            let x = if self.nav_cluster {
+                nav_x + 3 * U + BLOCK_GAP
+            } else {
+                nav_x
+            };
+            numpad(&mut keys, x, top);
+            if self.function_row {
+                media_keys(&mut keys, x);
+            }
+        }
+        keys
+    }
+}
+
+/// The finger that presses a key when touch typing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EnumIter)]
+pub enum Finger {
+    LeftPinky,
+    LeftRing,
+    LeftMiddle,
+    LeftIndex,
+    Thumb,
+    RightIndex,
+    RightMiddle,
+    RightRing,
+    RightPinky,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Hand {
+    Left,
+    Right,
+}
+
+impl Finger {
+    /// Thumbs share the space bar, so they aren't counted for either hand.
+    pub fn hand(self) -> Option<Hand> {
+        use Finger::*;
+        match self {
+            LeftPinky | LeftRing | LeftMiddle | LeftIndex => Some(Hand::Left),
+            Thumb => None,
+            RightIndex | RightMiddle | RightRing | RightPinky => Some(Hand::Right),
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        use Finger::*;
+        match self {
+            LeftPinky => "L pinky",
+            LeftRing => "L ring",
+            LeftMiddle => "L middle",
+            LeftIndex => "L index",
+            Thumb => "Thumbs",
+            RightIndex => "R index",
+            RightMiddle => "R middle",
+            RightRing => "R ring",
+            RightPinky => "R pinky",
+        }
+    }
+}
+
+/// Rows of the main block, top to bottom.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Row {
+    Number,
+    Top,
+    Home,
+    Bottom,
+    Modifier,
+}
+
+/// How a main-block key is typed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Touch {
+    pub finger: Finger,
+    pub row: Row,
+    /// The key the finger rests on.
+    pub home: bool,
+}
+
+const fn touch(finger: Finger, row: Row) -> Touch {
+    Touch {
+        finger,
+        row,
+        home: false,
+    }
+}
+
+const EXTRA_TOP: Touch = touch(Finger::RightPinky, Row::Top);
+const EXTRA_HOME: Touch = touch(Finger::RightPinky, Row::Home);
+const ISO: Touch = touch(Finger::LeftPinky, Row::Bottom);
+const ABNT: Touch = touch(Finger::RightPinky, Row::Bottom);
+
+/// Fingers by column, from the left edge of a row's character keys. The
+/// number row starts one column further left, over the gap before Q.
+fn slot_touch(row: Row, i: usize) -> Touch {
+    use Finger::*;
+    const COLUMNS: [Finger; 12] = [
+        LeftPinky,
+        LeftRing,
+        LeftMiddle,
+        LeftIndex,
+        LeftIndex,
+        RightIndex,
+        RightIndex,
+        RightMiddle,
+        RightRing,
+        RightPinky,
+        RightPinky,
+        RightPinky,
+    ];
+    let column = if row == Row::Number {
+        i.saturating_sub(1)
+    } else {
+        i
+    };
+    Touch {
+        finger: COLUMNS[column.min(COLUMNS.len() - 1)],
+        row,
+        home: row == Row::Home && matches!(i, 0..=3 | 6..=9),
+    }
+}
+
+/// Touch for the fixed keys around the character keys.
+fn fixed_touch(json_key: &str) -> Option<Touch> {
+    use Finger::*;
+    let (finger, row) = match json_key {
+        "TAB" => (LeftPinky, Row::Top),
+        "CAPSLOCK" => (LeftPinky, Row::Home),
+        "LEFTSHIFT" | "LESS" => (LeftPinky, Row::Bottom),
+        "LEFTCONTROL" => (LeftPinky, Row::Modifier),
+        "BACKSPACE" | "YEN" => (RightPinky, Row::Number),
+        "RETURN" => (RightPinky, Row::Home),
+        "RIGHTSHIFT" | "RO" => (RightPinky, Row::Bottom),
+        "RIGHTCONTROL" => (RightPinky, Row::Modifier),
+        "SPACE" | "LEFTWINDOWS" | "LEFTALT" | "RIGHTALT" | "RIGHTWINDOWS" | "MENU" | "MUHENKAN"
+        | "HENKAN" | "KANA" => (Thumb, Row::Modifier),
+        _ => return None,
+    };
+    Some(Touch {
+        finger,
+        row,
+        home: json_key == "SPACE",
+    })
+}
+
+fn char_key(c: char, x: u16, y: u16, width: u16) -> KeyParams {
+    let label = if c.is_alphabetic() {
+        c.to_uppercase().to_string()
+    } else {
+        c.to_string()
+    };
+    let json_key = get_api_key_from_char(c).to_uppercase();
+    KeyParams::new(&label, &json_key, x, y, width)
+}
+
+/// Row-staggered boards. Returns the block width.
+fn staggered_block(keys: &mut Vec<KeyParams>, board: Board, slots: &Slots) -> u16 {
+    let mut add = |label: &str, json: &str, x: u16, y: u16, w: u16| {
+        keys.push(KeyParams::new(label, json, x, y, w));
+    };
+    let row = |keys: &[char], kind: Row, x: u16, y: u16| -> Vec<KeyParams> {
+        keys.iter()
+            .enumerate()
+            .map(|(i, c)| char_key(*c, x + i as u16 * U, y, U).with_touch(slot_touch(kind, i)))
+            .collect()
+    };
+    let (r0, r1, r2, r3, r4) = (
+        0,
+        KEY_HEIGHT,
+        2 * KEY_HEIGHT,
+        3 * KEY_HEIGHT,
+        4 * KEY_HEIGHT,
+    );
+    let mut chars = Vec::new();
+
+    // Number row
+    chars.extend(row(&slots.number, Row::Number, 0, r0));
+    if board == Board::Jis {
+        add("¥", "YEN", 52, r0, U);
+        add("Bksp", "BACKSPACE", 56, r0, U);
+    } else {
+        add("Bksp", "BACKSPACE", 52, r0, 8);
+    }
+
+    // Top row; ISO-style boards start the tall Enter here.
+    add("Tab", "TAB", 0, r1, 6);
+    chars.extend(row(&slots.top, Row::Top, 6, r1));
+    if board == Board::Ansi {
+        if let Some(c) = slots.extra {
+            chars.push(char_key(c, 54, r1, 6).with_touch(EXTRA_TOP));
+        }
+    } else {
+        add("Enter", "RETURN", 54, r1, 6);
+    }
+
+    // Home row
+    add("Caps", "CAPSLOCK", 0, r2, 7);
+    chars.extend(row(&slots.home, Row::Home, 7, r2));
+    if board == Board::Ansi {
+        add("Enter", "RETURN", 51, r2, 9);
+    } else {
+        if let Some(c) = slots.extra {
+            chars.push(char_key(c, 51, r2, U).with_touch(EXTRA_HOME));
+        }
+        add("", "RETURN", 55, r2, 5);
+    }
+
+    // Bottom row
+    match board {
+        Board::Iso | Board::Abnt2 => {
+            add("Shift", "LEFTSHIFT", 0, r3, 5);
+            // `<` is its own key, not the comma it shares a character with.
+            let iso = match slots.iso {
+                None | Some('<') => KeyParams::new("<", "LESS", 5, r3, U),
+                Some(c) => char_key(c, 5, r3, U),
+            };
+            chars.push(iso.with_touch(ISO));
+        }
+        _ => add("Shift", "LEFTSHIFT", 0, r3, 9),
+    }
+    chars.extend(row(&slots.bottom, Row::Bottom, 9, r3));
+    match board {
+        Board::Abnt2 | Board::Jis => {
+            match (slots.abnt, board) {
+                (Some(c), _) => chars.push(char_key(c, 49, r3, U).with_touch(ABNT)),
+                (None, Board::Jis) => add("Ro", "RO", 49, r3, U),
+                (None, _) => add("/", "SLASH", 49, r3, U),
+            }
+            add("Shift", "RIGHTSHIFT", 53, r3, 7);
+        }
+        _ => add("Shift", "RIGHTSHIFT", 49, r3, 11),
+    }
+
+    // Modifier row
+    if board == Board::Jis {
+        add("Ctrl", "LEFTCONTROL", 0, r4, 5);
+        add("Win", "LEFTWINDOWS", 5, r4, U);
+        add("Alt", "LEFTALT", 9, r4, U);
+        add("NoCnv", "MUHENKAN", 13, r4, 5);
+        add("Space", "SPACE", 18, r4, 16);
+        add("Conv", "HENKAN", 34, r4, 5);
+        add("Kana", "KANA", 39, r4, U);
+        add("Alt", "RIGHTALT", 43, r4, U);
+        add("Win", "RIGHTWINDOWS", 47, r4, U);
+        add("Menu", "MENU", 51, r4, U);
+        add("Ctrl", "RIGHTCONTROL", 55, r4, 5);
+    } else {
+        add("Ctrl", "LEFTCONTROL", 0, r4, 5);
+        add("Win", "LEFTWINDOWS", 5, r4, 5);
+        add("Alt", "LEFTALT", 10, r4, 5);
+        add("Space", "SPACE", 15, r4, 25);
+        add("Alt", "RIGHTALT", 40, r4, 5);
+        add("Win", "RIGHTWINDOWS", 45, r4, 5);
+        add("Menu", "MENU", 50, r4, 5);
+        add("Ctrl", "RIGHTCONTROL", 55, r4, 5);
+    }
+
+    keys.extend(chars);
+    60
+}
+
+/// Column-aligned 14-wide grid; the split board opens a gap after the
+/// sixth column (between T and Y). Returns the block width.
+fn grid_block(keys: &mut Vec<KeyParams>, board: Board, slots: &Slots) -> u16 {
+    let gap = if board == Board::Split { 6 } else { 0 };
+    let col = |c: u16| c * U + if c >= 6 { gap } else { 0 };
+    let mut add = |label: &str, json: &str, c: u16, y: u16, cols: u16| {
+        keys.push(KeyParams::new(label, json, col(c), y, cols * U));
+    };
+    let (r0, r1, r2, r3, r4) = (
+        0,
+        KEY_HEIGHT,
+        2 * KEY_HEIGHT,
+        3 * KEY_HEIGHT,
+        4 * KEY_HEIGHT,
+    );
+    let mut chars = Vec::new();
+    let mut row = |keys: &[char], kind: Row, first: u16, y: u16| {
+        for (i, c) in keys.iter().enumerate() {
+            chars.push(char_key(*c, col(first + i as u16), y, U).with_touch(slot_touch(kind, i)));
+        }
+    };
+
+    row(&slots.number, Row::Number, 0, r0);
+    row(&slots.top, Row::Top, 1, r1);
+    row(&slots.home, Row::Home, 1, r2);
+    row(&slots.bottom, Row::Bottom, 1, r3);
+    if let Some(c) = slots.extra {
+        chars.push(char_key(c, col(13), r1, U).with_touch(EXTRA_TOP));
+    }
+    let spare = slots.iso.or(slots.abnt);
+    if let Some(c) = spare {
+        chars.push(char_key(c, col(11), r3, U).with_touch(ABNT));
+    }
+
+    add("Bksp", "BACKSPACE", 13, r0, 1);
+    add("Tab", "TAB", 0, r1, 1);
+    add("Caps", "CAPSLOCK", 0, r2, 1);
+    add("Enter", "RETURN", 12, r2, 2);
+    add("Shift", "LEFTSHIFT", 0, r3, 1);
+    if spare.is_none() {
+        add("Shift", "RIGHTSHIFT", 11, r3, 3);
+    } else {
+        add("Shift", "RIGHTSHIFT", 12, r3, 2);
+    }
+
+    add("Ctrl", "LEFTCONTROL", 0, r4, 1);
+    add("Win", "LEFTWINDOWS", 1, r4, 1);
+    add("Alt", "LEFTALT", 2, r4, 1);
+    if board == Board::Split {
+        // A space bar under each thumb.
+        add("Space", "SPACE", 3, r4, 3);
+        add("Space", "SPACE", 6, r4, 4);
+        add("Alt", "RIGHTALT", 10, r4, 1);
+        add("Win", "RIGHTWINDOWS", 11, r4, 1);
+        add("Menu", "MENU", 12, r4, 1);
+        add("Ctrl", "RIGHTCONTROL", 13, r4, 1);
+    } else {
+        add("Space", "SPACE", 3, r4, 6);
+        add("Alt", "RIGHTALT", 9, r4, 1);
+        add("Win", "RIGHTWINDOWS", 10, r4, 1);
+        add("Menu", "MENU", 11, r4, 1);
+        add("Ctrl", "RIGHTCONTROL", 12, r4, 2);
+    }
+
+    keys.extend(chars);
+    col(14)
+}
+
+/// Esc and F1-F12, right-aligned in groups of four over a block `width` wide.
+fn function_row(keys: &mut Vec<KeyParams>, width: u16, nav_x: Option<u16>) {
+    keys.push(KeyParams::new("Esc", "ESCAPE", 0, 0, U));
+    let groups = [width - 52, width - 34, width - 16];
+    for (g, start) in groups.iter().enumerate() {
+        for i in 0..4 {
+            let n = g * 4 + i + 1;
+            let name = format!("F{}", n);
+            keys.push(KeyParams::new(&name, &name, start + i as u16 * U, 0, U));
+        }
+    }
+    if let Some(x) = nav_x {
+        keys.push(KeyParams::new("PrtSc", "PRINT", x, 0, U));
+        keys.push(KeyParams::new("ScrLk", "SCROLLLOCK", x + U, 0, U));
+        keys.push(KeyParams::new("Pause", "PAUSE", x + 2 * U, 0, U));
+    }
+}
+
+/// Volume and play keys in the function row, over the numpad.
+fn media_keys(keys: &mut Vec<KeyParams>, x: u16) {
+    for (i, (label, json)) in [
+        ("Mute", "VOLUMEMUTE"),
+        ("V-", "VOLUMEDOWN"),
+        ("V+", "VOLUMEUP"),
+        ("Play", "MEDIAPLAYPAUSE"),
+    ]
+    .iter()
+    .enumerate()
+    {
+        keys.push(KeyParams::new(label, json, x + i as u16 * U, 0, U));
+    }
+}
+
+/// Insert/Delete block over the inverted-T arrows.
+fn nav_cluster(keys: &mut Vec<KeyParams>, x: u16, top: u16) {
+    let mut add = |label: &str, json: &str, col: u16, row: u16| {
+        keys.push(KeyParams::new(
+            label,
+            json,
+            x + col * U,
+            top + row * KEY_HEIGHT,
+            U,
+        ));
+    };
+    add("Ins", "INSERT", 0, 0);
+    add("Home", "HOME", 1, 0);
+    add("PgUp", "PAGEUP", 2, 0);
+    add("Del", "DELETE", 0, 1);
+    add("End", "END", 1, 1);
+    add("PgDn", "PAGEDOWN", 2, 1);
+    add("Up", "UP", 1, 3);
+    add("Left", "LEFT", 0, 4);
+    add("Down", "DOWN", 1, 4);
+    add("Right", "RIGHT", 2, 4);
+}
+
+/// Standard numeric keypad. The tall `+` and Enter keys are drawn as two
+/// cells with the same key name.
+fn numpad(keys: &mut Vec<KeyParams>, x: u16, top: u16) {
+    let mut add = |label: &str, json: &str, col: u16, row: u16, cols: u16| {
+        keys.push(KeyParams::new(
+            label,
+            json,
+            x + col * U,
+            top + row * KEY_HEIGHT,
+            cols * U,
+        ));
+    };
+    add("Num", "NUMLOCK", 0, 0, 1);
+    add("/", "NUMPADDIVIDE", 1, 0, 1);
+    add("*", "NUMPADMULTIPLY", 2, 0, 1);
+    add("-", "NUMPADSUBTRACT", 3, 0, 1);
+    for (row, digits) in [
+        (1, ["7", "8", "9"]),
+        (2, ["4", "5", "6"]),
+        (3, ["1", "2", "3"]),
+    ] {
+        for (col, digit) in digits.iter().enumerate() {
+            add(digit, &format!("NUMPAD{}", digit), col as u16, row, 1);
+        }
+    }
+    add("+", "NUMPADADD", 3, 1, 1);
+    add("", "NUMPADADD", 3, 2, 1);
+    add("0", "NUMPAD0", 0, 4, 2);
+    add(".", "NUMPADDECIMAL", 2, 4, 1);
+    add("Ent", "NUMPADENTER", 3, 3, 1);
+    add("", "NUMPADENTER", 3, 4, 1);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const QWERTY: &str = "`1234567890-=qwertyuiop[]\\asdfghjkl;'zxcvbnm,./";
+    const UK: &str = "`1234567890-=qwertyuiop[]#asdfghjkl;'\\zxcvbnm,./";
+
+    fn find<'a>(keys: &'a [KeyParams], label: &str) -> &'a KeyParams {
+        keys.iter()
+            .find(|k| k.label == label)
+            .unwrap_or_else(|| panic!("no key {}", label))
+    }
+
+    fn overlaps(a: &KeyParams, b: &KeyParams) -> bool {
+        a.y == b.y && a.x < b.x + b.width && b.x < a.x + a.width
+    }
+
+    #[test]
+    fn test_iso_maps_keep_the_extra_key() {
+        let slots = Slots::parse(UK);
+        assert_eq!(slots.iso, Some('\\'));
+        assert_eq!(slots.bottom.last(), Some(&'/'));
+
+        let iso = Geometry::PRESETS[1].1.keys(UK);
+        assert_eq!(find(&iso, "\\").x, 5);
+        assert_eq!(find(&iso, "Z").x, 9);
+        assert_eq!(find(&iso, "/").x, 45);
+        // Hash moves to the home row next to the tall Enter.
+        assert_eq!(find(&iso, "#").y, 6);
+
+        // ANSI has no key for it; the bottom row still ends with slash.
+        let ansi = Geometry::default().keys(UK);
+        assert!(ansi.iter().all(|k| k.label != "\\"));
+        assert_eq!(find(&ansi, "/").x, 45);
+
+        // A map with `<` there, or without the key, gets LESS.
+        let de = Geometry::PRESETS[1]
+            .1
+            .keys("^1234567890ß´qwertzuiopü+#asdfghjklöä<yxcvbnm,.-");
+        let us = Geometry::PRESETS[1]
+            .1
+            .keys("`1234567890-=qwertyuiop[]\\asdfghjkl;'zxcvbnm,./");
+        for keys in [&de, &us] {
+            assert_eq!(find(keys, "<").json_key, "LESS");
+            assert_eq!(find(keys, "<").x, 5);
+            assert_eq!(find(keys, ",").json_key, "COMMA");
+        }
+    }
+
+    #[test]
+    fn test_presets_have_no_overlapping_keys() {
+        for (name, geometry) in Geometry::PRESETS {
+            let keys = geometry.keys(QWERTY);
+            for (i, a) in keys.iter().enumerate() {
+                for b in &keys[i + 1..] {
+                    assert!(
+                        !overlaps(a, b),
+                        "{}: {} overlaps {}",
+                        name,
+                        a.label,
+                        b.label
+                    );
+                }
+            }
+            assert_eq!(geometry.name(), name);
+        }
+    }
+
+    #[test]
+    fn test_optional_blocks() {
+        let full = Geometry::PRESETS[6].1.keys(QWERTY);
+        assert_eq!(find(&full, "Esc").y, 0);
+        assert_eq!(find(&full, "F12").x + U, 60);
+        assert_eq!(find(&full, "Q").y, 7);
+        assert_eq!(find(&full, "Num").x, 76);
+        assert_eq!(find(&full, "Ins").y, 4);
+        assert_eq!(find(&full, "Mute").x, 76);
+        assert_eq!(find(&full, "Play").y, 0);
+
+        let sixty = Geometry::PRESETS[4].1.keys(QWERTY);
+        assert!(sixty.iter().all(|k| k.x + k.width <= 60));
+
+        let custom = Geometry {
+            numpad: true,
+            ..Geometry::default()
+        };
+        assert_eq!(custom.name(), "ANSI +Num");
+        assert_eq!(custom.next(), Geometry::default());
+        assert!(custom.keys(QWERTY).iter().all(|k| k.label != "Mute"));
+        let tkl = Geometry {
+            function_row: true,
+            ..Geometry::default()
+        };
+        assert_eq!(tkl.name(), "TKL");
+    }
+}
@@ -0,0 +1,348 @@
+//! Keyboards described by other tools: keyboard-layout-editor.com (KLE)
+//! JSON, and QMK `info.json` with an optional `keymap.json`.
+//!
+//! Positions come in key units and are scaled to the heatmap grid. Key
+//! heights and rotation are not drawn; rotated clusters keep their origin.
+
+use super::layouts::{KEY_HEIGHT, KeyDef, get_api_key_from_char};
+use crate::key_mapping::{map_legend, map_qmk_keycode};
+use anyhow::{Context, Result, bail};
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// Cells per key unit across; down is [`KEY_HEIGHT`].
+const CELLS_PER_UNIT: f64 = 4.0;
+/// Keys without a legend at least this wide are taken as space bars.
+const SPACE_MIN_UNITS: f64 = 4.0;
+
+/// A key in key units, before scaling.
+struct Placed {
+    label: String,
+    key: String,
+    x: f64,
+    y: f64,
+    w: f64,
+}
+
+/// Parses the "Download JSON" export of keyboard-layout-editor.com.
+pub fn parse_kle(json: &str) -> Result<Vec<KeyDef>> {
+    let value: Value = serde_json::from_str(json)
+        .context("Not valid JSON (use the \"Download JSON\" export, not the raw data)")?;
+    let rows = value.as_array().context("Expected an array of rows")?;
+
+    let mut placed = Vec::new();
+    let (mut x, mut y) = (0.0, 0.0);
+    let (mut rx, mut ry) = (0.0, 0.0);
+    let mut w = 1.0;
+    for row in rows {
+        // The first entry may be the board's metadata object.
+        let Some(items) = row.as_array() else {
+            continue;
+        };
+        for item in items {
+            match item {
+                Value::Object(props) => {
+                    let num = |k: &str| props.get(k).and_then(Value::as_f64);
+                    if num("rx").is_some() || num("ry").is_some() {
+                        rx = num("rx").unwrap_or(rx);
+                        ry = num("ry").unwrap_or(ry);
+                        x = rx;
+                        y = ry;
+                    }
+                    x += num("x").unwrap_or(0.0);
+                    y += num("y").unwrap_or(0.0);
+                    if let Some(width) = num("w") {
+                        w = width;
+                    }
+                }
+                Value::String(legend) => {
+                    let (label, key) = from_legend(legend, w);
+                    placed.push(Placed {
+                        label,
+                        key,
+                        x,
+                        y,
+                        w,
+                    });
+                    x += w;
+                    w = 1.0;
+                }
+                other => bail!("Unexpected value in a row: {}", other),
+            }
+        }
+        y += 1.0;
+        x = rx;
+    }
+    if placed.is_empty() {
+        bail!("No keys found");
+    }
+    Ok(finish(placed))
+}
+
+#[derive(Deserialize)]
+struct QmkInfo {
+    layouts: BTreeMap<String, QmkLayout>,
+}
+
+#[derive(Deserialize)]
+struct QmkLayout {
+    layout: Vec<QmkKey>,
+}
+
+#[derive(Deserialize)]
+struct QmkKey {
+    x: f64,
+    y: f64,
+    #[serde(default = "one_unit")]
+    w: f64,
+    label: Option<String>,
+}
+
+fn one_unit() -> f64 {
+    1.0
+}
+
+/// `keymap.json` as written by QMK Configurator or `qmk c2json`, or a VIA
+/// export. Only the base layer is used.
+#[derive(Deserialize)]
+struct QmkKeymap {
+    layout: Option<String>,
+    layers: Vec<Vec<Value>>,
+}
+
+/// Parses a QMK `info.json`. `layout` picks one of its `LAYOUT_*` macros,
+/// defaulting to the keymap's, then to the only one. Without a keymap the
+/// keys are named from their labels.
+pub fn parse_qmk(info: &str, keymap: Option<&str>, layout: Option<&str>) -> Result<Vec<KeyDef>> {
+    let info: QmkInfo = serde_json::from_str(info).context("Failed to parse info.json")?;
+    let keymap: Option<QmkKeymap> = keymap
+        .map(serde_json::from_str)
+        .transpose()
+        .context("Failed to parse keymap")?;
+
+    let wanted = layout.or(keymap.as_ref().and_then(|k| k.layout.as_deref()));
+    let (name, qmk_layout) = match wanted {
+        Some(name) => info
+            .layouts
+            .get_key_value(name)
+            .with_context(|| format!("info.json has no layout {}", name))?,
+        None if info.layouts.len() == 1 => info.layouts.iter().next().unwrap(),
+        None => bail!(
+            "Pick a layout: {}",
+            info.layouts.keys().cloned().collect::<Vec<_>>().join(", ")
+        ),
+    };
+
+    let base = match &keymap {
+        Some(keymap) => {
+            let layer = keymap.layers.first().context("Keymap has no layers")?;
+            if layer.len() != qmk_layout.layout.len() {
+                bail!(
+                    "Keymap has {} keys but {} has {}",
+                    layer.len(),
+                    name,
+                    qmk_layout.layout.len()
+                );
+            }
+            Some(layer)
+        }
+        None => None,
+    };
+
+    let placed = qmk_layout
+        .layout
+        .iter()
+        .enumerate()
+        .map(|(i, k)| {
+            let (label, key) = match base.map(|layer| &layer[i]) {
+                Some(code) => {
+                    let code = code.as_str().unwrap_or_default();
+                    from_keycode(code, k.label.as_deref())
+                }
+                None => from_legend(k.label.as_deref().unwrap_or_default(), k.w),
+            };
+            Placed {
+                label,
+                key,
+                x: k.x,
+                y: k.y,
+                w: k.w,
+            }
+        })
+        .collect();
+    Ok(finish(placed))
+}
+
+fn from_keycode(code: &str, label: Option<&str>) -> (String, String) {
+    match map_qmk_keycode(code) {
+        Some(key) => {
+            // `LT(1, KC_SPC)` is labelled by the key it taps: `SPC`.
+            let label = label.map(str::to_string).unwrap_or_else(|| {
+                let inner = code.rsplit(['(', ',']).next().unwrap_or(code);
+                inner
+                    .trim()
+                    .trim_end_matches(')')
+                    .trim_start_matches("KC_")
+                    .to_string()
+            });
+            (label, key)
+        }
+        // Layer keys and the like never show up in WhatPulse.
+        None => (code.to_string(), code.to_string()),
+    }
+}
+
+/// Label and key name from a legend. KLE puts one legend per line, shifted
+/// characters first, so the last line is the one the key types.
+fn from_legend(legend: &str, width: f64) -> (String, String) {
+    let text = strip_html(legend);
+    let lines: Vec<&str> = text
+        .split('\n')
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .collect();
+    let Some(label) = lines.last() else {
+        let key = if width >= SPACE_MIN_UNITS {
+            "SPACE"
+        } else {
+            ""
+        };
+        return (String::new(), key.to_string());
+    };
+
+    let key = lines.iter().rev().find_map(|line| {
+        let mut chars = line.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Some(get_api_key_from_char(c).to_uppercase()),
+            _ => map_legend(line),
+        }
+    });
+    let label = if label.chars().count() == 1 {
+        label.to_uppercase()
+    } else {
+        label.to_string()
+    };
+    let key = key.unwrap_or_else(|| label.to_uppercase());
+    (label, key)
+}
+
+fn strip_html(legend: &str) -> String {
+    let mut out = String::new();
+    let mut in_tag = false;
+    for c in legend.replace("<br>", "\n").chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            c if !in_tag => out.push(c),
+            _ => {}
+        }
+    }
+    out.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&amp;", "&")
+}
+
+/// Scales to cells from the top-left key and gives sided modifiers their side.
+fn finish(placed: Vec<Placed>) -> Vec<KeyDef> {
+    let min_x = placed.iter().map(|p| p.x).fold(f64::INFINITY, f64::min);
+    let min_y = placed.iter().map(|p| p.y).fold(f64::INFINITY, f64::min);
+    let max_x = placed.iter().map(|p| p.x + p.w).fold(0.0, f64::max);
+    let middle = (min_x + max_x) / 2.0;
+
+    placed
+        .into_iter()
+        .map(|p| {
+            let key = match p.key.as_str() {
+                "SHIFT" | "CONTROL" | "ALT" | "WINDOWS" => {
+                    let side = if p.x + p.w / 2.0 < middle {
+                        "LEFT"
+                    } else {
+                        "RIGHT"
+                    };
+                    format!("{}{}", side, p.key)
+                }
+                _ => p.key,
+            };
+            KeyDef {
+                label: p.label,
+                key,
+                x: ((p.x - min_x) * CELLS_PER_UNIT).round() as u16,
+                y: ((p.y - min_y) * KEY_HEIGHT as f64).round() as u16,
+                width: (p.w * CELLS_PER_UNIT).round().max(1.0) as u16,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn find<'a>(keys: &'a [KeyDef], key: &str) -> &'a KeyDef {
+        keys.iter()
+            .find(|k| k.key == key)
+            .unwrap_or_else(|| panic!("no key {}", key))
+    }
+
+    #[test]
+    fn test_kle_rows_widths_and_legends() {
+        let json = r#"[
+            {"name": "Tiny"},
+            ["~\n`", "!\n1", {"w": 2}, "Backspace"],
+            [{"w": 1.5}, "Tab", "Q", {"x": 0.5}, "<i class='kb'></i>Del"],
+            [{"w": 1.25}, "Shift", "&lt;\n,", {"x": 2, "w": 1.25}, "Shift"],
+            [{"y": 0.5, "w": 6.25}, ""]
+        ]"#;
+        let keys = parse_kle(json).unwrap();
+        assert_eq!(keys.len(), 10);
+
+        let one = find(&keys, "1");
+        assert_eq!((one.label.as_str(), one.x, one.y), ("1", 4, 0));
+        assert_eq!(find(&keys, "BACKSPACE").width, 8);
+        let q = find(&keys, "Q");
+        assert_eq!((q.x, q.y), (6, 3));
+        assert_eq!(find(&keys, "DELETE").x, 12);
+        assert_eq!(find(&keys, "COMMA").label, ",");
+        assert_eq!(find(&keys, "LEFTSHIFT").x, 0);
+        assert_eq!(find(&keys, "RIGHTSHIFT").x, 17);
+        let space = find(&keys, "SPACE");
+        assert_eq!((space.y, space.width), (11, 25));
+    }
+
+    #[test]
+    fn test_qmk_info_with_keymap() {
+        let info = r#"{
+            "keyboard_name": "Macro",
+            "layouts": {
+                "LAYOUT": {"layout": [
+                    {"matrix": [0, 0], "x": 0, "y": 0, "label": "Esc"},
+                    {"matrix": [0, 1], "x": 1, "y": 0},
+                    {"matrix": [0, 2], "x": 2, "y": 0, "w": 2},
+                    {"matrix": [1, 0], "x": 0, "y": 1, "w": 1.25, "label": "Ctrl"}
+                ]}
+            }
+        }"#;
+        let keymap = r#"{"layout": "LAYOUT", "layers": [
+            ["KC_ESC", "LT(1, KC_SPC)", "MO(1)", "KC_LCTL"],
+            ["KC_TRNS", "KC_TRNS", "KC_TRNS", "KC_TRNS"]
+        ]}"#;
+        let keys = parse_qmk(info, Some(keymap), None).unwrap();
+        let names: Vec<&str> = keys.iter().map(|k| k.key.as_str()).collect();
+        assert_eq!(names, ["ESCAPE", "SPACE", "MO(1)", "LEFTCONTROL"]);
+        assert_eq!(keys[1].label, "SPC");
+        assert_eq!((keys[3].y, keys[3].width), (3, 5));
+
+        // Labels alone, and a keymap that doesn't fit.
+        let keys = parse_qmk(info, None, Some("LAYOUT")).unwrap();
+        assert_eq!(keys[0].key, "ESCAPE");
+        assert_eq!(keys[3].key, "LEFTCONTROL");
+        let short = r#"{"layers": [["KC_A"]]}"#;
+        assert!(parse_qmk(info, Some(short), None).is_err());
+
+        assert_eq!(map_qmk_keycode("LSFT_T(KC_A)").as_deref(), Some("A"));
+        assert_eq!(map_qmk_keycode("KC_P7").as_deref(), Some("NUMPAD7"));
+        assert_eq!(map_qmk_keycode("KC_F13").as_deref(), Some("F13"));
+        assert_eq!(map_qmk_keycode("KC_TRNS"), None);
+    }
+}
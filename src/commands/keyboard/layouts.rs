@@ -1,5 +1,14 @@
+use super::geometry::{Geometry, Touch};
+use super::import;
+use anyhow::{Context, Result, bail};
+use directories::ProjectDirs;
+use serde::Deserialize;
+use std::collections::HashMap;
 use std::fmt::Display;
-use strum::{EnumIter, IntoEnumIterator};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use strum::{AsRefStr, EnumIter, IntoEnumIterator};
 
 pub const KEY_HEIGHT: u16 = 3;
 
@@ -10,6 +19,9 @@ pub struct KeyParams {
     pub x: u16,
     pub y: u16,
     pub width: u16,
+    /// Finger and row on boards drawn from a geometry; `None` for keys
+    /// outside the main block and for fixed `keys` layouts.
+    pub touch: Option<Touch>,
 }
 
 impl KeyParams {
@@ -20,11 +32,18 @@ impl KeyParams {
             x,
             y,
             width,
+            touch: None,
         }
     }
+
+    pub fn with_touch(mut self, touch: Touch) -> Self {
+        self.touch = Some(touch);
+        self
+    }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter, AsRefStr)]
+#[strum(serialize_all = "snake_case")]
 pub enum KeyboardLayout {
     Qwerty,
     Qwertz,
@@ -119,342 +138,259 @@ pub enum KeyboardLayout {
     RomanianProgrammers,
     CzechProgrammers,
     Hungarian,
+    /// A user layout that isn't replacing a built-in one; index into the registry.
+    #[strum(disabled)]
+    Custom(usize),
 }
 
 impl Display for KeyboardLayout {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            KeyboardLayout::Qwerty => write!(f, "US QWERTY"),
-            KeyboardLayout::Qwertz => write!(f, "German QWERTZ"),
-            KeyboardLayout::Azerty => write!(f, "French AZERTY"),
-            KeyboardLayout::Dvorak => write!(f, "Dvorak Standard"),
-            KeyboardLayout::Colemak => write!(f, "Colemak"),
-            KeyboardLayout::Workman => write!(f, "Workman"),
-            KeyboardLayout::Qzerty => write!(f, "Italian QZERTY"),
-            KeyboardLayout::DvorakLeft => write!(f, "Dvorak Left-Handed"),
-            KeyboardLayout::DvorakRight => write!(f, "Dvorak Right-Handed"),
-            KeyboardLayout::ProgrammerDvorak => write!(f, "Programmer Dvorak"),
-            KeyboardLayout::ColemakModDh => write!(f, "Colemak Mod-DH"),
-            KeyboardLayout::Norman => write!(f, "Norman"),
-            KeyboardLayout::Bepo => write!(f, "French Bépo"),
-            KeyboardLayout::Neo => write!(f, "German Neo 2"),
-            KeyboardLayout::AdNW => write!(f, "AdNW (Aus der Neo-Welt)"),
-            KeyboardLayout::Halmak => write!(f, "Halmak"),
-            KeyboardLayout::Engram => write!(f, "Engram"),
-            KeyboardLayout::Mtgap => write!(f, "MTGAP"),
-            KeyboardLayout::Capewell => write!(f, "Capewell"),
-            KeyboardLayout::CapewellDvorak => write!(f, "Capewell-Dvorak"),
-            KeyboardLayout::Qwerf => write!(f, "QWERF"),
-            KeyboardLayout::Minimak => write!(f, "Minimak"),
-            KeyboardLayout::Tarmak => write!(f, "Tarmak"),
-            KeyboardLayout::CarpalxQgmlwy => write!(f, "Carpalx QGMLWY"),
-            KeyboardLayout::CarpalxQwyrfm => write!(f, "Carpalx QWYRFM"),
-            KeyboardLayout::Asset => write!(f, "Asset"),
-            KeyboardLayout::Qwpr => write!(f, "QWPR"),
-            KeyboardLayout::Klauser => write!(f, "Klauser"),
-            KeyboardLayout::Arensito => write!(f, "Arensito"),
-            KeyboardLayout::HandsDown => write!(f, "Hands Down"),
-            KeyboardLayout::Canary => write!(f, "Canary"),
-            KeyboardLayout::Gallium => write!(f, "Gallium"),
-            KeyboardLayout::Semimak => write!(f, "Semimak"),
-            KeyboardLayout::Graphite => write!(f, "Graphite"),
-            KeyboardLayout::Sturdy => write!(f, "Sturdy"),
-            KeyboardLayout::Ren => write!(f, "Ren"),
-            KeyboardLayout::Isrt => write!(f, "ISRT"),
-            KeyboardLayout::Maltron => write!(f, "Maltron 3D"),
-            KeyboardLayout::Malt => write!(f, "Malt"),
-            KeyboardLayout::Hcesar => write!(f, "H-CESAR"),
-            KeyboardLayout::Fitaly => write!(f, "FITALY"),
-            KeyboardLayout::Jcuken => write!(f, "Russian JCUKEN"),
-            KeyboardLayout::JcukenPhonetic => write!(f, "Russian Phonetic (YaWERT)"),
-            KeyboardLayout::Arabic101 => write!(f, "Arabic (101)"),
-            KeyboardLayout::Arabic102 => write!(f, "Arabic (102)"),
-            KeyboardLayout::PersianStandard => write!(f, "Persian (Standard)"),
-            KeyboardLayout::Urdu => write!(f, "Urdu"),
-            KeyboardLayout::Pashto => write!(f, "Pashto"),
-            KeyboardLayout::HebrewStandard => write!(f, "Hebrew (SI-1452)"),
-            KeyboardLayout::HebrewPhonetic => write!(f, "Hebrew (Phonetic)"),
-            KeyboardLayout::TurkishF => write!(f, "Turkish F"),
-            KeyboardLayout::TurkishQ => write!(f, "Turkish Q"),
-            KeyboardLayout::Greek => write!(f, "Greek"),
-            KeyboardLayout::Inscript => write!(f, "Indian InScript"),
-            KeyboardLayout::Tamil99 => write!(f, "Tamil 99"),
-            KeyboardLayout::Wijesekara => write!(f, "Sinhala Wijesekara"),
-            KeyboardLayout::ThaiKedmanee => write!(f, "Thai Kedmanee"),
-            KeyboardLayout::ThaiPattachote => write!(f, "Thai Pattachote"),
-            KeyboardLayout::Khmer => write!(f, "Khmer"),
-            KeyboardLayout::Lao => write!(f, "Lao"),
-            KeyboardLayout::Myanmar => write!(f, "Myanmar"),
-            KeyboardLayout::Vietnamese => write!(f, "Vietnamese"),
-            KeyboardLayout::Georgian => write!(f, "Georgian"),
-            KeyboardLayout::Armenian => write!(f, "Armenian"),
-            KeyboardLayout::Cherokee => write!(f, "Cherokee"),
-            KeyboardLayout::Tifinagh => write!(f, "Tifinagh (Berber)"),
-            KeyboardLayout::Inuktitut => write!(f, "Inuktitut (Nunavut)"),
-            KeyboardLayout::Dzongkha => write!(f, "Dzongkha"),
-            KeyboardLayout::Tibetan => write!(f, "Tibetan"),
-            KeyboardLayout::MongolianCyrillic => write!(f, "Mongolian (Cyrillic)"),
-            KeyboardLayout::BulgarianPhonetic => write!(f, "Bulgarian (Phonetic)"),
-            KeyboardLayout::UkrainianEnhanced => write!(f, "Ukrainian (Enhanced)"),
-            KeyboardLayout::Belarusian => write!(f, "Belarusian"),
-            KeyboardLayout::Kazakh => write!(f, "Kazakh"),
-            KeyboardLayout::Scandinavian => write!(f, "Nordic (Scandinavian)"),
-            KeyboardLayout::SwissGerman => write!(f, "Swiss German"),
-            KeyboardLayout::SwissFrench => write!(f, "Swiss French"),
-            KeyboardLayout::CanadianMultilingual => write!(f, "Canadian Multilingual Standard"),
-            KeyboardLayout::UsInternational => write!(f, "US International"),
-            KeyboardLayout::UkExtended => write!(f, "UK Extended"),
-            KeyboardLayout::Brazilian => write!(f, "Portuguese (Brazil ABNT2)"),
-            KeyboardLayout::Portuguese => write!(f, "Portuguese (Portugal)"),
-            KeyboardLayout::Spanish => write!(f, "Spanish (Spain)"),
-            KeyboardLayout::Italian => write!(f, "Italian"),
-            KeyboardLayout::Latvian => write!(f, "Latvian (QWERTY)"),
-            KeyboardLayout::LithuanianAzerty => write!(f, "Lithuanian ĄŽERTY"),
-            KeyboardLayout::Estonian => write!(f, "Estonian"),
-            KeyboardLayout::PolishProgrammers => write!(f, "Polish (Programmers)"),
-            KeyboardLayout::RomanianProgrammers => write!(f, "Romanian (Programmers)"),
-            KeyboardLayout::CzechProgrammers => write!(f, "Czech (Programmers)"),
-            KeyboardLayout::Hungarian => write!(f, "Hungarian"),
+        match self.definition() {
+            Some(def) => write!(f, "{}", def.name),
+            None => write!(f, "{}", self.id()),
         }
     }
 }
 
 impl KeyboardLayout {
-    pub fn get_keys(&self) -> Vec<KeyParams> {
-        let map_str = match self {
-            Self::Qwerty => "`1234567890-=qwertyuiop[]\\asdfghjkl;'zxcvbnm,./",
-            Self::Qwertz => "^1234567890ß´qwertzuiopü+#asdfghjklöäyxcvbnm,.-",
-            Self::Azerty => "²&é\"'(-è_çà)=azertyuiop^$*qsdfghjklmùwxcvbn,;:!",
-            Self::Dvorak => "`1234567890[]',.pyfgcrl/=\\aoeuidhtns-;qjkxbmwvz",
-            Self::Colemak => "`1234567890-=qwfpgjluy;[]\\arstdhneio'zxcvbkm,./",
-            Self::Workman => "`1234567890-=qdrwbjfup;[]\\ashtgyneoi'zxmcvkl,./",
-            Self::Qzerty => "\\1234567890'ìqzweyuiopè+ùasdfghjklmàwxcvbn.,-ò",
-            Self::DvorakLeft => "`[]/pfmlj4321;qbyurso.65=\\-kcdtheaz87'xgwvni,90",
-            Self::DvorakRight => "`1234567890/[]'q,.pyfglc=\\zaoeuidhtns-qjkxbmwvz",
-            Self::ProgrammerDvorak => "$&[{}(=*)+]!#;,.pyfgcrl/@\\aoeuidhtns-'qjkxbmwvz",
-            Self::ColemakModDh => "`1234567890-=qwfpbjluy;[]\\arstgmneio'zxcdvkh,./",
-            Self::Norman => "`1234567890-=qwdfkjurlo;[]\\asetgynioh'zxcvbpm,./",
-            Self::Bepo => "$1234567890=%b_po_v_dljzWçauie,ctsrnm_à_y.k'qghf",
-            Self::Neo => "^1234567890-`xvlcwkhgfqß´uiaeosnrtdyüöäpzbm,.j",
-            Self::AdNW => "`1234567890-=kuü.ävgcljf[]\\hieaodtrnsßxyö,qbpwmz",
-            Self::Halmak => "`1234567890-=wlrbz;qud j[]\\shnt,aeoi'fmvc/g.kpx",
-            Self::Engram => "`1234567890-=byou'ldwvz[]\\ciea,htsnqgxrmkjpf.;",
-
-            // New additions
-            Self::Mtgap => "`1234567890-=ypoujkdlcw[]\\inearsghtm;'qz/.,bfvx",
-            Self::Capewell => "`1234567890-=.ywdfjpluq[]\\aersgbtnio;'xzvcmhk,/", // Approx
-            Self::CapewellDvorak => "`1234567890[]'.,pyfgcrl/=\\aoeuidhtns-;qjkxbmwvz",
-            Self::Qwerf => "`1234567890-=qwerfjuio;[]\\asdyghklnm'zxcvbp,./", // Placeholder
-            Self::Minimak => "`1234567890-=qwdrykuio;[]\\asethgjnl'zxcvbpm,./", // Minimak 8
-            Self::Tarmak => "`1234567890-=qweptjyuk;[]\\asdrglnioh'zxcvbmf,./", // Tarmak 1
-            Self::CarpalxQgmlwy => "`1234567890-=qgmlwyfub;[]\\dstnriaeoh'zxcvjkp,./",
-            Self::CarpalxQwyrfm => "`1234567890-=qwyrfmluob[]\\asdhtgneio'zxcvjkp,./",
-            Self::Asset => "`1234567890-=qwjfgypul;[]\\asetdhnior'zxcvbkm,./",
-            Self::Qwpr => "`1234567890-=qwprfyjuld[]\\asetghnio;'zxcvbkm,./",
-            Self::Klauser => "`1234567890-=qwertyuiop[]\\asdfghjkl;'zxcvbnm,./", // Placeholder
-            Self::Arensito => "`1234567890-=ql,p......[]\\arenbgsito;'kmhdfuvc.,/wxyz......", // Arensito
-            Self::HandsDown => "`1234567890-=jgyu-l/.[;[]\\ristdhn,eo'xkbvwmqzf", // Hands Down Reference (ANSI)
-            Self::Canary => "`1234567890-=wlypkzxou;[]\\crstbfneia'jvdgqm/.,",
-            Self::Gallium => "`1234567890-=bldcvjyou,[]\\nrtsgphaei'xqmwzkf.;/",
-            Self::Semimak => "`1234567890-=flhdmv,uo;[]\\srntkyaei.'wxgbqjzp/",
-            Self::Graphite => "`1234567890-=bldwz'fouj[]\\nrtsgyhaei;qxmcvkp.,/",
-            Self::Sturdy => "`1234567890-=vmlcpx.ou;[]\\stryknaeih'gwjdfbzq,/",
-            Self::Ren => "`1234567890-=vymcuk.ou;[]\\strlhnai,e'gwjdfbzqp/",
-            Self::Isrt => "`1234567890-=yclmkzfu,;[]\\isrtgpneao'qvwdjbh./x",
-            Self::Maltron => "`1234567890-=qpycbvmuzl[]\\anisfdthoe'jwg,k.x;r", // ANSI approximation
-            Self::Malt => "`1234567890-=qpycbvmuzl[]\\anisfdthoe'jwg,k.x;r", // Same as Maltron usually
-            Self::Hcesar => "`1234567890-=hcesarodin[]\\tulpqgmbvf;'zjxkyw.,-/",
-            Self::Jcuken => "ё1234567890-=йцукенгшщзхъ\\фывапролджэячсмитьбю.",
-            Self::Arabic101 => "ذ1234567890-=ضصثقفغعهخحجد\\شسيبلاتنمكطئءؤرلاىةوزظ",
-            Self::Arabic102 => "ذ1234567890-=ضصثقفغعهخحجد\\شسيبلاتنمكطئءؤرلاىةوزظ", // Very similar to 101, usually < > variations
-            Self::PersianStandard => "÷1234567890-=ضصثقفغعهخحجچ\\شسيبلاتنمكگظطزرذدپو.",
-            Self::Urdu => "ۓ1234567890-=ٹچپہجخگفدعثڑ\\مَنلتکیبشسوضقصرذڈزطظ.",
-            Self::Pashto => "پ1234567890-=ٹڅچږجخگفدعښ\\مۍنلتکیبشسوضقصرذډزطظ.",
-            Self::HebrewStandard => ";1234567890-=/'קראטוןםפ][\\שדגכעיחלךף,זסבהנמצתץ.",
-            Self::HebrewPhonetic => "`1234567890-=קװארטיופ][\\אסדפגהJKL;'זXCVBנמ,./", // Rough phonetic
-            Self::TurkishF => "+1234567890/-fgğıodrnhpqwxuieaütkmlyşjövcçzsb.,",
-            Self::TurkishQ => "\"1234567890*-qwertyuıopğü,asdfghjklşi<zxcvbnmöç.",
-            Self::Greek => "`1234567890-=;ςερτυθιοπ[]\\ασδφγηξκλ΄'ζχψωβνμ,./",
-            Self::Inscript => "ॊ1234567890-=ौैाीूबहगदजड़\\ोे्िुपरकतचटॆंमनलसवशयष",
-            Self::Tamil99 => "ஆ1234567890-=ஆஈஊஐஏளரனடண\\ஓஏஅஇஉபகதசஜொோ்ிுயலறவஷ",
-            Self::Wijesekara => "1234567890-=ුඅැරඑහිසදච[]\\්ිාෙටයවනක;'ංජඩඉබප,./",
-            Self::ThaiKedmanee => "_1234567890-=ภถุึคตจขชๆไ\\ๆไำพะัีรนยบฃฟหกดเ้่าสวง",
-            Self::ThaiPattachote => "1234567890-=ตยอรรนวมงล\\กล่ดกเ้่าสบปอทมใฝ", // Approx
-            Self::Khmer => "1234567890-=ឆึេរតយុិោព[]\\ាសដថងហ្កល;'ឋខចវបនម,./",
-            Self::Lao => "ຢຟໂຖຸູຄຕຈຂ-=ົາເີືແ ້ ັ ືຍ[]\\ັຫກດເ ້ ່າສ;'ຜປແອຶືທ,./",
-            Self::Myanmar => "၁၂၃၄၅၆၇၈၉၀-=ဆတနမအပကငသစ[]\\ေျိ်ါ့ြုူ;'ဖထခလဘညာ,./",
-            Self::Vietnamese => "ĂÂÊỘ̀̉̃́Đ₫_qwertyuiopƯƠ\\asdfghjkl;'zxcvbnm,./",
-
-            // Missing Map Strings
-            Self::Fitaly => "1234567890-=zvchwk......[]\\fitaly......;'gdorsb......,./qjumpx......",
-            Self::JcukenPhonetic => "~1234567890-=явертыуиоп[]\\асдфгхйкл;'зхьцвбнм,./",
-            Self::Georgian => "“1234567890-=ქწერთყუიოპ[]\\ასდფგჰჯკლ;'ზხცვბნმ,./",
-            Self::Armenian => "՝1234567890-=քոեռթըւիօպ[]\\ասդֆգհյկլ;'զղցվբնմ,./",
-            Self::Cherokee => "Ꮚ1234567890-=ᏯᏪᎡᏛᎢᏲᎤᎢᎣᏢ[]\\ᎠᏍᏓᏩᎦᎭᎫᎧᎸ;'ᏴᏟᏟᏭᏄᎹ,./",
-            Self::Tifinagh => "²1234567890-=ⴰⵣⴻⵔⵜⵢⵓⵉⵄⵃ[]\\ⵇⵙⴷⴼⴳⵀⵊⴽⵍⵎ;'ⵡⵅⵛⵯⴱⵏ,;:!",
-            Self::Inuktitut => "1234567890-=qwertyuiop[]\\asdfghjkl;'zxcvbnm,./", // Placeholder
-            Self::Dzongkha => "1234567890-=qwertyuiop[]\\asdfghjkl;'zxcvbnm,./",  // Placeholder
-            Self::Tibetan => "1234567890-=qwertyuiop[]\\asdfghjkl;'zxcvbnm,./",   // Placeholder
-            Self::MongolianCyrillic => "№1234567890-=йцукенгшщзхъ\\фывапролджэ.ячсмитьбю,",
-            Self::BulgarianPhonetic => "ю1234567890-=чшертъуиопящ\\асдфгхйкл;'жзьцвбнм,./",
-            Self::UkrainianEnhanced => "'1234567890-=йцукенгшщзхї\\фывапролджє/ячсмитьбю.",
-            Self::Belarusian => "ё1234567890-=йцукенгшўзх'\\фывапролджэ/ячсмітьбю.",
-            Self::Kazakh => "(1234567890-=йцукенгшщзхъ\\фывапролджэ/ячсмитьбю.",
-            Self::Scandinavian => "§1234567890+´qwertyuiopå¨'asdfghjklöä<zxcvbnm,.-",
-            Self::SwissGerman => "§1234567890'^qwertzuiopü¨$asdfghjklöä<yxcvbnm,.-",
-            Self::SwissFrench => "§1234567890'^qwertzuiopè¨$asdfghjkléà<yxcvbnm,.-",
-            Self::CanadianMultilingual => "/1234567890-=\\qwertyuiop^¸[asdfghjkl;`]zxcvbnm,./",
-            Self::UsInternational => "`1234567890-=qwertyuiop[]\\asdfghjkl;'zxcvbnm,./",
-            Self::UkExtended => "`1234567890-=qwertyuiop[]#asdfghjkl;'\\zxcvbnm,./",
-            Self::Brazilian => "'1234567890-=qwertyuiop´[}asdfghjklç^]zxcvbnm,.;/",
-            Self::Portuguese => "\\1234567890'«qwertyuiop+´~asdfghjklçº<zxcvbnm,.-",
-            Self::Spanish => "º1234567890'¡qwertyuiop`+çasdfghjklñ´<zxcvbnm,.-",
-            Self::Italian => "\\1234567890'ìqwertyuiopè+asdfghjklòàù<zxcvbnm,.-",
-            Self::Latvian => "`1234567890-=qwertyuiop[]\\asdfghjkl;'zxcvbnm,./",
-            Self::LithuanianAzerty => "ĄČĘĖĮŠŲŪ90-=Žqwertyuiop[]\\asdfghjkl;'zxcvbnm,./",
-            Self::Estonian => "ˇ1234567890+´qwertyuiopüõ'asdfghjklöä<zxcvbnm,.-",
-            Self::PolishProgrammers => "~1234567890+=qwertyuiop[]\\asdfghjkl;'zxcvbnm,./",
-            Self::RomanianProgrammers => "~1234567890+=qwertyuiop[]\\asdfghjkl;'zxcvbnm,./",
-            Self::CzechProgrammers => "~1234567890+=qwertyuiop[]\\asdfghjkl;'zxcvbnm,./",
-            Self::Hungarian => "0123456789öüóqwertzuiopőúűasdfghjkléáíyxcvbnm,.-",
-        };
+    /// Stable name used in layout files, e.g. `colemak_mod_dh`.
+    pub fn id(&self) -> String {
+        match self {
+            Self::Custom(i) => registry()
+                .custom
+                .get(*i)
+                .map_or_else(|| format!("custom_{}", i), |def| def.id.clone()),
+            builtin => builtin.as_ref().to_string(),
+        }
+    }
+
+    fn definition(&self) -> Option<&'static LayoutDef> {
+        let registry = registry();
+        match self {
+            Self::Custom(i) => registry.custom.get(*i),
+            builtin => registry.builtin.get(builtin.as_ref()),
+        }
+    }
 
-        // Sanity check for string length if needed, but we'll just process what we have
-        build_ansi_layout(map_str)
+    pub fn get_keys(&self, geometry: Geometry) -> Vec<KeyParams> {
+        self.definition()
+            .map(|def| def.keys(geometry))
+            .unwrap_or_default()
     }
 
+    /// Built-in layouts followed by the user's own.
     pub fn all() -> Vec<Self> {
-        Self::iter().collect()
+        Self::iter()
+            .chain((0..registry().custom.len()).map(Self::Custom))
+            .collect()
+    }
+
+    /// The layout with this id or display name, ignoring case.
+    pub fn find(name: &str) -> Option<Self> {
+        Self::all().into_iter().find(|layout| {
+            layout.id().eq_ignore_ascii_case(name) || layout.to_string().eq_ignore_ascii_case(name)
+        })
     }
 }
 
-fn build_ansi_layout(map: &str) -> Vec<KeyParams> {
-    let mut keys = Vec::new();
-    let chars: Vec<char> = map.chars().collect();
-
-    // Helper to add key
-    let mut add = |label: &str, json: &str, x: u16, y: u16, w: u16| {
-        keys.push(KeyParams::new(label, json, x, y, w));
-    };
-
-    // --- Row 1 ---
-    // Physical: Grave, 1, 2, 3, 4, 5, 6, 7, 8, 9, 0, Minus, Equal
-    let r1_x = [0, 4, 8, 12, 16, 20, 24, 28, 32, 36, 40, 44, 48];
-    for i in 0..13 {
-        if i < chars.len() {
-            let label = chars[i].to_string();
-            // Special handling for uppercase if it's a letter
-            let display_label = if label.chars().next().unwrap().is_alphabetic() {
-                label.to_uppercase()
-            } else {
-                label.clone()
-            };
-            let json_key = get_api_key_from_char(chars[i]).to_uppercase();
-            add(&display_label, &json_key, r1_x[i], 0, 4);
-        }
+/// One key in a layout file.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct KeyDef {
+    pub label: String,
+    /// WhatPulse key name, e.g. `A`, `SPACE`, `LEFTSHIFT`.
+    pub key: String,
+    pub x: u16,
+    pub y: u16,
+    #[serde(default = "default_key_width")]
+    pub width: u16,
+}
+
+fn default_key_width() -> u16 {
+    4
+}
+
+/// A layout as written in a layout file: a `map` of the character keys,
+/// drawn on whichever [`Geometry`] is selected, or a fixed board given as the
+/// full `keys` list or imported from `kle` or `qmk` files.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct LayoutDef {
+    /// Defaults to the file name for user layouts.
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    pub map: Option<String>,
+    #[serde(default)]
+    pub keys: Vec<KeyDef>,
+    /// keyboard-layout-editor.com JSON, relative to the layout file.
+    pub kle: Option<PathBuf>,
+    pub qmk: Option<QmkSource>,
+}
+
+/// QMK files for a layout, relative to the layout file.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct QmkSource {
+    pub info: PathBuf,
+    /// `keymap.json` or a VIA export; without it keys are named by label.
+    pub keymap: Option<PathBuf>,
+    /// Which `LAYOUT_*` of `info.json`, if the keymap doesn't say.
+    pub layout: Option<String>,
+}
+
+impl LayoutDef {
+    pub fn parse(content: &str) -> Result<Self> {
+        let def: LayoutDef = toml::from_str(content)?;
+        def.validate()?;
+        Ok(def)
     }
-    // Backspace (Fixed)
-    add("Bksp", "BACKSPACE", 52, 0, 8);
-
-    // --- Row 2 ---
-    // Tab (Fixed)
-    add("Tab", "TAB", 0, 3, 6);
-    // Physical: Q, W, E, R, T, Y, U, I, O, P, BracketLeft, BracketRight, Backslash
-    let r2_x = [6, 10, 14, 18, 22, 26, 30, 34, 38, 42, 46, 50, 54];
-    for i in 0..13 {
-        if 13 + i < chars.len() {
-            let label = chars[13 + i].to_string();
-            let display_label = if label.chars().next().unwrap().is_alphabetic() {
-                label.to_uppercase()
-            } else {
-                label.clone()
-            };
-            let json_key = get_api_key_from_char(chars[13 + i]).to_uppercase();
-            add(
-                &display_label,
-                &json_key,
-                r2_x[i],
-                3,
-                if i == 12 { 6 } else { 4 },
+
+    fn validate(&self) -> Result<()> {
+        let sources = [
+            self.map.is_some(),
+            !self.keys.is_empty(),
+            self.kle.is_some(),
+            self.qmk.is_some(),
+        ];
+        match sources.iter().filter(|s| **s).count() {
+            1 => {}
+            0 => bail!(
+                "Layout '{}' needs one of `map`, `keys`, `kle` or `qmk`",
+                self.name
+            ),
+            _ => bail!(
+                "Layout '{}' has more than one of `map`, `keys`, `kle` and `qmk`",
+                self.name
+            ),
+        }
+        if let Some(key) = self.keys.iter().find(|k| k.width == 0) {
+            bail!(
+                "Layout '{}': key '{}' needs a width of at least 1",
+                self.name,
+                key.key
             );
         }
+        Ok(())
+    }
+
+    /// Reads `kle` or `qmk` files from `dir` into `keys`.
+    fn import(&mut self, dir: &Path) -> Result<()> {
+        let read = |path: &Path| {
+            let path = dir.join(path);
+            fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))
+        };
+        if let Some(kle) = self.kle.take() {
+            self.keys = import::parse_kle(&read(&kle)?)?;
+        } else if let Some(qmk) = self.qmk.take() {
+            let keymap = qmk.keymap.as_deref().map(read).transpose()?;
+            self.keys =
+                import::parse_qmk(&read(&qmk.info)?, keymap.as_deref(), qmk.layout.as_deref())?;
+        }
+        Ok(())
+    }
+
+    pub fn keys(&self, geometry: Geometry) -> Vec<KeyParams> {
+        match &self.map {
+            Some(map) => geometry.keys(map),
+            None => self
+                .keys
+                .iter()
+                .map(|k| KeyParams::new(&k.label, &k.key, k.x, k.y, k.width))
+                .collect(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct BuiltinFile {
+    layout: Vec<LayoutDef>,
+}
+
+const BUILTIN_LAYOUTS: &str = include_str!("builtin_layouts.toml");
+
+/// Built-in layouts, with user files from `<config dir>/layouts/*.toml`
+/// either replacing one (same `id`) or added as [`KeyboardLayout::Custom`].
+#[derive(Debug, Default)]
+pub struct LayoutRegistry {
+    builtin: HashMap<String, LayoutDef>,
+    custom: Vec<LayoutDef>,
+    /// Files that could not be loaded, shown in the layout picker.
+    pub errors: Vec<String>,
+}
+
+impl LayoutRegistry {
+    fn builtin() -> Self {
+        let file: BuiltinFile =
+            toml::from_str(BUILTIN_LAYOUTS).expect("builtin_layouts.toml is invalid");
+        Self {
+            builtin: file
+                .layout
+                .into_iter()
+                .map(|def| (def.id.clone(), def))
+                .collect(),
+            ..Default::default()
+        }
     }
 
-    // --- Row 3 ---
-    // Caps (Fixed)
-    add("Caps", "CAPSLOCK", 0, 6, 7);
-    // Physical: A, S, D, F, G, H, J, K, L, Semicolon, Apostrophe
-    let r3_x = [7, 11, 15, 19, 23, 27, 31, 35, 39, 43, 47];
-    for i in 0..11 {
-        if 26 + i < chars.len() {
-            let label = chars[26 + i].to_string();
-            let display_label = if label.chars().next().unwrap().is_alphabetic() {
-                label.to_uppercase()
-            } else {
-                label.clone()
-            };
-            let json_key = get_api_key_from_char(chars[26 + i]).to_uppercase();
-            add(&display_label, &json_key, r3_x[i], 6, 4);
+    fn load() -> Self {
+        let mut registry = Self::builtin();
+        if let Some(dir) = user_layouts_dir() {
+            registry.load_dir(&dir);
         }
+        registry
     }
-    // Enter (Fixed)
-    add("Enter", "RETURN", 51, 6, 9);
-
-    // --- Row 4 ---
-    // Left Shift (Fixed)
-    add("Shift", "LEFTSHIFT", 0, 9, 9);
-    // Physical: Z, X, C, V, B, N, M, Comma, Period, Slash
-    let r4_x = [9, 13, 17, 21, 25, 29, 33, 37, 41, 45];
-    for i in 0..10 {
-        if 37 + i < chars.len() {
-            let label = chars[37 + i].to_string();
-            let display_label = if label.chars().next().unwrap().is_alphabetic() {
-                label.to_uppercase()
-            } else {
-                label.clone()
-            };
-            let json_key = get_api_key_from_char(chars[37 + i]).to_uppercase();
-            add(&display_label, &json_key, r4_x[i], 9, 4);
+
+    fn load_dir(&mut self, dir: &Path) {
+        let Ok(entries) = fs::read_dir(dir) else {
+            return;
+        };
+        let mut paths: Vec<PathBuf> = entries
+            .flatten()
+            .map(|e| e.path())
+            .filter(|p| p.extension().is_some_and(|ext| ext == "toml"))
+            .collect();
+        paths.sort();
+
+        for path in paths {
+            let loaded = fs::read_to_string(&path)
+                .map_err(anyhow::Error::from)
+                .and_then(|content| LayoutDef::parse(&content))
+                .and_then(|mut def| def.import(dir).map(|_| def));
+            match loaded {
+                Ok(mut def) => {
+                    if def.id.is_empty() {
+                        def.id = path
+                            .file_stem()
+                            .map(|s| s.to_string_lossy().into_owned())
+                            .unwrap_or_default();
+                    }
+                    self.add(def);
+                }
+                Err(e) => {
+                    let message = format!("{}: {}", path.display(), e);
+                    log::warn!("Skipping keyboard layout {}", message);
+                    self.errors.push(message);
+                }
+            }
         }
     }
-    // Right Shift (Fixed)
-    add("Shift", "RIGHTSHIFT", 49, 9, 11);
-
-    // --- Row 5 ---
-    // Fixed Control row
-    add("Ctrl", "LEFTCONTROL", 0, 12, 5);
-    add("Win", "LEFTWINDOWS", 5, 12, 5);
-    add("Alt", "LEFTALT", 10, 12, 5);
-    add("Space", "SPACE", 15, 12, 25);
-    add("Alt", "RIGHTALT", 40, 12, 5);
-    add("Win", "RIGHTWINDOWS", 45, 12, 5);
-    add("Menu", "MENU", 50, 12, 5);
-    add("Ctrl", "RIGHTCONTROL", 55, 12, 5);
-
-    // --- Navigation Cluster ---
-    // Gap of 2 units from main block (ends at 60), so start at 62
-    let nav_start = 62;
-
-    // Row 1: Insert, Home, PageUp
-    add("Ins", "INSERT", nav_start, 0, 4);
-    add("Home", "HOME", nav_start + 4, 0, 4);
-    add("PgUp", "PAGEUP", nav_start + 8, 0, 4);
-
-    // Row 2: Delete, End, PageDown
-    add("Del", "DELETE", nav_start, 3, 4);
-    add("End", "END", nav_start + 4, 3, 4);
-    add("PgDn", "PAGEDOWN", nav_start + 8, 3, 4);
-
-    // --- Arrow Keys ---
-    // Row 4: Up Arrow (aligned with empty space above Down)
-    add("Up", "UP", nav_start + 4, 9, 4);
-
-    // Row 5: Left, Down, Right Arrows
-    add("Left", "LEFT", nav_start, 12, 4);
-    add("Down", "DOWN", nav_start + 4, 12, 4);
-    add("Right", "RIGHT", nav_start + 8, 12, 4);
-
-    keys
+
+    fn add(&mut self, def: LayoutDef) {
+        if self.builtin.contains_key(&def.id) {
+            self.builtin.insert(def.id.clone(), def);
+        } else if let Some(existing) = self.custom.iter_mut().find(|c| c.id == def.id) {
+            *existing = def;
+        } else {
+            self.custom.push(def);
+        }
+    }
+}
+
+pub fn registry() -> &'static LayoutRegistry {
+    static REGISTRY: OnceLock<LayoutRegistry> = OnceLock::new();
+    REGISTRY.get_or_init(LayoutRegistry::load)
+}
+
+fn user_layouts_dir() -> Option<PathBuf> {
+    ProjectDirs::from("com", "wtfpulse", "wtfpulse").map(|d| d.config_dir().join("layouts"))
 }
 
 pub fn get_api_key_from_char(c: char) -> String {
@@ -497,3 +433,95 @@ pub fn get_api_key_from_char(c: char) -> String {
         other => other.to_string().to_uppercase(),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_every_builtin_variant_has_a_definition() {
+        let registry = LayoutRegistry::builtin();
+        for layout in KeyboardLayout::iter() {
+            let def = registry
+                .builtin
+                .get(layout.as_ref())
+                .unwrap_or_else(|| panic!("no data for {:?}", layout));
+            def.validate().unwrap();
+        }
+        assert_eq!(registry.builtin.len(), KeyboardLayout::iter().count());
+    }
+
+    #[test]
+    fn test_zero_width_keys_are_rejected() {
+        let def = "name = \"Pad\"\n[[keys]]\nlabel = \"A\"\nkey = \"A\"\nx = 0\ny = 0\n";
+        assert!(LayoutDef::parse(def).is_ok());
+        let err = LayoutDef::parse(&format!("{def}width = 0\n")).unwrap_err();
+        assert!(err.to_string().contains("key 'A'"), "{err}");
+    }
+
+    #[test]
+    fn test_qwerty_from_data() {
+        let keys = KeyboardLayout::Qwerty.get_keys(Geometry::default());
+        let find = |key: &str| keys.iter().find(|k| k.json_key == key).unwrap();
+        assert_eq!(find("Q").x, 6);
+        assert_eq!(find("BACKSLASH").width, 6);
+        assert_eq!(find("QUOTE").label, "'");
+        assert_eq!(KeyboardLayout::ColemakModDh.to_string(), "Colemak Mod-DH");
+    }
+
+    #[test]
+    fn test_user_layouts_replace_or_extend_builtins() {
+        let dir = std::env::temp_dir().join(format!("wtfpulse-layouts-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("klauser.toml"),
+            "id = \"klauser\"\nname = \"Klauser (fixed)\"\nmap = \"`1234567890-=kpuä,.clgjx[]\\\\hieao dtrnsfyöüq bmwvz\"\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.join("macro_pad.toml"),
+            r#"
+name = "Macro Pad"
+
+[[keys]]
+label = "F13"
+key = "F13"
+x = 0
+y = 0
+
+[[keys]]
+label = "Enter"
+key = "RETURN"
+x = 4
+y = 0
+width = 8
+"#,
+        )
+        .unwrap();
+        fs::write(dir.join("split.json"), r#"[["Q", {"x": 1}, "P"]]"#).unwrap();
+        fs::write(
+            dir.join("split.toml"),
+            "name = \"Split\"\nkle = \"split.json\"\n",
+        )
+        .unwrap();
+        fs::write(dir.join("broken.toml"), "name = \"No keys\"\n").unwrap();
+        fs::write(dir.join("notes.txt"), "ignored").unwrap();
+
+        let mut registry = LayoutRegistry::builtin();
+        registry.load_dir(&dir);
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(registry.builtin["klauser"].name, "Klauser (fixed)");
+        assert_eq!(registry.custom.len(), 2);
+        let pad = &registry.custom[0];
+        assert_eq!(pad.id, "macro_pad");
+        let keys = pad.keys(Geometry::PRESETS[1].1);
+        assert_eq!(keys.len(), 2);
+        assert_eq!((keys[1].json_key.as_str(), keys[1].width), ("RETURN", 8));
+        assert_eq!(keys[0].width, 4);
+        let split = registry.custom[1].keys(Geometry::default());
+        assert_eq!((split[1].json_key.as_str(), split[1].x), ("P", 8));
+        assert_eq!(registry.errors.len(), 1);
+        assert!(registry.errors[0].contains("broken.toml"));
+    }
+}
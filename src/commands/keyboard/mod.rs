@@ -1,5 +1,5 @@
 use crate::commands::TuiPage;
-use crate::tui::app::{App, SelectionStep, TimePeriod};
+use crate::tui::app::{App, KeyCounts, KeyboardPanel, SelectionStep, TimePeriod};
 use crossterm::event::{KeyCode, KeyEvent};
 use ratatui::{
     Frame,
@@ -8,9 +8,14 @@ use ratatui::{
     text::{Line, Span},
     widgets::{Block, Borders, Clear, List, ListItem, Paragraph},
 };
+use std::collections::HashMap;
 
+pub mod analysis;
+pub mod combos;
+pub mod geometry;
+mod import;
 pub mod layouts;
-use layouts::{KEY_HEIGHT, KeyboardLayout};
+use layouts::{KEY_HEIGHT, KeyParams, KeyboardLayout};
 
 inventory::submit! {
     TuiPage {
@@ -185,6 +190,30 @@ fn handle_key(app: &mut App, key: KeyEvent) -> bool {
             app.keyboard.layout_search_query.clear();
             true
         }
+        KeyCode::Char('g') => {
+            app.keyboard.geometry = app.keyboard.geometry.next();
+            true
+        }
+        KeyCode::Char('f') => {
+            app.keyboard.geometry.function_row = !app.keyboard.geometry.function_row;
+            true
+        }
+        KeyCode::Char('p') => {
+            app.keyboard.geometry.numpad = !app.keyboard.geometry.numpad;
+            true
+        }
+        KeyCode::Char('a') => {
+            app.keyboard.panel = toggle(app.keyboard.panel, KeyboardPanel::FingerLoad);
+            true
+        }
+        KeyCode::Char('c') => {
+            app.keyboard.panel = toggle(app.keyboard.panel, KeyboardPanel::Combos);
+            true
+        }
+        KeyCode::Char('b') if app.keyboard.panel == KeyboardPanel::FingerLoad => {
+            app.keyboard.baseline = app.keyboard.layout;
+            true
+        }
         KeyCode::Char('h') => {
             app.dashboard_period = match app.dashboard_period {
                 TimePeriod::Today => TimePeriod::Custom,
@@ -228,6 +257,15 @@ fn handle_key(app: &mut App, key: KeyEvent) -> bool {
     }
 }
 
+/// `panel`, or back to the heatmap if it is already showing.
+fn toggle(current: KeyboardPanel, panel: KeyboardPanel) -> KeyboardPanel {
+    if current == panel {
+        KeyboardPanel::Heatmap
+    } else {
+        panel
+    }
+}
+
 fn fetch_heatmap(app: &App) {
     let period_str = match app.dashboard_period {
         TimePeriod::Today => "today".to_string(),
@@ -253,14 +291,18 @@ pub fn render_tui(f: &mut Frame, app: &App, area: Rect) {
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
-            Constraint::Length(3),
+            Constraint::Length(4),
             Constraint::Min(KEY_HEIGHT + 2),
             Constraint::Length(4),
         ])
         .split(area);
 
-    render_statistics(f, chunks[0]);
-    render_keyboard(f, app, chunks[1]);
+    render_statistics(f, app, chunks[0]);
+    match app.keyboard.panel {
+        KeyboardPanel::Heatmap => render_keyboard(f, app, chunks[1]),
+        KeyboardPanel::FingerLoad => render_analysis(f, app, chunks[1]),
+        KeyboardPanel::Combos => render_combos(f, app, chunks[1]),
+    }
     render_footer(f, app, chunks[2]);
 
     if app.keyboard.show_layout_popup {
@@ -277,8 +319,17 @@ fn render_layout_popup(f: &mut Frame, app: &App, area: Rect) {
 
     f.render_widget(Clear, popup_area);
 
+    let skipped = layouts::registry().errors.len();
+    let title = if skipped > 0 {
+        format!(
+            " Select Layout ({} layout file(s) skipped, see log) ",
+            skipped
+        )
+    } else {
+        " Select Layout ".to_string()
+    };
     let block = Block::default()
-        .title(" Select Layout ")
+        .title(title)
         .borders(Borders::ALL)
         .style(Style::default().bg(Color::Black));
     f.render_widget(block.clone(), popup_area);
@@ -348,7 +399,78 @@ fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
         .split(popup_layout[1])[1]
 }
 
-fn render_statistics(f: &mut Frame, area: Rect) {
+/// Modifier key names as they appear in heatmaps.
+pub const MODIFIER_KEYS: [&str; 14] = [
+    "SHIFT",
+    "LEFTSHIFT",
+    "RIGHTSHIFT",
+    "CONTROL",
+    "LEFTCONTROL",
+    "RIGHTCONTROL",
+    "ALT",
+    "LEFTALT",
+    "RIGHTALT",
+    "ALTGR",
+    "META",
+    "LEFTWINDOWS",
+    "RIGHTWINDOWS",
+    "CAPSLOCK",
+];
+
+/// Summary of a heatmap for the header's second line.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct KeySummary {
+    pub total: u64,
+    /// Most pressed keys, most first.
+    pub top: Vec<(String, u64)>,
+    pub distinct: usize,
+    /// Share of presses on modifier keys, 0-1.
+    pub modifier_share: f64,
+}
+
+pub fn summarize(data: &HashMap<String, u64>, top_n: usize) -> KeySummary {
+    let total: u64 = data.values().sum();
+    if total == 0 {
+        return KeySummary::default();
+    }
+    let mut keys: Vec<(String, u64)> = data
+        .iter()
+        .filter(|(_, count)| **count > 0)
+        .map(|(k, v)| (k.clone(), *v))
+        .collect();
+    keys.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    let modifiers: u64 = MODIFIER_KEYS.iter().filter_map(|k| data.get(*k)).sum();
+
+    KeySummary {
+        total,
+        distinct: keys.len(),
+        top: keys.into_iter().take(top_n).collect(),
+        modifier_share: modifiers as f64 / total as f64,
+    }
+}
+
+pub(crate) fn group_thousands(n: u64) -> String {
+    let digits = n.to_string();
+    let mut out = String::with_capacity(digits.len() + digits.len() / 3);
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            out.push(',');
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// Period heatmap plus the keys typed in this session.
+fn combined_heatmap(app: &App) -> HashMap<String, u64> {
+    let mut data = app.keyboard.heatmap_data.clone();
+    for (k, v) in &app.keyboard.session_heatmap {
+        *data.entry(k.clone()).or_insert(0) += v;
+    }
+    data
+}
+
+fn render_statistics(f: &mut Frame, app: &App, area: Rect) {
     let block = Block::default().borders(Borders::BOTTOM);
     f.render_widget(block, area);
 
@@ -356,6 +478,10 @@ fn render_statistics(f: &mut Frame, area: Rect) {
         vertical: 0,
         horizontal: 1,
     });
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(2), Constraint::Length(1)])
+        .split(inner);
 
     let chunks = Layout::default()
         .direction(Direction::Horizontal)
@@ -365,13 +491,22 @@ fn render_statistics(f: &mut Frame, area: Rect) {
             Constraint::Percentage(25),
             Constraint::Percentage(25),
         ])
-        .split(inner);
-
+        .split(rows[0]);
+
+    let counts = app.keyboard.counts.as_ref();
+    let from_db =
+        |get: fn(&KeyCounts) -> u64| counts.map_or("-".to_string(), |c| group_thousands(get(c)));
+    // Unpulsed keys only exist once the realtime feed has sent something.
+    let unpulsed = if app.kinetic_stats.last_update.is_some() {
+        group_thousands(app.kinetic_stats.unpulsed_keys.max(0) as u64)
+    } else {
+        "-".to_string()
+    };
     let stats = [
-        ("Today", "13,831"),
-        ("Yesterday", "6,283"),
-        ("Unpulsed", "2,284"),
-        ("All time", "3,186,900"),
+        ("Today", from_db(|c| c.today)),
+        ("Yesterday", from_db(|c| c.yesterday)),
+        ("Unpulsed", unpulsed),
+        ("All time", from_db(|c| c.all_time)),
     ];
 
     for (i, (label, value)) in stats.iter().enumerate() {
@@ -383,7 +518,7 @@ fn render_statistics(f: &mut Frame, area: Rect) {
                     .add_modifier(Modifier::BOLD),
             )),
             Line::from(Span::styled(
-                *value,
+                value.as_str(),
                 Style::default()
                     .fg(Color::White)
                     .add_modifier(Modifier::BOLD),
@@ -392,14 +527,40 @@ fn render_statistics(f: &mut Frame, area: Rect) {
         let p = Paragraph::new(text).alignment(Alignment::Center);
         f.render_widget(p, chunks[i]);
     }
+
+    let summary = summarize(&combined_heatmap(app), 5);
+    let line = if summary.total == 0 {
+        Line::from(Span::styled(
+            "No key data for this period",
+            Style::default().fg(Color::DarkGray),
+        ))
+    } else {
+        let top: Vec<String> = summary
+            .top
+            .iter()
+            .map(|(key, count)| {
+                format!(
+                    "{} {:.1}%",
+                    key,
+                    *count as f64 / summary.total as f64 * 100.0
+                )
+            })
+            .collect();
+        Line::from(vec![
+            Span::styled("Top: ", Style::default().fg(Color::Cyan)),
+            Span::raw(top.join(", ")),
+            Span::styled(" | Distinct: ", Style::default().fg(Color::Cyan)),
+            Span::raw(summary.distinct.to_string()),
+            Span::styled(" | Modifiers: ", Style::default().fg(Color::Cyan)),
+            Span::raw(format!("{:.1}%", summary.modifier_share * 100.0)),
+        ])
+    };
+    f.render_widget(Paragraph::new(line).alignment(Alignment::Center), rows[1]);
 }
 
 fn render_keyboard(f: &mut Frame, app: &App, area: Rect) {
     // Combine API data with session data
-    let mut data = app.keyboard.heatmap_data.clone();
-    for (k, v) in &app.keyboard.session_heatmap {
-        *data.entry(k.clone()).or_insert(0) += v;
-    }
+    let data = combined_heatmap(app);
 
     if data.is_empty() {
         let p = Paragraph::new("No data available for this period")
@@ -409,12 +570,215 @@ fn render_keyboard(f: &mut Frame, app: &App, area: Rect) {
         return;
     }
 
+    let keys = app.keyboard.layout.get_keys(app.keyboard.geometry);
+    render_heatmap(f, &keys, &data, area);
+
+    // Keys that don't fit are skipped; say so rather than show half a board.
+    let width = keys.iter().map(|k| k.x + k.width).max().unwrap_or(0);
+    let height = keys.iter().map(|k| k.y + KEY_HEIGHT).max().unwrap_or(0);
+    if width > area.width || height > area.height {
+        let hint = Paragraph::new(format!(
+            "Board needs {}x{}; enlarge the window or hide the function row (f) or numpad (p)",
+            width, height
+        ))
+        .style(Style::default().fg(Color::Yellow))
+        .alignment(Alignment::Center);
+        let line = Rect::new(area.x, area.bottom().saturating_sub(1), area.width, 1);
+        f.render_widget(Clear, line);
+        f.render_widget(hint, line);
+    }
+}
+
+/// Finger and hand load of the selected layout next to the baseline, both
+/// fed the same counts.
+fn render_analysis(f: &mut Frame, app: &App, area: Rect) {
+    let block = Block::default().borders(Borders::ALL).title(format!(
+        " Finger Load: {} vs {} (b: make baseline) ",
+        app.keyboard.layout, app.keyboard.baseline
+    ));
+    let data = combined_heatmap(app);
+    if data.is_empty() {
+        let p = Paragraph::new("No data available for this period")
+            .block(block)
+            .alignment(Alignment::Center);
+        f.render_widget(p, area);
+        return;
+    }
+
+    let geometry = app.keyboard.geometry;
+    let current = analysis::analyze(&app.keyboard.layout.get_keys(geometry), &data);
+    let baseline = analysis::analyze(&app.keyboard.baseline.get_keys(geometry), &data);
+
+    const BAR_WIDTH: usize = 24;
+    let label = Style::default().fg(Color::Cyan);
+    let dim = Style::default().fg(Color::DarkGray);
+    let max_share = current
+        .fingers
+        .iter()
+        .map(|(finger, _)| current.finger_share(*finger))
+        .fold(0.0, f64::max);
+
+    let mut lines = vec![Line::from(Span::styled(
+        format!(
+            "{:<10}{:<width$}{:>9}{:>9}",
+            "",
+            "",
+            "Layout",
+            "Base",
+            width = BAR_WIDTH + 1
+        ),
+        dim,
+    ))];
+    for (finger, _) in &current.fingers {
+        let share = current.finger_share(*finger);
+        let filled = if max_share > 0.0 {
+            (share / max_share * BAR_WIDTH as f64).round() as usize
+        } else {
+            0
+        };
+        lines.push(Line::from(vec![
+            Span::styled(format!("{:<10}", finger.label()), label),
+            Span::styled(
+                format!("{:<width$} ", "█".repeat(filled), width = BAR_WIDTH),
+                Style::default().fg(Color::Yellow),
+            ),
+            Span::raw(format!("{:>8.1}%", share * 100.0)),
+            Span::styled(
+                format!("{:>8.1}%", baseline.finger_share(*finger) * 100.0),
+                dim,
+            ),
+        ]));
+    }
+    lines.push(Line::from(""));
+
+    let summary = [
+        ("Left hand", current.left_hand, baseline.left_hand),
+        ("Home row", current.home_row, baseline.home_row),
+        ("Row jumps", current.row_jumps, baseline.row_jumps),
+    ];
+    for (name, value, base) in summary {
+        lines.push(Line::from(vec![
+            Span::styled(format!("{:<width$}", name, width = BAR_WIDTH + 11), label),
+            Span::raw(format!("{:>8.1}%", value * 100.0)),
+            Span::styled(format!("{:>8.1}%", base * 100.0), dim),
+        ]));
+    }
+    lines.push(Line::from(vec![
+        Span::styled(
+            format!("{:<width$}", "Effort", width = BAR_WIDTH + 11),
+            label,
+        ),
+        Span::raw(format!("{:>9.2}", current.effort)),
+        Span::styled(format!("{:>9.2}", baseline.effort), dim),
+    ]));
+    lines.push(Line::from(Span::styled(
+        "Hands exclude thumbs; row jumps are estimated from key frequencies; lower effort is better.",
+        dim,
+    )));
+
+    f.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+/// Bigrams and shortcuts typed this session, from the realtime feed and
+/// keys pressed in the TUI.
+fn render_combos(f: &mut Frame, app: &App, area: Rect) {
+    let combos = &app.keyboard.combos;
+    let block = Block::default().borders(Borders::ALL).title(format!(
+        " Combos this session: {} presses in order, {} too fast to order ",
+        group_thousands(combos.ordered),
+        group_thousands(combos.unordered)
+    ));
+    if combos.ordered == 0 {
+        let p = Paragraph::new(
+            "No key sequences yet. They come from the realtime feed and keys typed here.",
+        )
+        .block(block)
+        .alignment(Alignment::Center);
+        f.render_widget(p, area);
+        return;
+    }
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage(30),
+            Constraint::Percentage(40),
+            Constraint::Percentage(30),
+        ])
+        .split(inner);
+    let rows = inner.height.saturating_sub(1) as usize;
+    let label = Style::default().fg(Color::Cyan);
+    let dim = Style::default().fg(Color::DarkGray);
+    let total = combos.total_bigrams();
+    let percent = |count: u64| count as f64 / total.max(1) as f64 * 100.0;
+
+    let mut bigrams = vec![Line::from(Span::styled("Top bigrams", label))];
+    bigrams.extend(combos.top_bigrams(rows).into_iter().map(|((a, b), count)| {
+        Line::from(vec![
+            Span::raw(format!("{:<16}", format!("{} {}", a, b))),
+            Span::styled(format!("{:>6} {:>5.1}%", count, percent(count)), dim),
+        ])
+    }));
+
+    let keys = app.keyboard.layout.get_keys(app.keyboard.geometry);
+    let same_finger = combos.same_finger(&keys, rows);
+    let same_total: u64 = combos
+        .same_finger(&keys, usize::MAX)
+        .iter()
+        .map(|s| s.2)
+        .sum();
+    let mut same = vec![Line::from(Span::styled(
+        format!(
+            "Same finger on {}: {:.1}%",
+            app.keyboard.layout,
+            percent(same_total)
+        ),
+        label,
+    ))];
+    same.extend(same_finger.into_iter().map(|((a, b), finger, count)| {
+        Line::from(vec![
+            Span::raw(format!("{:<16}", format!("{} {}", a, b))),
+            Span::styled(format!("{:<12}{:>6}", finger.label(), count), dim),
+        ])
+    }));
+
+    let mut shortcuts = vec![Line::from(Span::styled("Shortcuts", label))];
+    shortcuts.extend(combos.top_shortcuts(rows).into_iter().map(|(name, count)| {
+        Line::from(vec![
+            Span::raw(format!("{:<16}", name)),
+            Span::styled(format!("{:>6}", count), dim),
+        ])
+    }));
+
+    for (lines, column) in [bigrams, same, shortcuts].into_iter().zip(columns.iter()) {
+        f.render_widget(Paragraph::new(lines), *column);
+    }
+}
+
+/// The count `data` has for `key`: by key name, then by label, then for
+/// both sides of a modifier as the database records them.
+pub(crate) fn key_count(data: &HashMap<String, u64>, key: &KeyParams) -> u64 {
+    data.get(&key.json_key)
+        .or_else(|| data.get(&key.label.to_uppercase()))
+        .or_else(|| crate::key_mapping::sideless(&key.json_key).and_then(|k| data.get(k)))
+        .copied()
+        .unwrap_or(0)
+}
+
+/// Draws `keys` with each key colored by its count in `data`.
+pub(crate) fn render_heatmap(
+    f: &mut Frame,
+    keys: &[KeyParams],
+    data: &HashMap<String, u64>,
+    area: Rect,
+) {
     let max_count = data.values().max().copied().unwrap_or(1);
 
     // Center the keyboard in the available area
-    // Keyboard size is roughly 74x15 (60 main + 2 gap + 12 nav)
-    let kbd_width = 74;
-    let kbd_height = 15;
+    let kbd_width = keys.iter().map(|k| k.x + k.width).max().unwrap_or(0);
+    let kbd_height = keys.iter().map(|k| k.y + KEY_HEIGHT).max().unwrap_or(0);
 
     let x_offset = if area.width > kbd_width {
         area.x + (area.width - kbd_width) / 2
@@ -428,7 +792,7 @@ fn render_keyboard(f: &mut Frame, app: &App, area: Rect) {
         area.y
     };
 
-    for key in app.keyboard.layout.get_keys() {
+    for key in keys {
         // Calculate absolute position
         let x = x_offset + key.x;
         let y = y_offset + key.y;
@@ -441,12 +805,7 @@ fn render_keyboard(f: &mut Frame, app: &App, area: Rect) {
         let rect = Rect::new(x, y, key.width, KEY_HEIGHT);
 
         // Determine color
-        let k1 = &key.json_key;
-        let k2 = &key.label.to_uppercase();
-
-        // Try exact match, then label match
-        let count = data.get(k1).or_else(|| data.get(k2)).copied().unwrap_or(0);
-
+        let count = key_count(data, key);
         let bg_color = get_color(count, max_count);
 
         // Determine text color for contrast
@@ -460,7 +819,7 @@ fn render_keyboard(f: &mut Frame, app: &App, area: Rect) {
             .borders(Borders::ALL)
             .style(Style::default().bg(bg_color).fg(fg_color));
 
-        let p = Paragraph::new(key.label)
+        let p = Paragraph::new(key.label.as_str())
             .block(block)
             .alignment(Alignment::Center);
 
@@ -488,8 +847,10 @@ fn render_footer(f: &mut Frame, app: &App, area: Rect) {
         TimePeriod::Custom => "Custom",
     };
     let layout_text = format!(
-        " Layout: {} (k) | Period: {} (h/l | /: Custom)",
-        app.keyboard.layout, period_str
+        " Layout: {} (k) | Board: {} (g, f, p) | Analysis (a) | Combos (c) | Period: {} (h/l | /: Custom)",
+        app.keyboard.layout,
+        app.keyboard.geometry.name(),
+        period_str
     );
     let p_controls = Paragraph::new(layout_text)
         .block(Block::default().borders(Borders::TOP))
@@ -546,7 +907,7 @@ fn render_footer(f: &mut Frame, app: &App, area: Rect) {
     }
 }
 
-fn get_color(count: u64, max: u64) -> Color {
+pub(crate) fn get_color(count: u64, max: u64) -> Color {
     if max == 0 || count == 0 {
         return Color::Rgb(20, 20, 50); // Base Dark Blue for zero/empty
     }
@@ -588,3 +949,40 @@ fn get_color(count: u64, max: u64) -> Color {
 
     Color::Rgb(r, g, b)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_summarizes_top_keys_and_modifiers() {
+        let data: HashMap<String, u64> = [
+            ("E", 50),
+            ("SPACE", 30),
+            ("LEFTSHIFT", 15),
+            ("RIGHTSHIFT", 5),
+            ("Q", 0),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v))
+        .collect();
+
+        let summary = summarize(&data, 2);
+        assert_eq!(summary.total, 100);
+        assert_eq!(
+            summary.top,
+            vec![("E".to_string(), 50), ("SPACE".to_string(), 30)]
+        );
+        assert_eq!(summary.distinct, 4);
+        assert!((summary.modifier_share - 0.2).abs() < 1e-9);
+        assert_eq!(summarize(&HashMap::new(), 5), KeySummary::default());
+    }
+
+    #[test]
+    fn test_groups_thousands() {
+        assert_eq!(group_thousands(0), "0");
+        assert_eq!(group_thousands(999), "999");
+        assert_eq!(group_thousands(13831), "13,831");
+        assert_eq!(group_thousands(3186900), "3,186,900");
+    }
+}
@@ -0,0 +1,354 @@
+use crate::commands::TuiPage;
+use crate::commands::keyboard::analysis::{self, LoadReport, MM_PER_UNIT};
+use crate::commands::keyboard::geometry::Geometry;
+use crate::commands::keyboard::layouts::KeyboardLayout;
+use crate::tui::app::App;
+use crate::tui::period_utils::get_display_period;
+use crate::tui::table_utils::{handle_table_nav, render_scrollbar};
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    Frame,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    widgets::{Block, Borders, Cell, Paragraph, Row, Table},
+};
+use std::collections::HashMap;
+
+inventory::submit! {
+    TuiPage {
+        title: "Layout Lab",
+        category: "Input",
+        render: render_tui,
+        handle_key,
+        handle_mouse: crate::commands::default_handle_mouse,
+        priority: 13,
+    }
+}
+
+/// Candidates typing less than this share of the current layout's counts
+/// (missing letters) are listed last.
+const MIN_COVERAGE: f64 = 0.95;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LabSort {
+    #[default]
+    Score,
+    Travel,
+    SameFinger,
+    Alternation,
+    Effort,
+}
+
+impl LabSort {
+    fn next(self) -> Self {
+        match self {
+            LabSort::Score => LabSort::Travel,
+            LabSort::Travel => LabSort::SameFinger,
+            LabSort::SameFinger => LabSort::Alternation,
+            LabSort::Alternation => LabSort::Effort,
+            LabSort::Effort => LabSort::Score,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            LabSort::Score => "Score",
+            LabSort::Travel => "Travel",
+            LabSort::SameFinger => "Same finger",
+            LabSort::Alternation => "Alternation",
+            LabSort::Effort => "Effort",
+        }
+    }
+
+    /// Lower is better, except for alternation.
+    fn key(self, row: &LabRow) -> f64 {
+        match self {
+            LabSort::Score => row.score,
+            LabSort::Travel => row.report.travel,
+            LabSort::SameFinger => row.report.same_finger,
+            LabSort::Alternation => -row.report.alternation,
+            LabSort::Effort => row.report.effort,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct LabRow {
+    pub layout: KeyboardLayout,
+    pub report: LoadReport,
+    /// Share of the current layout's counts the candidate has keys for.
+    pub coverage: f64,
+    /// Mean of travel, same-finger use, non-alternation and effort relative
+    /// to the current layout, times 100. Lower is better.
+    pub score: f64,
+}
+
+/// Every layout fed the counts typed on `current`, best first by `sort`.
+pub fn rank(
+    counts: &HashMap<String, u64>,
+    current: KeyboardLayout,
+    geometry: Geometry,
+    sort: LabSort,
+) -> Vec<LabRow> {
+    let current_keys = current.get_keys(geometry);
+    let baseline = analysis::analyze(&current_keys, counts);
+
+    let mut rows: Vec<LabRow> = KeyboardLayout::all()
+        .into_iter()
+        .map(|layout| {
+            let keys = layout.get_keys(geometry);
+            let (remapped, unmapped) = analysis::remap(counts, &current_keys, &keys);
+            let report = analysis::analyze(&keys, &remapped);
+            let typed = report.total + unmapped;
+            let coverage = if typed == 0 {
+                0.0
+            } else {
+                report.total as f64 / typed as f64
+            };
+            let score = score(&report, &baseline);
+            LabRow {
+                layout,
+                report,
+                coverage,
+                score,
+            }
+        })
+        .collect();
+    sort_rows(&mut rows, sort);
+    rows
+}
+
+fn sort_rows(rows: &mut [LabRow], sort: LabSort) {
+    rows.sort_by(|a, b| {
+        let partial = |r: &LabRow| r.coverage < MIN_COVERAGE;
+        partial(a)
+            .cmp(&partial(b))
+            .then(sort.key(a).total_cmp(&sort.key(b)))
+    });
+}
+
+fn score(report: &LoadReport, baseline: &LoadReport) -> f64 {
+    let ratio = |value: f64, base: f64| if base > 0.0 { value / base } else { 1.0 };
+    let parts = [
+        ratio(report.travel, baseline.travel),
+        ratio(report.same_finger, baseline.same_finger),
+        ratio(1.0 - report.alternation, 1.0 - baseline.alternation),
+        ratio(report.effort, baseline.effort),
+    ];
+    parts.iter().sum::<f64>() / parts.len() as f64 * 100.0
+}
+
+/// Rankings are recomputed only when their inputs change.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LabCacheKey {
+    current: KeyboardLayout,
+    geometry: Geometry,
+    keys: usize,
+    total: u64,
+    sort: LabSort,
+}
+
+fn ranking(app: &App) -> Vec<LabRow> {
+    let counts = &app.keyboard.heatmap_data;
+    let key = LabCacheKey {
+        current: app.keyboard.baseline,
+        geometry: app.keyboard.geometry,
+        keys: counts.len(),
+        total: counts.values().sum(),
+        sort: app.layout_lab.sort,
+    };
+    let mut cache = app.layout_lab.cache.borrow_mut();
+    if let Some((cached_key, rows)) = cache.as_ref()
+        && *cached_key == key
+    {
+        return rows.clone();
+    }
+    let rows = rank(counts, key.current, key.geometry, key.sort);
+    *cache = Some((key, rows.clone()));
+    rows
+}
+
+fn handle_key(app: &mut App, key: KeyEvent) -> bool {
+    match key.code {
+        KeyCode::Char('s') => {
+            app.layout_lab.sort = app.layout_lab.sort.next();
+            true
+        }
+        KeyCode::Enter => {
+            let selected = app.layout_lab.table_state.borrow().selected();
+            if let Some(row) = selected.and_then(|i| ranking(app).into_iter().nth(i)) {
+                app.keyboard.layout = row.layout;
+            }
+            true
+        }
+        _ => {
+            let len = KeyboardLayout::all().len();
+            handle_table_nav(&mut app.layout_lab.table_state.borrow_mut(), key.code, len)
+        }
+    }
+}
+
+fn render_tui(f: &mut Frame, app: &App, area: Rect) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(5), Constraint::Length(2)])
+        .split(area);
+
+    let current = app.keyboard.baseline;
+    let title = format!(
+        " Layout Lab: {} keys ({}) typed on {}, {} board (s: Sort [{}], Enter: show on Keyboard) ",
+        crate::commands::keyboard::group_thousands(app.keyboard.heatmap_data.values().sum()),
+        get_display_period(app.dashboard_period),
+        current,
+        app.keyboard.geometry.name(),
+        app.layout_lab.sort.label(),
+    );
+    let block = Block::default().borders(Borders::ALL).title(title);
+
+    if app.keyboard.heatmap_data.is_empty() {
+        let p = Paragraph::new(
+            "No key counts loaded yet. The lab uses the Keyboard page's period and database counts.",
+        )
+        .style(Style::default().fg(Color::DarkGray))
+        .block(block);
+        f.render_widget(p, chunks[0]);
+        render_help(f, chunks[1]);
+        return;
+    }
+
+    let rows_data = ranking(app);
+    let rows: Vec<Row> = rows_data
+        .iter()
+        .enumerate()
+        .map(|(i, row)| {
+            let marker = if row.layout == current { "*" } else { "" };
+            let style = if row.layout == current {
+                Style::default().fg(Color::Cyan)
+            } else if row.coverage < MIN_COVERAGE {
+                Style::default().fg(Color::DarkGray)
+            } else {
+                Style::default()
+            };
+            Row::new(vec![
+                format!("{}{}", i + 1, marker),
+                row.layout.to_string(),
+                format!("{:.0}", row.score),
+                format!("{:.1}", row.report.travel * MM_PER_UNIT),
+                format!("{:.1}%", row.report.same_finger * 100.0),
+                format!("{:.1}%", row.report.alternation * 100.0),
+                format!("{:.2}", row.report.effort),
+                format!("{:.0}%", row.coverage * 100.0),
+            ])
+            .style(style)
+        })
+        .collect();
+
+    let header_style = Style::default()
+        .fg(Color::Yellow)
+        .add_modifier(Modifier::BOLD);
+    let header = Row::new(
+        [
+            "#",
+            "Layout",
+            "Score",
+            "mm/key",
+            "Same finger",
+            "Alternation",
+            "Effort",
+            "Coverage",
+        ]
+        .map(|h| Cell::from(h).style(header_style)),
+    )
+    .bottom_margin(1);
+    let widths = [
+        Constraint::Length(4),
+        Constraint::Min(20),
+        Constraint::Length(6),
+        Constraint::Length(7),
+        Constraint::Length(12),
+        Constraint::Length(12),
+        Constraint::Length(7),
+        Constraint::Length(9),
+    ];
+    let table = Table::new(rows, widths)
+        .header(header)
+        .block(block)
+        .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+        .highlight_symbol(">> ");
+
+    let mut state = app.layout_lab.table_state.borrow_mut();
+    f.render_stateful_widget(table, chunks[0], &mut state);
+    render_scrollbar(f, app, chunks[0], rows_data.len(), &mut state);
+    render_help(f, chunks[1]);
+}
+
+fn render_help(f: &mut Frame, area: Rect) {
+    let text = "Score: travel, same-finger use, lack of alternation and effort relative to your \
+                layout (*), 100 = same, lower is better. Same finger and alternation are \
+                estimates from key frequencies. Your layout is the Keyboard page's baseline (a, b).";
+    let p = Paragraph::new(text)
+        .style(Style::default().fg(Color::DarkGray))
+        .wrap(ratatui::widgets::Wrap { trim: true });
+    f.render_widget(p, area);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ranks_against_the_current_layout() {
+        let counts: HashMap<String, u64> = [
+            ("E", 130),
+            ("T", 90),
+            ("A", 80),
+            ("O", 75),
+            ("I", 70),
+            ("N", 67),
+            ("S", 63),
+            ("H", 61),
+            ("R", 60),
+            ("D", 43),
+            ("L", 40),
+            ("U", 28),
+            ("C", 28),
+            ("M", 24),
+            ("W", 24),
+            ("SPACE", 180),
+        ]
+        .into_iter()
+        .map(|(k, c)| (k.to_string(), c))
+        .collect();
+
+        let rows = rank(
+            &counts,
+            KeyboardLayout::Qwerty,
+            Geometry::default(),
+            LabSort::Score,
+        );
+        assert_eq!(rows.len(), KeyboardLayout::all().len());
+        let find = |layout| rows.iter().find(|r| r.layout == layout).unwrap();
+
+        let qwerty = find(KeyboardLayout::Qwerty);
+        assert!((qwerty.score - 100.0).abs() < 1e-9);
+        assert_eq!(qwerty.coverage, 1.0);
+        let colemak = find(KeyboardLayout::Colemak);
+        assert!(colemak.score < 100.0);
+        assert!(colemak.report.travel < qwerty.report.travel);
+
+        // Layouts without Latin letters can't type these counts.
+        let greek = find(KeyboardLayout::Greek);
+        assert!(greek.coverage < MIN_COVERAGE);
+        let first_partial = rows.iter().position(|r| r.coverage < MIN_COVERAGE).unwrap();
+        assert!(
+            rows[first_partial..]
+                .iter()
+                .all(|r| r.coverage < MIN_COVERAGE)
+        );
+        assert!(
+            rows[..first_partial]
+                .windows(2)
+                .all(|w| w[0].score <= w[1].score)
+        );
+    }
+}
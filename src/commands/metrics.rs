@@ -0,0 +1,496 @@
+use crate::client::{UserResponse, WhatpulseClient};
+use crate::commands::calorimetry::{MouseSwitchProfile, SwitchProfile};
+use crate::db::{AppStats, Database, NetworkStats};
+use crate::http;
+use crate::realtime::{RealtimeData, RealtimeEvent, RealtimeHub};
+use crate::tui::app::KineticStats;
+use anyhow::{Context, Result};
+use std::fmt::Write as _;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::net::{TcpListener, TcpStream};
+
+/// Everything exposed on `/metrics`. Refreshed by the scrape loop and the
+/// realtime WebSocket, read by the HTTP handler.
+#[derive(Debug, Default)]
+pub struct MetricsSnapshot {
+    pub user: Option<UserResponse>,
+    pub apps: Vec<AppStats>,
+    pub network: Vec<NetworkStats>,
+    pub kinetic: KineticStats,
+    pub realtime: Option<RealtimeData>,
+    pub realtime_connected: bool,
+    pub realtime_reconnects: u32,
+    pub refresh_errors: u64,
+    pub last_refresh: Option<chrono::DateTime<chrono::Local>>,
+}
+
+type SharedSnapshot = Arc<Mutex<MetricsSnapshot>>;
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+struct Exposition {
+    out: String,
+}
+
+impl Exposition {
+    fn family(&mut self, name: &str, kind: &str, help: &str) {
+        let _ = writeln!(self.out, "# HELP {} {}", name, help);
+        let _ = writeln!(self.out, "# TYPE {} {}", name, kind);
+    }
+
+    fn sample(&mut self, name: &str, labels: &[(&str, &str)], value: f64) {
+        if labels.is_empty() {
+            let _ = writeln!(self.out, "{} {}", name, value);
+        } else {
+            let labels: Vec<String> = labels
+                .iter()
+                .map(|(k, v)| format!("{}=\"{}\"", k, escape_label(v)))
+                .collect();
+            let _ = writeln!(self.out, "{}{{{}}} {}", name, labels.join(","), value);
+        }
+    }
+
+    fn single(&mut self, name: &str, kind: &str, help: &str, value: f64) {
+        self.family(name, kind, help);
+        self.sample(name, &[], value);
+    }
+}
+
+/// Renders the snapshot in the Prometheus text exposition format (0.0.4).
+pub fn render_metrics(snapshot: &MetricsSnapshot) -> String {
+    let mut e = Exposition { out: String::new() };
+
+    if let Some(user) = &snapshot.user {
+        let t = &user.totals;
+        e.single(
+            "wtfpulse_keys_total",
+            "counter",
+            "Lifetime keys pulsed.",
+            t.keys.unwrap_or(0) as f64,
+        );
+        e.single(
+            "wtfpulse_clicks_total",
+            "counter",
+            "Lifetime clicks pulsed.",
+            t.clicks.unwrap_or(0) as f64,
+        );
+        e.single(
+            "wtfpulse_scrolls_total",
+            "counter",
+            "Lifetime scrolls pulsed.",
+            t.scrolls as f64,
+        );
+        e.single(
+            "wtfpulse_download_megabytes_total",
+            "counter",
+            "Lifetime download in MB.",
+            t.download_mb.unwrap_or(0.0),
+        );
+        e.single(
+            "wtfpulse_upload_megabytes_total",
+            "counter",
+            "Lifetime upload in MB.",
+            t.upload_mb.unwrap_or(0.0),
+        );
+        e.single(
+            "wtfpulse_uptime_seconds_total",
+            "counter",
+            "Lifetime uptime in seconds.",
+            t.uptime_seconds.unwrap_or(0) as f64,
+        );
+        e.single(
+            "wtfpulse_distance_miles_total",
+            "counter",
+            "Lifetime mouse distance in miles.",
+            t.distance_miles.unwrap_or(0.0),
+        );
+        if let Some(ranks) = &user.ranks {
+            e.family("wtfpulse_rank", "gauge", "Global rank per statistic.");
+            for (metric, rank) in [
+                ("keys", ranks.keys),
+                ("clicks", ranks.clicks),
+                ("download", ranks.download),
+                ("upload", ranks.upload),
+                ("uptime", ranks.uptime),
+                ("scrolls", ranks.scrolls),
+                ("distance", ranks.distance),
+            ] {
+                e.sample("wtfpulse_rank", &[("metric", metric)], rank as f64);
+            }
+        }
+    }
+
+    let k = &snapshot.kinetic;
+    e.single(
+        "wtfpulse_realtime_connected",
+        "gauge",
+        "1 if the client WebSocket is connected.",
+        if snapshot.realtime_connected {
+            1.0
+        } else {
+            0.0
+        },
+    );
+    e.single(
+        "wtfpulse_realtime_reconnects_total",
+        "counter",
+        "Reconnect attempts to the client WebSocket.",
+        snapshot.realtime_reconnects as f64,
+    );
+    e.single(
+        "wtfpulse_keys_per_second",
+        "gauge",
+        "Current typing rate reported by the client.",
+        k.keys_per_second,
+    );
+    e.single(
+        "wtfpulse_unpulsed_keys",
+        "gauge",
+        "Keys recorded since the last pulse.",
+        k.unpulsed_keys as f64,
+    );
+    e.single(
+        "wtfpulse_unpulsed_clicks",
+        "gauge",
+        "Clicks recorded since the last pulse.",
+        k.unpulsed_clicks as f64,
+    );
+    e.single(
+        "wtfpulse_unpulsed_scrolls",
+        "gauge",
+        "Scrolls recorded since the last pulse.",
+        k.unpulsed_scrolls as f64,
+    );
+    if let Some(rt) = &snapshot.realtime {
+        for (name, help, value) in [
+            (
+                "wtfpulse_clicks_per_second",
+                "Current click rate reported by the client.",
+                rt.clicks_per_second,
+            ),
+            (
+                "wtfpulse_download_bytes_per_second",
+                "Current download rate reported by the client.",
+                rt.download_bytes_per_second,
+            ),
+            (
+                "wtfpulse_upload_bytes_per_second",
+                "Current upload rate reported by the client.",
+                rt.upload_bytes_per_second,
+            ),
+            (
+                "wtfpulse_unpulsed_download_bytes",
+                "Bytes downloaded since the last pulse.",
+                rt.unpulsed_download as f64,
+            ),
+            (
+                "wtfpulse_unpulsed_upload_bytes",
+                "Bytes uploaded since the last pulse.",
+                rt.unpulsed_upload as f64,
+            ),
+            (
+                "wtfpulse_unpulsed_uptime_seconds",
+                "Uptime since the last pulse.",
+                rt.unpulsed_uptime as f64,
+            ),
+            (
+                "wtfpulse_unpulsed_distance_miles",
+                "Mouse distance since the last pulse.",
+                rt.unpulsed_distance_miles,
+            ),
+        ] {
+            e.single(name, "gauge", help, value);
+        }
+    }
+    e.single(
+        "wtfpulse_kinetic_power_watts",
+        "gauge",
+        "Instantaneous finger power output.",
+        k.current_power_watts,
+    );
+    e.single(
+        "wtfpulse_kinetic_work_joules_total",
+        "counter",
+        "Work done pressing keys since the exporter started.",
+        k.accumulated_work_joules,
+    );
+    e.single(
+        "wtfpulse_kinetic_click_work_joules_total",
+        "counter",
+        "Work done clicking mouse buttons since the exporter started.",
+        k.click_work_joules,
+    );
+
+    if !snapshot.apps.is_empty() {
+        for (name, help, value) in [
+            (
+                "wtfpulse_app_keys_total",
+                "Keys per application.",
+                (|a: &AppStats| a.keys as f64) as fn(&AppStats) -> f64,
+            ),
+            (
+                "wtfpulse_app_clicks_total",
+                "Clicks per application.",
+                |a| a.clicks as f64,
+            ),
+            (
+                "wtfpulse_app_scrolls_total",
+                "Scrolls per application.",
+                |a| a.scrolls as f64,
+            ),
+            (
+                "wtfpulse_app_download_megabytes_total",
+                "Download per application in MB.",
+                |a| a.download_mb,
+            ),
+            (
+                "wtfpulse_app_upload_megabytes_total",
+                "Upload per application in MB.",
+                |a| a.upload_mb,
+            ),
+        ] {
+            e.family(name, "counter", help);
+            for app in &snapshot.apps {
+                e.sample(name, &[("app", &app.name)], value(app));
+            }
+        }
+    }
+
+    if !snapshot.network.is_empty() {
+        e.family(
+            "wtfpulse_interface_download_megabytes_total",
+            "counter",
+            "Download per network interface in MB.",
+        );
+        for iface in &snapshot.network {
+            e.sample(
+                "wtfpulse_interface_download_megabytes_total",
+                &[("interface", &iface.interface)],
+                iface.download_mb,
+            );
+        }
+        e.family(
+            "wtfpulse_interface_upload_megabytes_total",
+            "counter",
+            "Upload per network interface in MB.",
+        );
+        for iface in &snapshot.network {
+            e.sample(
+                "wtfpulse_interface_upload_megabytes_total",
+                &[("interface", &iface.interface)],
+                iface.upload_mb,
+            );
+        }
+    }
+
+    e.single(
+        "wtfpulse_refresh_errors_total",
+        "counter",
+        "Failed refreshes of API or database data.",
+        snapshot.refresh_errors as f64,
+    );
+    if let Some(last) = snapshot.last_refresh {
+        e.single(
+            "wtfpulse_last_refresh_timestamp_seconds",
+            "gauge",
+            "Unix time of the last scheduled refresh.",
+            last.timestamp() as f64,
+        );
+    }
+
+    e.out
+}
+
+async fn handle_connection(mut stream: TcpStream, snapshot: SharedSnapshot) -> Result<()> {
+    let request = http::read_request(&mut stream).await?;
+
+    let (status, content_type, body) = match (request.method.as_str(), request.path.as_str()) {
+        ("GET", "/metrics") => {
+            let body = match snapshot.lock() {
+                Ok(s) => render_metrics(&s),
+                Err(_) => String::new(),
+            };
+            ("200 OK", "text/plain; version=0.0.4; charset=utf-8", body)
+        }
+        ("GET", "/") => (
+            "200 OK",
+            "text/plain; charset=utf-8",
+            "wtfpulse exporter: see /metrics\n".to_string(),
+        ),
+        _ => (
+            "404 Not Found",
+            "text/plain; charset=utf-8",
+            "not found\n".to_string(),
+        ),
+    };
+
+    http::write_response(&mut stream, status, content_type, &body).await
+}
+
+async fn refresh(client: &WhatpulseClient, snapshot: &SharedSnapshot) {
+    let user = client.get_user().await;
+    let db = tokio::task::spawn_blocking(|| -> Result<(Vec<AppStats>, Vec<NetworkStats>)> {
+        let db = Database::new()?;
+        Ok((db.get_app_stats("all")?, db.get_network_stats("all")?))
+    })
+    .await
+    .map_err(anyhow::Error::from)
+    .and_then(|r| r);
+
+    let Ok(mut s) = snapshot.lock() else {
+        return;
+    };
+    match user {
+        Ok(user) => s.user = Some(user),
+        Err(e) => {
+            log::warn!("Failed to refresh user stats: {}", e);
+            s.refresh_errors += 1;
+        }
+    }
+    match db {
+        Ok((apps, network)) => {
+            s.apps = apps;
+            s.network = network;
+        }
+        Err(e) => {
+            log::warn!("Failed to refresh database stats: {}", e);
+            s.refresh_errors += 1;
+        }
+    }
+    s.last_refresh = Some(chrono::Local::now());
+}
+
+pub async fn execute(client: &WhatpulseClient, listen: &str, interval_secs: u64) -> Result<()> {
+    let listener = TcpListener::bind(listen)
+        .await
+        .with_context(|| format!("Failed to listen on {}", listen))?;
+    println!(
+        "Serving metrics on http://{}/metrics",
+        listener.local_addr()?
+    );
+    println!("Press Ctrl+C to exit.");
+
+    let snapshot: SharedSnapshot = Arc::new(Mutex::new(MetricsSnapshot::default()));
+
+    let server_snapshot = snapshot.clone();
+    tokio::spawn(async move {
+        loop {
+            match listener.accept().await {
+                Ok((stream, _)) => {
+                    let snapshot = server_snapshot.clone();
+                    tokio::spawn(async move {
+                        if let Err(e) = handle_connection(stream, snapshot).await {
+                            log::debug!("Metrics request failed: {}", e);
+                        }
+                    });
+                }
+                Err(e) => log::warn!("Accept failed: {}", e),
+            }
+        }
+    });
+
+    let hub = RealtimeHub::spawn();
+    let mut events = hub.subscribe();
+    let profile = SwitchProfile::cherry_mx_red();
+    let mouse_profile = MouseSwitchProfile::default();
+
+    let mut ticker = tokio::time::interval(Duration::from_secs(interval_secs.max(1)));
+    let shutdown = tokio::signal::ctrl_c();
+    tokio::pin!(shutdown);
+    loop {
+        tokio::select! {
+            _ = ticker.tick() => {
+                // Refreshes wait on the API, so they are raced against Ctrl+C
+                // rather than holding up shutdown.
+                tokio::select! {
+                    _ = refresh(client, &snapshot) => {}
+                    _ = &mut shutdown => {
+                        println!("\nExiting...");
+                        break;
+                    }
+                }
+            }
+            Ok(event) = events.recv() => {
+                if let Ok(mut s) = snapshot.lock() {
+                    match event {
+                        RealtimeEvent::Update(data) => {
+                            s.realtime_connected = true;
+                            s.kinetic.update(&data, &profile, &mouse_profile);
+                            s.realtime = Some(data);
+                        }
+                        RealtimeEvent::Connected => s.realtime_connected = true,
+                        RealtimeEvent::Disconnected(_) => s.realtime_connected = false,
+                        RealtimeEvent::Reconnecting { .. } => {
+                            s.realtime_reconnects = hub.state().reconnects;
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            _ = &mut shutdown => {
+                println!("\nExiting...");
+                break;
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_renders_labels_and_escapes() {
+        let snapshot = MetricsSnapshot {
+            apps: vec![AppStats {
+                name: "My \"App\"".to_string(),
+                keys: 42,
+                clicks: 1,
+                scrolls: 0,
+                download_mb: 0.5,
+                upload_mb: 0.0,
+            }],
+            realtime_connected: true,
+            ..Default::default()
+        };
+
+        let out = render_metrics(&snapshot);
+        assert!(out.contains("# TYPE wtfpulse_app_keys_total counter\n"));
+        assert!(out.contains("wtfpulse_app_keys_total{app=\"My \\\"App\\\"\"} 42\n"));
+        assert!(out.contains("wtfpulse_realtime_connected 1\n"));
+        assert!(!out.contains("wtfpulse_keys_total"));
+        assert!(!out.contains("wtfpulse_clicks_per_second"));
+
+        let snapshot = MetricsSnapshot {
+            realtime: Some(RealtimeData {
+                clicks_per_second: 0.5,
+                unpulsed_download: 2048,
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let out = render_metrics(&snapshot);
+        assert!(out.contains("wtfpulse_clicks_per_second 0.5\n"));
+        assert!(out.contains("wtfpulse_unpulsed_download_bytes 2048\n"));
+    }
+
+    #[test]
+    fn test_every_sample_has_a_type() {
+        let out = render_metrics(&MetricsSnapshot::default());
+        for line in out.lines().filter(|l| !l.starts_with('#')) {
+            let name = line.split([' ', '{']).next().unwrap();
+            assert!(
+                out.contains(&format!("# TYPE {} ", name)),
+                "missing TYPE for {}",
+                name
+            );
+        }
+    }
+}
@@ -5,19 +5,30 @@ use clap::Subcommand;
 use crossterm::event::KeyEvent;
 use ratatui::Frame;
 use ratatui::layout::Rect;
+use std::time::Duration;
 
 pub mod applications;
 pub mod calorimetry;
 pub mod computers;
+pub mod export;
+pub mod forecast;
+pub mod heatmap;
 pub mod keyboard;
+pub mod layout_lab;
+pub mod metrics;
 pub mod monitor;
 pub mod mouse;
 pub mod network;
+pub mod pulse;
 pub mod pulses;
 pub mod raw;
+pub mod rhythm;
 pub mod scroll_tower;
+pub mod serve;
 pub mod settings;
 pub mod tui;
+pub mod typing_test;
+pub mod unmapped_keys;
 pub mod uptime;
 pub mod user;
 
@@ -48,19 +59,125 @@ pub enum Commands {
     User,
     /// Fetch recent pulses
     Pulses,
+    /// Ask the local WhatPulse client to pulse now
+    Pulse,
     /// Fetch computers list
     Computers,
     /// Calculate energy expenditure
     Calorimetry,
     /// Launch the interactive dashboard
-    Tui,
+    Tui {
+        /// Feed the dashboard from a `monitor --record` file instead of the client
+        #[arg(long)]
+        replay: Option<std::path::PathBuf>,
+        /// Replay speed, e.g. 4x
+        #[arg(long, default_value = "1x", value_parser = crate::realtime::recording::parse_speed, requires = "replay")]
+        speed: f64,
+    },
     /// Fetch raw JSON from a specific path
     Raw {
         /// The API path (e.g., /api/v1/user)
         path: String,
     },
     /// Monitor real-time pulses (CLI Mode)
-    Monitor,
+    Monitor {
+        /// Output format
+        #[arg(long, value_enum, default_value_t = monitor::MonitorFormat::Human)]
+        format: monitor::MonitorFormat,
+        /// Emit at most one sample every N seconds
+        #[arg(long)]
+        interval: Option<f64>,
+        /// Stop after N seconds
+        #[arg(long)]
+        duration: Option<f64>,
+        /// Comma-separated fields to include (default: all but heatmap)
+        #[arg(long, value_enum, value_delimiter = ',')]
+        fields: Vec<monitor::MonitorField>,
+        /// Save the raw client frames to FILE (NDJSON) for later replay
+        #[arg(long, value_name = "FILE", conflicts_with = "replay")]
+        record: Option<std::path::PathBuf>,
+        /// Read frames from a recording instead of the client
+        #[arg(long, value_name = "FILE")]
+        replay: Option<std::path::PathBuf>,
+        /// Replay speed, e.g. 4x
+        #[arg(long, default_value = "1x", value_parser = crate::realtime::recording::parse_speed, requires = "replay")]
+        speed: f64,
+    },
+    /// Export aggregates to CSV / JSON / NDJSON for analysis
+    Export {
+        /// Which dataset to export
+        #[arg(value_enum)]
+        dataset: export::ExportDataset,
+        /// Time period (today, yesterday, week, month, year, all, custom:START:END)
+        #[arg(long, default_value = "all")]
+        period: String,
+        /// Output format
+        #[arg(long, value_enum, default_value_t = export::ExportFormat::Csv)]
+        format: export::ExportFormat,
+        /// Output file (defaults to stdout)
+        #[arg(long)]
+        out: Option<std::path::PathBuf>,
+        /// One row per day (per day and item for apps, network and heatmap)
+        #[arg(long)]
+        daily: bool,
+    },
+    /// Project totals forward and estimate when a milestone is reached
+    Forecast {
+        /// Which total to forecast
+        #[arg(long, value_enum, default_value_t = forecast::ForecastMetric::Keys)]
+        metric: forecast::ForecastMetric,
+        /// Milestone to estimate (defaults to the next round numbers)
+        #[arg(long)]
+        target: Option<u64>,
+        /// Model used to project daily totals
+        #[arg(long, value_enum, default_value_t = forecast::ForecastMethod::Seasonal)]
+        method: forecast::ForecastMethod,
+    },
+    /// Render the keyboard heatmap to an SVG, PNG or HTML file
+    Heatmap {
+        /// Keyboard layout (id or name, e.g. qwerty, colemak_mod_dh)
+        #[arg(long, default_value = "qwerty")]
+        layout: String,
+        /// Time period (today, yesterday, week, month, year, all, custom:START:END)
+        #[arg(long, default_value = "all")]
+        period: String,
+        /// Board shape (ANSI, ISO, JIS, ...)
+        #[arg(long, default_value = "ANSI")]
+        board: String,
+        /// Output file; the extension picks the format (.svg, .png, .html)
+        #[arg(long)]
+        out: std::path::PathBuf,
+    },
+    /// Serve wtfpulse data as a local JSON API
+    Serve {
+        /// Address to listen on
+        #[arg(long, default_value = "127.0.0.1:3499")]
+        listen: String,
+    },
+    /// Serve Prometheus / OpenMetrics metrics over HTTP
+    ServeMetrics {
+        /// Address to listen on
+        #[arg(long, default_value = "127.0.0.1:9898")]
+        listen: String,
+        /// Seconds between refreshes of API and database metrics
+        #[arg(long, default_value_t = 60)]
+        interval: u64,
+    },
+    /// Show a weekday x hour-of-day activity punch card
+    Rhythm {
+        /// Time period (today, yesterday, week, month, year, all, custom:START:END)
+        #[arg(long, default_value = "all")]
+        period: String,
+        /// Which input to chart
+        #[arg(long, value_enum, default_value_t = rhythm::RhythmMetric::Keys)]
+        metric: rhythm::RhythmMetric,
+    },
+    /// List key ids in the local database that have no key name
+    UnmappedKeys {
+        /// Time period (today, yesterday, week, month, year, all, custom:START:END)
+        #[arg(long, default_value = "all")]
+        period: String,
+    },
 }
 
 impl Commands {
@@ -68,11 +185,55 @@ impl Commands {
         match self {
             Commands::User => user::execute(client).await,
             Commands::Pulses => pulses::execute(client).await,
+            Commands::Pulse => pulse::execute(client).await,
             Commands::Computers => computers::execute(client).await,
             Commands::Calorimetry => calorimetry::execute(client).await,
-            Commands::Tui => tui::execute(client).await,
+            Commands::Tui { replay, speed } => tui::execute(client, replay, speed).await,
             Commands::Raw { path } => raw::execute(client, path).await,
-            Commands::Monitor => monitor::execute(client).await,
+            Commands::Monitor {
+                format,
+                interval,
+                duration,
+                fields,
+                record,
+                replay,
+                speed,
+            } => {
+                let options = monitor::MonitorOptions {
+                    format,
+                    interval: interval.map(Duration::try_from_secs_f64).transpose()?,
+                    duration: duration.map(Duration::try_from_secs_f64).transpose()?,
+                    fields,
+                    record,
+                    replay,
+                    speed,
+                };
+                monitor::execute(options).await
+            }
+            Commands::Export {
+                dataset,
+                period,
+                format,
+                out,
+                daily,
+            } => export::execute(client, dataset, &period, format, out, daily).await,
+            Commands::Forecast {
+                metric,
+                target,
+                method,
+            } => forecast::execute(client, metric, method, target).await,
+            Commands::Heatmap {
+                layout,
+                period,
+                board,
+                out,
+            } => heatmap::execute(&layout, &period, &board, &out),
+            Commands::Serve { listen } => serve::execute(client, &listen).await,
+            Commands::ServeMetrics { listen, interval } => {
+                metrics::execute(client, &listen, interval).await
+            }
+            Commands::Rhythm { period, metric } => rhythm::execute(client, &period, metric).await,
+            Commands::UnmappedKeys { period } => unmapped_keys::execute(&period),
         }
     }
 }
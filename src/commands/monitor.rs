@@ -1,9 +1,11 @@
-use crate::client::WhatpulseClient;
 use crate::commands::TuiPage;
-use crate::tui::app::{Action, App, MonitorCommand, RealtimeData, UnitSystem};
+use crate::commands::export::csv_field;
+use crate::realtime::wpm::WpmWindow;
+use crate::realtime::{CLIENT_WS_URL, RealtimeData, RealtimeEvent, RealtimeHub, Recorder};
+use crate::tui::app::{App, UnitSystem};
 use anyhow::Result;
+use chrono::{DateTime, Local, SecondsFormat};
 use crossterm::event::{KeyCode, KeyEvent};
-use futures_util::{SinkExt, StreamExt};
 use ratatui::{
     Frame,
     layout::{Constraint, Direction, Layout, Rect},
@@ -11,9 +13,11 @@ use ratatui::{
     text::{Line, Span},
     widgets::{Block, Borders, Gauge, Paragraph, Sparkline},
 };
-use serde::{Deserialize, Serialize};
-use std::time::Duration;
-use tokio_tungstenite::{connect_async, tungstenite::protocol::Message};
+use serde_json::json;
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast;
 
 // Constants
 // Gauge Scale Physics:
@@ -35,216 +39,287 @@ inventory::submit! {
     }
 }
 
-#[derive(Serialize, Debug)]
-struct WpWebSocketRequest {
-    source: String,
-    action: String,
+#[derive(Debug, Clone, Copy, PartialEq, Default, clap::ValueEnum)]
+pub enum MonitorFormat {
+    #[default]
+    Human,
+    /// A single JSON array, closed on exit
+    Json,
+    Ndjson,
+    Csv,
 }
 
-#[derive(Deserialize, Debug)]
-struct WpWebSocketMsg {
-    action: String,
-    data: Option<WpDataResponse>,
+#[derive(Debug, Clone, Copy, PartialEq, clap::ValueEnum)]
+pub enum MonitorField {
+    #[value(name = "timestamp")]
+    Timestamp,
+    #[value(name = "keys_per_second")]
+    KeysPerSecond,
+    #[value(name = "unpulsed_keys")]
+    UnpulsedKeys,
+    #[value(name = "unpulsed_clicks")]
+    UnpulsedClicks,
+    #[value(name = "unpulsed_scrolls")]
+    UnpulsedScrolls,
+    #[value(name = "clicks_per_second")]
+    ClicksPerSecond,
+    #[value(name = "download_bytes_per_second")]
+    DownloadBytesPerSecond,
+    #[value(name = "upload_bytes_per_second")]
+    UploadBytesPerSecond,
+    #[value(name = "unpulsed_download")]
+    UnpulsedDownload,
+    #[value(name = "unpulsed_upload")]
+    UnpulsedUpload,
+    #[value(name = "unpulsed_uptime")]
+    UnpulsedUptime,
+    #[value(name = "unpulsed_distance_miles")]
+    UnpulsedDistanceMiles,
+    #[value(name = "heatmap")]
+    Heatmap,
 }
 
-use std::collections::HashMap;
+pub const DEFAULT_FIELDS: [MonitorField; 5] = [
+    MonitorField::Timestamp,
+    MonitorField::KeysPerSecond,
+    MonitorField::UnpulsedKeys,
+    MonitorField::UnpulsedClicks,
+    MonitorField::UnpulsedScrolls,
+];
+
+impl MonitorField {
+    fn name(self) -> &'static str {
+        match self {
+            MonitorField::Timestamp => "timestamp",
+            MonitorField::KeysPerSecond => "keys_per_second",
+            MonitorField::UnpulsedKeys => "unpulsed_keys",
+            MonitorField::UnpulsedClicks => "unpulsed_clicks",
+            MonitorField::UnpulsedScrolls => "unpulsed_scrolls",
+            MonitorField::ClicksPerSecond => "clicks_per_second",
+            MonitorField::DownloadBytesPerSecond => "download_bytes_per_second",
+            MonitorField::UploadBytesPerSecond => "upload_bytes_per_second",
+            MonitorField::UnpulsedDownload => "unpulsed_download",
+            MonitorField::UnpulsedUpload => "unpulsed_upload",
+            MonitorField::UnpulsedUptime => "unpulsed_uptime",
+            MonitorField::UnpulsedDistanceMiles => "unpulsed_distance_miles",
+            MonitorField::Heatmap => "heatmap",
+        }
+    }
 
-#[derive(Deserialize, Debug)]
-struct WpDataResponse {
-    #[serde(rename = "account-totals")]
-    _account_totals: Option<serde_json::Value>,
-    realtime: Option<WpRealtime>,
-    unpulsed: Option<WpUnpulsed>,
-    heatmap: Option<HashMap<String, u64>>,
+    fn value(self, data: &RealtimeData, at: DateTime<Local>) -> serde_json::Value {
+        match self {
+            MonitorField::Timestamp => json!(at.to_rfc3339_opts(SecondsFormat::Millis, false)),
+            MonitorField::KeysPerSecond => json!(data.keys_per_second),
+            MonitorField::UnpulsedKeys => json!(data.unpulsed_keys),
+            MonitorField::UnpulsedClicks => json!(data.unpulsed_clicks),
+            MonitorField::UnpulsedScrolls => json!(data.unpulsed_scrolls),
+            MonitorField::ClicksPerSecond => json!(data.clicks_per_second),
+            MonitorField::DownloadBytesPerSecond => json!(data.download_bytes_per_second),
+            MonitorField::UploadBytesPerSecond => json!(data.upload_bytes_per_second),
+            MonitorField::UnpulsedDownload => json!(data.unpulsed_download),
+            MonitorField::UnpulsedUpload => json!(data.unpulsed_upload),
+            MonitorField::UnpulsedUptime => json!(data.unpulsed_uptime),
+            MonitorField::UnpulsedDistanceMiles => json!(data.unpulsed_distance_miles),
+            MonitorField::Heatmap => json!(data.heatmap),
+        }
+    }
 }
 
-#[derive(Deserialize, Debug)]
-struct WpRealtime {
-    keys: String, // "0.00" or "0,00"
-    #[serde(rename = "clicks")]
-    _clicks: String,
+/// Formats realtime samples for stdout. Keeps track of what has been written
+/// so CSV gets one header and JSON a well-formed array.
+pub struct MonitorWriter {
+    format: MonitorFormat,
+    fields: Vec<MonitorField>,
+    rows: usize,
 }
 
-#[derive(Deserialize, Debug)]
-struct WpUnpulsed {
-    keys: i64,
-    clicks: i64,
-    #[serde(default)]
-    scrolls: i64,
-}
+impl MonitorWriter {
+    pub fn new(format: MonitorFormat, fields: Vec<MonitorField>) -> Self {
+        Self {
+            format,
+            fields,
+            rows: 0,
+        }
+    }
+
+    pub fn row(&mut self, data: &RealtimeData, at: DateTime<Local>) -> String {
+        let values: Vec<(&str, serde_json::Value)> = self
+            .fields
+            .iter()
+            .map(|f| (f.name(), f.value(data, at)))
+            .collect();
+        let object = || {
+            let map: serde_json::Map<String, serde_json::Value> = values
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.clone()))
+                .collect();
+            serde_json::Value::Object(map).to_string()
+        };
+
+        let out = match self.format {
+            MonitorFormat::Human => {
+                let parts: Vec<String> = self
+                    .fields
+                    .iter()
+                    .zip(&values)
+                    .map(|(field, (name, value))| match field {
+                        MonitorField::Timestamp => format!("[{}]", at.format("%H:%M:%S")),
+                        MonitorField::KeysPerSecond => {
+                            format!("{:.2} keys/s", data.keys_per_second)
+                        }
+                        MonitorField::ClicksPerSecond => {
+                            format!("{:.2} clicks/s", data.clicks_per_second)
+                        }
+                        _ => format!("{}: {}", name, value),
+                    })
+                    .collect();
+                format!("{}\n", parts.join(" | "))
+            }
+            MonitorFormat::Ndjson => format!("{}\n", object()),
+            MonitorFormat::Json => {
+                let sep = if self.rows == 0 { "[\n  " } else { ",\n  " };
+                format!("{}{}", sep, object())
+            }
+            MonitorFormat::Csv => {
+                let row: Vec<String> = values
+                    .iter()
+                    .map(|(_, v)| match v {
+                        serde_json::Value::String(s) => csv_field(s),
+                        other => csv_field(&other.to_string()),
+                    })
+                    .collect();
+                let mut out = String::new();
+                if self.rows == 0 {
+                    let header: Vec<&str> = self.fields.iter().map(|f| f.name()).collect();
+                    out.push_str(&header.join(","));
+                    out.push('\n');
+                }
+                out.push_str(&row.join(","));
+                out.push('\n');
+                out
+            }
+        };
+        self.rows += 1;
+        out
+    }
 
-// Helper to parse localized float strings (e.g. "2,17" or "2.17")
-fn parse_localized_float(s: &str) -> f64 {
-    let normalized = s.replace(',', ".");
-    normalized.parse::<f64>().unwrap_or(0.0)
+    /// Closing output, e.g. the end of the JSON array.
+    pub fn finish(&self) -> String {
+        match self.format {
+            MonitorFormat::Json if self.rows == 0 => "[]\n".to_string(),
+            MonitorFormat::Json => "\n]\n".to_string(),
+            _ => String::new(),
+        }
+    }
 }
 
-// CLI Execution (Streaming Mode)
-pub async fn execute(_client: &WhatpulseClient) -> Result<()> {
-    let url = url::Url::parse("ws://127.0.0.1:3489")?;
-    println!("Connecting to {}...", url);
+pub struct MonitorOptions {
+    pub format: MonitorFormat,
+    /// Emit at most one sample per interval (the first one after it elapses).
+    pub interval: Option<Duration>,
+    /// Stop after this long.
+    pub duration: Option<Duration>,
+    pub fields: Vec<MonitorField>,
+    /// Save raw client frames here.
+    pub record: Option<PathBuf>,
+    /// Read frames from this recording instead of the client.
+    pub replay: Option<PathBuf>,
+    pub speed: f64,
+}
 
-    let (mut ws_stream, _) = connect_async(url.to_string()).await?;
-    println!("Connected! Sending subscription...");
+fn status(format: MonitorFormat, message: &str) {
+    // Keep stdout machine-readable for anything but the human format.
+    if format == MonitorFormat::Human {
+        println!("{}", message);
+    } else {
+        eprintln!("{}", message);
+    }
+}
 
-    // Subscribe to realtime stats
-    ws_stream
-        .send(Message::Text("/v1/realtime".to_string().into()))
-        .await?;
+// CLI Execution (Streaming Mode)
+pub async fn execute(options: MonitorOptions) -> Result<()> {
+    let hub = match &options.replay {
+        Some(path) => RealtimeHub::replay(path, options.speed)?,
+        None => RealtimeHub::spawn(),
+    };
+    if let Some(path) = &options.record {
+        hub.record(Recorder::create(path)?);
+    }
+    let (state, mut events) = hub.subscribe_with_state();
+    let format = options.format;
+    let fields = if options.fields.is_empty() {
+        DEFAULT_FIELDS.to_vec()
+    } else {
+        options.fields
+    };
+    let mut writer = MonitorWriter::new(format, fields);
+    let mut stdout = std::io::stdout();
 
-    let (_, mut read) = ws_stream.split();
+    match &options.replay {
+        Some(path) => status(
+            format,
+            &format!("Replaying {} at {}x...", path.display(), options.speed),
+        ),
+        None => status(format, &format!("Connecting to {}...", CLIENT_WS_URL)),
+    }
+    if let Some(path) = &options.record {
+        status(format, &format!("Recording frames to {}", path.display()));
+    }
+    status(format, "Press Ctrl+C to exit.");
+    if let Some(entry) = state.log.back() {
+        status(format, &entry.message);
+    }
 
-    println!("Listening for pulses...");
-    println!("Press Ctrl+C to exit.");
+    let deadline = tokio::time::sleep(options.duration.unwrap_or(Duration::MAX));
+    tokio::pin!(deadline);
+    let mut last_emit: Option<Instant> = None;
 
     loop {
         tokio::select! {
-            msg = read.next() => {
-                match msg {
-                    Some(Ok(Message::Text(text))) => {
-                        println!("{}", text);
+            event = events.recv() => match event {
+                Ok(RealtimeEvent::Connected) => status(format, "Connected! Listening for updates..."),
+                Ok(RealtimeEvent::Error(error)) => eprintln!("Error: {}", error),
+                Ok(RealtimeEvent::Update(data)) => {
+                    let now = Instant::now();
+                    let due = match (options.interval, last_emit) {
+                        (Some(interval), Some(last)) => now.duration_since(last) >= interval,
+                        _ => true,
+                    };
+                    if due {
+                        last_emit = Some(now);
+                        stdout.write_all(writer.row(&data, Local::now()).as_bytes())?;
+                        stdout.flush()?;
                     }
-                    Some(Ok(Message::Close(_))) => {
-                        println!("Connection closed.");
-                        break;
+                }
+                Ok(
+                    event @ (RealtimeEvent::Disconnected(_)
+                    | RealtimeEvent::Reconnecting { .. }
+                    | RealtimeEvent::Notice { .. }),
+                ) => {
+                    if let Some(message) = event.log_message() {
+                        status(format, &message);
                     }
-                    Some(Err(e)) => {
-                        eprintln!("Error: {}", e);
+                    // A replay doesn't reconnect, so its end is the end of the stream.
+                    if options.replay.is_some() && matches!(event, RealtimeEvent::Disconnected(_)) {
                         break;
                     }
-                    None => break,
-                    _ => {}
                 }
-            }
+                Ok(RealtimeEvent::Connecting) | Ok(RealtimeEvent::Debug(_)) => {}
+                Err(broadcast::error::RecvError::Lagged(_)) => {}
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+            _ = &mut deadline => break,
             _ = tokio::signal::ctrl_c() => {
-                println!("\nExiting...");
+                status(format, "\nExiting...");
                 break;
             }
         }
     }
+    stdout.write_all(writer.finish().as_bytes())?;
+    stdout.flush()?;
     Ok(())
 }
 
-// Background Task for TUI
-pub async fn spawn_monitor_task(
-    tx: tokio::sync::mpsc::Sender<Action>,
-    mut rx_cmd: tokio::sync::mpsc::Receiver<MonitorCommand>,
-) {
-    let url = url::Url::parse("ws://127.0.0.1:3489").unwrap();
-    loop {
-        // let _ = tx.send(Action::DebugInfo(format!("Connecting to {}...", url))).await;
-        match connect_async(url.to_string()).await {
-            Ok((ws_stream, _)) => {
-                let _ = tx.send(Action::WebSocketStatus(true, None)).await;
-                // let _ = tx.send(Action::DebugInfo("Connected! Sending Identify...".to_string())).await;
-
-                let (mut write, mut read) = ws_stream.split();
-
-                // Handshake: Identify as plugin
-                let identify_req = WpWebSocketRequest {
-                    source: "plugin".to_string(),
-                    action: "identify".to_string(),
-                };
-                let identify_json = serde_json::to_string(&identify_req).unwrap();
-
-                if let Err(e) = write.send(Message::Text(identify_json.into())).await {
-                    let _ = tx
-                        .send(Action::WebSocketStatus(
-                            true,
-                            Some(format!("Handshake failed: {}", e)),
-                        ))
-                        .await;
-                }
-
-                loop {
-                    tokio::select! {
-                        // Handle incoming WebSocket messages
-                        msg = read.next() => {
-                            match msg {
-                                Some(Ok(Message::Text(text))) => {
-                                    if let Ok(_val) = serde_json::from_str::<serde_json::Value>(&text) {
-                                    }
-
-                                    match serde_json::from_str::<WpWebSocketMsg>(&text) {
-                                        Ok(msg) => {
-                                            if msg.action == "update-status" {
-                                                if let Some(data) = msg.data {
-                                                    let kps = if let Some(rt) = data.realtime {
-                                                        parse_localized_float(&rt.keys)
-                                                    } else {
-                                                        0.0
-                                                    };
-
-                                                    let (keys, clicks, scrolls) = if let Some(up) = data.unpulsed {
-                                                        (up.keys, up.clicks, up.scrolls)
-                                                    } else {
-                                                        (0, 0, 0)
-                                                    };
-
-                                                    let heatmap = data.heatmap.unwrap_or_default();
-                                                    let _ = tx.send(Action::RealtimeUpdate(RealtimeData {
-                                                        unpulsed_keys: keys,
-                                                        unpulsed_clicks: clicks,
-                                                        unpulsed_scrolls: scrolls,
-                                                        keys_per_second: kps,
-                                                        heatmap,
-                                                    })).await;
-                                                }
-                                            } else {
-                                                let _ = tx.send(Action::DebugInfo(format!("Unknown Action: {}", msg.action))).await;
-                                            }
-                                        }
-                                        Err(e) => {
-                                            let _ = tx.send(Action::DebugInfo(format!("JSON Parse Error: {} | Raw: {}", e, text))).await;
-                                        }
-                                    }
-                                }
-                                Some(Ok(Message::Close(_))) => break,
-                                Some(Err(_)) => break,
-                                None => break,
-                                _ => {}
-                            }
-                        }
-                        // Handle outgoing commands from TUI
-                        cmd = rx_cmd.recv() => {
-                            if let Some(command) = cmd {
-                                let action_str = match command {
-                                    MonitorCommand::Pulse => "pulse",
-                                    MonitorCommand::OpenWindow => "open-window",
-                                };
-
-                                let req = WpWebSocketRequest {
-                                    source: "plugin".to_string(),
-                                    action: action_str.to_string(),
-                                };
-                                let req_json = serde_json::to_string(&req).unwrap();
-
-                                if let Err(e) = write.send(Message::Text(req_json.into())).await {
-                                     let _ = tx.send(Action::DebugInfo(format!("Send failed: {}", e))).await;
-                                }
-                            } else {
-                                break;
-                            }
-                        }
-                    }
-                }
-                let _ = tx
-                    .send(Action::WebSocketStatus(
-                        false,
-                        Some("Connection closed".to_string()),
-                    ))
-                    .await;
-            }
-            Err(e) => {
-                let _ = tx
-                    .send(Action::WebSocketStatus(false, Some(e.to_string())))
-                    .await;
-            }
-        }
-        tokio::time::sleep(Duration::from_secs(5)).await;
-    }
-}
-
 // TUI Rendering
 fn render_tui(f: &mut Frame, app: &App, area: Rect) {
     let chunks = Layout::default()
@@ -293,6 +368,15 @@ fn render_tui(f: &mut Frame, app: &App, area: Rect) {
             app.current_profile().force_newtons * 101.97, // N to gf roughly
             app.current_profile().distance_meters * 1000.0
         )),
+        Span::styled(
+            format!(
+                " | {:.2} clicks/s | Net ↓{:.1} KB/s ↑{:.1} KB/s",
+                app.kinetic_stats.clicks_per_second,
+                app.kinetic_stats.download_bytes_per_second / 1024.0,
+                app.kinetic_stats.upload_bytes_per_second / 1024.0
+            ),
+            Style::default().fg(Color::DarkGray),
+        ),
     ]))
     .block(
         Block::default()
@@ -409,9 +493,28 @@ fn render_tui(f: &mut Frame, app: &App, area: Rect) {
             app.kinetic_stats.accumulated_work_joules
         ))]),
         Line::from(""),
+        Line::from(vec![
+            Span::styled("Click Work: ", Style::default().fg(Color::Yellow)),
+            Span::raw(format!(
+                "{:.4} J ({:.4} W, {})",
+                app.kinetic_stats.click_work_joules,
+                app.kinetic_stats.click_power_watts,
+                app.current_mouse_profile().name
+            )),
+        ]),
+        Line::from(vec![
+            Span::styled("Session: ", Style::default().fg(Color::Yellow)),
+            Span::raw(format!(
+                "{} keys, {} clicks, {} scrolls",
+                app.kinetic_stats.session_keys,
+                app.kinetic_stats.session_clicks,
+                app.kinetic_stats.session_scrolls
+            )),
+        ]),
+        Line::from(""),
         Line::from(vec![
             Span::styled("Shortcuts: ", Style::default().fg(Color::DarkGray)),
-            Span::raw("'u' to toggle units"),
+            Span::raw("'u' units, 'p' key switch, 'c' click switch"),
         ]),
     ];
 
@@ -420,7 +523,24 @@ fn render_tui(f: &mut Frame, app: &App, area: Rect) {
             .borders(Borders::ALL)
             .title("Kinetic Telemetry"),
     );
-    f.render_widget(stats, bottom_chunks[0]);
+    let left_chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(10),   // Telemetry
+            Constraint::Length(6), // WPM
+        ])
+        .split(bottom_chunks[0]);
+    f.render_widget(stats, left_chunks[0]);
+    render_wpm(f, app, left_chunks[1]);
+
+    let right_chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(5),    // Sparkline
+            Constraint::Length(5), // Input Rates
+            Constraint::Length(7), // Connection Log
+        ])
+        .split(bottom_chunks[1]);
 
     // Sparkline
     let sparkline = Sparkline::default()
@@ -431,7 +551,139 @@ fn render_tui(f: &mut Frame, app: &App, area: Rect) {
         )
         .data(&app.kinetic_stats.history_power)
         .style(Style::default().fg(Color::LightBlue));
-    f.render_widget(sparkline, bottom_chunks[1]);
+    f.render_widget(sparkline, right_chunks[0]);
+
+    render_rates(f, app, right_chunks[1]);
+    render_connection_log(f, app, right_chunks[2]);
+}
+
+/// Keys, clicks and scrolls per second side by side, each with its peak (↑).
+fn render_rates(f: &mut Frame, app: &App, area: Rect) {
+    let k = &app.kinetic_stats;
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Ratio(1, 3); 3])
+        .split(area);
+
+    for (i, (label, rate, peak, history, color)) in [
+        (
+            "Keys/s",
+            k.keys_per_second,
+            k.peak_keys_per_second,
+            &k.history_keys,
+            Color::Cyan,
+        ),
+        (
+            "Clicks/s",
+            k.clicks_per_second,
+            k.peak_clicks_per_second,
+            &k.history_clicks,
+            Color::Magenta,
+        ),
+        (
+            "Scrolls/s",
+            k.scrolls_per_second,
+            k.peak_scrolls_per_second,
+            &k.history_scrolls,
+            Color::Green,
+        ),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        // Show the most recent samples that fit.
+        let width = columns[i].width.saturating_sub(2) as usize;
+        let visible = &history[history.len().saturating_sub(width)..];
+        let sparkline = Sparkline::default()
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(format!("{} {:.1} ↑{:.1}", label, rate, peak)),
+            )
+            .data(visible)
+            .style(Style::default().fg(color));
+        f.render_widget(sparkline, columns[i]);
+    }
+}
+
+/// Gross and net WPM per window with personal bests, next to the net 1m history.
+fn render_wpm(f: &mut Frame, app: &App, area: Rect) {
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Length(34), Constraint::Min(10)])
+        .split(area);
+
+    let mut lines = vec![Line::from(Span::styled(
+        "      Gross    Net   Best",
+        Style::default().fg(Color::DarkGray),
+    ))];
+    for window in WpmWindow::ALL {
+        let best = app
+            .wpm
+            .bests
+            .get(window)
+            .map_or("-".to_string(), |b| format!("{:.0}", b.wpm));
+        lines.push(Line::from(vec![
+            Span::styled(
+                format!("{:<4}", window.label()),
+                Style::default().fg(Color::Yellow),
+            ),
+            Span::raw(format!(
+                "{:>7.0}{:>7.0}",
+                app.wpm.gross_wpm(window),
+                app.wpm.net_wpm(window)
+            )),
+            Span::styled(format!("{:>7}", best), Style::default().fg(Color::Green)),
+        ]));
+    }
+    let table = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title("WPM (net is an estimate)"),
+    );
+    f.render_widget(table, columns[0]);
+
+    let width = columns[1].width.saturating_sub(2) as usize;
+    let history = &app.wpm.history[app.wpm.history.len().saturating_sub(width)..];
+    let sparkline = Sparkline::default()
+        .block(Block::default().borders(Borders::ALL).title("Net WPM (1m)"))
+        .data(history)
+        .style(Style::default().fg(Color::Yellow));
+    f.render_widget(sparkline, columns[1]);
+}
+
+fn render_connection_log(f: &mut Frame, app: &App, area: Rect) {
+    let block = Block::default().borders(Borders::ALL).title(format!(
+        "Connection Log (reconnects: {})",
+        app.kinetic_stats.reconnects
+    ));
+    let visible = area.height.saturating_sub(2) as usize;
+
+    let lines: Vec<Line> = app
+        .kinetic_stats
+        .connection_log
+        .iter()
+        .rev()
+        .take(visible)
+        .map(|entry| {
+            let color = if entry.message.starts_with("Connected") {
+                Color::Green
+            } else if entry.message.starts_with("Retry") {
+                Color::DarkGray
+            } else {
+                Color::Red
+            };
+            Line::from(vec![
+                Span::styled(
+                    format!("{} ", entry.at.format("%H:%M:%S")),
+                    Style::default().fg(Color::DarkGray),
+                ),
+                Span::styled(entry.message.clone(), Style::default().fg(color)),
+            ])
+        })
+        .collect();
+
+    f.render_widget(Paragraph::new(lines).block(block), area);
 }
 
 fn handle_key(app: &mut App, key: KeyEvent) -> bool {
@@ -441,6 +693,11 @@ fn handle_key(app: &mut App, key: KeyEvent) -> bool {
                 (app.keyboard.profile_index + 1) % app.keyboard.profiles.len();
             true
         }
+        KeyCode::Char('c') => {
+            app.mouse.switch_profile_index =
+                (app.mouse.switch_profile_index + 1) % app.mouse.switch_profiles.len();
+            true
+        }
         KeyCode::Char('u') => {
             app.unit_system = match app.unit_system {
                 UnitSystem::Metric => UnitSystem::Centimeters,
@@ -455,52 +712,71 @@ fn handle_key(app: &mut App, key: KeyEvent) -> bool {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use chrono::TimeZone;
+    use std::collections::HashMap;
+
+    fn sample() -> (RealtimeData, DateTime<Local>) {
+        let data = RealtimeData {
+            unpulsed_keys: 120,
+            unpulsed_clicks: 4,
+            unpulsed_scrolls: 0,
+            keys_per_second: 2.5,
+            heatmap: HashMap::from([("A".to_string(), 3)]),
+            ..Default::default()
+        };
+        let at = Local.with_ymd_and_hms(2025, 3, 1, 12, 30, 5).unwrap();
+        (data, at)
+    }
 
     #[test]
-    fn test_parse_localized_float() {
-        assert_eq!(parse_localized_float("2.17"), 2.17);
-        assert_eq!(parse_localized_float("2,17"), 2.17);
-        assert_eq!(parse_localized_float("0"), 0.0);
-        assert_eq!(parse_localized_float("invalid"), 0.0);
+    fn test_csv_writes_header_once() {
+        let (data, at) = sample();
+        let mut writer = MonitorWriter::new(
+            MonitorFormat::Csv,
+            vec![MonitorField::KeysPerSecond, MonitorField::UnpulsedKeys],
+        );
+        assert_eq!(
+            writer.row(&data, at),
+            "keys_per_second,unpulsed_keys\n2.5,120\n"
+        );
+        assert_eq!(writer.row(&data, at), "2.5,120\n");
+        assert_eq!(writer.finish(), "");
     }
 
     #[test]
-    fn test_deserialize_update_status() {
-        let json = r#"{
-            "action": "update-status",
-            "data": {
-                "account-totals": null,
-                "realtime": {
-                    "keys": "1,23",
-                    "clicks": "0.45"
-                },
-                "unpulsed": {
-                    "keys": 100,
-                    "clicks": 50
-                }
-            }
-        }"#;
-
-        let msg: WpWebSocketMsg = serde_json::from_str(json).unwrap();
-        assert_eq!(msg.action, "update-status");
-
-        let data = msg.data.unwrap();
-        let realtime = data.realtime.unwrap();
-        assert_eq!(realtime.keys, "1,23");
-        assert_eq!(realtime._clicks, "0.45");
-
-        let unpulsed = data.unpulsed.unwrap();
-        assert_eq!(unpulsed.keys, 100);
-        assert_eq!(unpulsed.clicks, 50);
+    fn test_json_output_is_a_valid_array() {
+        let (data, at) = sample();
+        let mut writer = MonitorWriter::new(
+            MonitorFormat::Json,
+            vec![MonitorField::UnpulsedClicks, MonitorField::Heatmap],
+        );
+        let out = writer.row(&data, at) + &writer.row(&data, at) + &writer.finish();
+        let parsed: serde_json::Value = serde_json::from_str(&out).unwrap();
+        assert_eq!(
+            parsed,
+            json!([
+                {"unpulsed_clicks": 4, "heatmap": {"A": 3}},
+                {"unpulsed_clicks": 4, "heatmap": {"A": 3}}
+            ])
+        );
+
+        let empty = MonitorWriter::new(MonitorFormat::Json, DEFAULT_FIELDS.to_vec());
+        assert_eq!(empty.finish(), "[]\n");
     }
 
     #[test]
-    fn test_serialize_request() {
-        let req = WpWebSocketRequest {
-            source: "plugin".to_string(),
-            action: "pulse".to_string(),
-        };
-        let json = serde_json::to_string(&req).unwrap();
-        assert_eq!(json, r#"{"source":"plugin","action":"pulse"}"#);
+    fn test_ndjson_includes_timestamp() {
+        let (data, at) = sample();
+        let mut writer = MonitorWriter::new(MonitorFormat::Ndjson, DEFAULT_FIELDS.to_vec());
+        let line = writer.row(&data, at);
+        assert!(line.ends_with('\n'));
+        let parsed: serde_json::Value = serde_json::from_str(&line).unwrap();
+        assert_eq!(parsed["unpulsed_keys"], json!(120));
+        assert!(
+            parsed["timestamp"]
+                .as_str()
+                .unwrap()
+                .starts_with("2025-03-01T12:30:05.000")
+        );
     }
 }
@@ -0,0 +1,15 @@
+use crate::client::WhatpulseClient;
+use crate::realtime::{self, PluginRequest};
+use anyhow::Result;
+use std::time::Duration;
+
+/// How long to wait for the connection and for the client's reply.
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+pub async fn execute(_client: &WhatpulseClient) -> Result<()> {
+    match realtime::send_once(PluginRequest::Pulse, TIMEOUT).await? {
+        Some(reply) => println!("Pulse sent: {}", reply),
+        None => println!("Pulse sent (no reply from the client)."),
+    }
+    Ok(())
+}
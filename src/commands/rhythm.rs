@@ -0,0 +1,592 @@
+use crate::client::{PulseResponse, WhatpulseClient};
+use crate::commands::TuiPage;
+use crate::commands::keyboard::get_color;
+use crate::db::{Database, HourlyActivity};
+use crate::tui::app::{App, TimePeriod};
+use crate::tui::period_utils::{
+    StatsTarget, get_display_period, handle_period_nav, is_date_in_period, parse_period_string,
+};
+use anyhow::Result;
+use chrono::{Datelike, Local, NaiveDate, Timelike};
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    Frame,
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Tabs},
+};
+
+inventory::submit! {
+    TuiPage {
+        title: "Rhythm",
+        category: "Uptime",
+        render: render_rhythm,
+        handle_key: handle_rhythm_key,
+        handle_mouse: crate::commands::default_handle_mouse,
+        priority: 61,
+    }
+}
+
+pub const WEEKDAYS: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, clap::ValueEnum)]
+pub enum RhythmMetric {
+    #[default]
+    Keys,
+    Clicks,
+    Scrolls,
+}
+
+impl RhythmMetric {
+    pub fn next(self) -> Self {
+        match self {
+            RhythmMetric::Keys => RhythmMetric::Clicks,
+            RhythmMetric::Clicks => RhythmMetric::Scrolls,
+            RhythmMetric::Scrolls => RhythmMetric::Keys,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            RhythmMetric::Keys => "Keys",
+            RhythmMetric::Clicks => "Clicks",
+            RhythmMetric::Scrolls => "Scrolls",
+        }
+    }
+}
+
+/// Input totals bucketed by weekday (Mon = 0) and hour of day.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RhythmMatrix {
+    pub keys: [[u64; 24]; 7],
+    pub clicks: [[u64; 24]; 7],
+    pub scrolls: [[u64; 24]; 7],
+}
+
+impl RhythmMatrix {
+    pub fn from_hourly(rows: &[HourlyActivity]) -> Self {
+        let mut matrix = Self::default();
+        for row in rows {
+            let Ok(date) = NaiveDate::parse_from_str(&row.day, "%Y-%m-%d") else {
+                continue;
+            };
+            let day = date.weekday().num_days_from_monday() as usize;
+            let hour = row.hour as usize;
+            if hour >= 24 {
+                continue;
+            }
+            matrix.keys[day][hour] += row.keys;
+            matrix.clicks[day][hour] += row.clicks;
+            matrix.scrolls[day][hour] += row.scrolls;
+        }
+        matrix
+    }
+
+    /// Fallback for when the local database has no hourly tables: each pulse's
+    /// totals are spread evenly over the uptime window that ended at the pulse.
+    pub fn from_pulses(pulses: &[PulseResponse], in_period: impl Fn(NaiveDate) -> bool) -> Self {
+        let mut acc = [[[0.0f64; 24]; 7]; 3];
+
+        for pulse in pulses {
+            let Some(end) = pulse.timestamp() else {
+                continue;
+            };
+            let totals = [
+                pulse.keys.unwrap_or(0) as f64,
+                pulse.clicks.unwrap_or(0) as f64,
+                pulse.scrolls.unwrap_or(0) as f64,
+            ];
+            let uptime = pulse.uptime_seconds.unwrap_or(0) as i64;
+
+            if uptime <= 0 {
+                add_bucket(&mut acc, end, &totals, 1.0, &in_period);
+                continue;
+            }
+
+            let mut curr = end - chrono::Duration::seconds(uptime);
+            while curr < end {
+                let next_hour = (curr
+                    .with_minute(0)
+                    .and_then(|t| t.with_second(0))
+                    .unwrap_or(curr))
+                    + chrono::Duration::hours(1);
+                let segment_end = next_hour.min(end);
+                let share = (segment_end - curr).num_seconds() as f64 / uptime as f64;
+                add_bucket(&mut acc, curr, &totals, share, &in_period);
+                curr = segment_end;
+            }
+        }
+
+        let round = |m: &[[f64; 24]; 7]| m.map(|row| row.map(|v| v.round() as u64));
+        Self {
+            keys: round(&acc[0]),
+            clicks: round(&acc[1]),
+            scrolls: round(&acc[2]),
+        }
+    }
+
+    pub fn cells(&self, metric: RhythmMetric) -> &[[u64; 24]; 7] {
+        match metric {
+            RhythmMetric::Keys => &self.keys,
+            RhythmMetric::Clicks => &self.clicks,
+            RhythmMetric::Scrolls => &self.scrolls,
+        }
+    }
+
+    pub fn max(&self, metric: RhythmMetric) -> u64 {
+        self.cells(metric)
+            .iter()
+            .flat_map(|row| row.iter())
+            .copied()
+            .max()
+            .unwrap_or(0)
+    }
+
+    pub fn total(&self, metric: RhythmMetric) -> u64 {
+        self.cells(metric).iter().flat_map(|row| row.iter()).sum()
+    }
+
+    /// The busiest (weekday, hour) cell, if anything was recorded.
+    pub fn peak(&self, metric: RhythmMetric) -> Option<(usize, usize, u64)> {
+        let mut best = None;
+        for (day, row) in self.cells(metric).iter().enumerate() {
+            for (hour, &value) in row.iter().enumerate() {
+                if value > 0 && best.is_none_or(|(_, _, v)| value > v) {
+                    best = Some((day, hour, value));
+                }
+            }
+        }
+        best
+    }
+
+    pub fn day_totals(&self, metric: RhythmMetric) -> [u64; 7] {
+        self.cells(metric).map(|row| row.iter().sum())
+    }
+
+    pub fn hour_totals(&self, metric: RhythmMetric) -> [u64; 24] {
+        let mut totals = [0u64; 24];
+        for row in self.cells(metric) {
+            for (hour, value) in row.iter().enumerate() {
+                totals[hour] += value;
+            }
+        }
+        totals
+    }
+}
+
+fn add_bucket(
+    acc: &mut [[[f64; 24]; 7]; 3],
+    at: chrono::DateTime<Local>,
+    totals: &[f64; 3],
+    share: f64,
+    in_period: &impl Fn(NaiveDate) -> bool,
+) {
+    if !in_period(at.date_naive()) {
+        return;
+    }
+    let day = at.weekday().num_days_from_monday() as usize;
+    let hour = at.hour() as usize;
+    for (metric, total) in totals.iter().enumerate() {
+        acc[metric][day][hour] += total * share;
+    }
+}
+
+fn index_of_max(values: &[u64]) -> Option<usize> {
+    values
+        .iter()
+        .enumerate()
+        .filter(|(_, v)| **v > 0)
+        .max_by_key(|(_, v)| **v)
+        .map(|(i, _)| i)
+}
+
+pub async fn execute(client: &WhatpulseClient, period: &str, metric: RhythmMetric) -> Result<()> {
+    let (time_period, date_picker) = parse_period_string(period)?;
+
+    let db_rows = Database::new().and_then(|db| db.get_hourly_activity(period));
+    let (matrix, source) = match db_rows {
+        Ok(rows) => (RhythmMatrix::from_hourly(&rows), "local database"),
+        Err(e) => {
+            if client.is_local() {
+                return Err(e.context(
+                    "No hourly data in the local database (pulse history requires an API key)",
+                ));
+            }
+            println!("Local database unavailable ({}), using pulse history...", e);
+            let pulses = client.get_pulses().await?;
+            let matrix = RhythmMatrix::from_pulses(&pulses, |d| {
+                is_date_in_period(d, time_period, &date_picker)
+            });
+            (matrix, "pulse history")
+        }
+    };
+
+    println!(
+        "\nActivity Rhythm: {} ({}, from {})",
+        metric.label(),
+        get_display_period(time_period),
+        source
+    );
+    println!("──────────────────────────────────────────────────────────────────────────────");
+
+    let shades = [' ', '·', '░', '▒', '▓', '█'];
+    let max = matrix.max(metric);
+    let hours: String = (0..24).map(|h| format!("{:<3}", h)).collect();
+    println!("     {}Total", hours);
+    for (day, row) in matrix.cells(metric).iter().enumerate() {
+        let cells: String = row
+            .iter()
+            .map(|&v| {
+                let idx = if max == 0 || v == 0 {
+                    0
+                } else {
+                    1 + ((v as f64 / max as f64) * (shades.len() - 2) as f64).round() as usize
+                };
+                let c = shades[idx.min(shades.len() - 1)];
+                format!("{}{} ", c, c)
+            })
+            .collect();
+        println!("{:<4} {}{}", WEEKDAYS[day], cells, row.iter().sum::<u64>());
+    }
+    println!("──────────────────────────────────────────────────────────────────────────────");
+
+    match matrix.peak(metric) {
+        Some((day, hour, value)) => {
+            println!(
+                "Peak:         {} {:02}:00 ({} {})",
+                WEEKDAYS[day],
+                hour,
+                value,
+                metric.label().to_lowercase()
+            );
+            if let Some(d) = index_of_max(&matrix.day_totals(metric)) {
+                println!("Busiest day:  {}", WEEKDAYS[d]);
+            }
+            if let Some(h) = index_of_max(&matrix.hour_totals(metric)) {
+                println!("Busiest hour: {:02}:00", h);
+            }
+            println!("Total:        {}", matrix.total(metric));
+        }
+        None => println!("No activity recorded for this period."),
+    }
+
+    Ok(())
+}
+
+fn handle_rhythm_key(app: &mut App, key: KeyEvent) -> bool {
+    if handle_period_nav(app, key, StatsTarget::Rhythm) {
+        return true;
+    }
+
+    match key.code {
+        KeyCode::Char('m') => {
+            app.rhythm.metric = app.rhythm.metric.next();
+            true
+        }
+        _ => false,
+    }
+}
+
+/// The pulse-history matrix for the selected period, built once per period
+/// and pulse load rather than on every frame.
+fn matrix_from_pulses(app: &App) -> RhythmMatrix {
+    let key = (
+        app.rhythm.period,
+        app.date_picker.start_date,
+        app.date_picker.end_date,
+    );
+    let mut cache = app.rhythm.pulse_matrix.borrow_mut();
+    if let Some((cached_key, matrix)) = cache.as_ref()
+        && *cached_key == key
+    {
+        return matrix.clone();
+    }
+    let matrix = RhythmMatrix::from_pulses(&app.recent_pulses, |d| {
+        is_date_in_period(d, app.rhythm.period, &app.date_picker)
+    });
+    *cache = Some((key, matrix.clone()));
+    matrix
+}
+
+fn render_rhythm(f: &mut Frame, app: &App, area: Rect) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(10),   // Matrix + Summary
+            Constraint::Length(3), // Period Tabs
+        ])
+        .split(area);
+
+    let main_chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Min(0), Constraint::Length(32)])
+        .split(chunks[0]);
+
+    // Prefer hourly rows from the local DB; otherwise derive from pulse history.
+    let pulse_matrix;
+    let (matrix, source) = match &app.rhythm.matrix {
+        Some(m) => (m, "Local DB"),
+        None => {
+            pulse_matrix = matrix_from_pulses(app);
+            (&pulse_matrix, "Pulses")
+        }
+    };
+    let metric = app.rhythm.metric;
+
+    render_matrix(f, matrix, metric, source, main_chunks[0]);
+    render_summary(f, app, matrix, metric, main_chunks[1]);
+    render_period_tabs(f, app.rhythm.period, chunks[1]);
+
+    if app.date_picker.open {
+        crate::tui::ui::render_date_picker(f, app, area);
+    }
+}
+
+fn render_matrix(
+    f: &mut Frame,
+    matrix: &RhythmMatrix,
+    metric: RhythmMetric,
+    source: &str,
+    area: Rect,
+) {
+    let block = Block::default().borders(Borders::ALL).title(format!(
+        " Activity Rhythm - {} ({}) (m: Metric) ",
+        metric.label(),
+        source
+    ));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let max = matrix.max(metric);
+    if max == 0 {
+        f.render_widget(
+            Paragraph::new("No activity recorded for this period.")
+                .style(Style::default().fg(Color::DarkGray)),
+            inner,
+        );
+        return;
+    }
+
+    let label_width = 5;
+    let cell_width = (inner.width.saturating_sub(label_width) / 24).clamp(2, 4) as usize;
+    let cell_height = (inner.height.saturating_sub(1) / 7).clamp(1, 2) as usize;
+
+    let mut lines = Vec::new();
+    let mut header = vec![Span::raw(" ".repeat(label_width as usize))];
+    for hour in 0..24 {
+        let label = if cell_width >= 3 || hour % 2 == 0 {
+            format!("{:<width$}", format!("{:02}", hour), width = cell_width)
+        } else {
+            " ".repeat(cell_width)
+        };
+        header.push(Span::styled(label, Style::default().fg(Color::DarkGray)));
+    }
+    lines.push(Line::from(header));
+
+    for (day, row) in matrix.cells(metric).iter().enumerate() {
+        for sub_row in 0..cell_height {
+            let label = if sub_row == 0 { WEEKDAYS[day] } else { "" };
+            let mut spans = vec![Span::styled(
+                format!("{:<width$}", label, width = label_width as usize),
+                Style::default().fg(Color::Cyan),
+            )];
+            for &value in row {
+                spans.push(Span::styled(
+                    format!("{:<width$}", " ", width = cell_width - 1),
+                    Style::default().bg(get_color(value, max)),
+                ));
+                spans.push(Span::raw(" "));
+            }
+            lines.push(Line::from(spans));
+        }
+    }
+
+    f.render_widget(Paragraph::new(lines), inner);
+}
+
+fn render_summary(
+    f: &mut Frame,
+    app: &App,
+    matrix: &RhythmMatrix,
+    metric: RhythmMetric,
+    area: Rect,
+) {
+    let label_style = Style::default().fg(Color::Gray);
+    let value_style = Style::default()
+        .fg(Color::White)
+        .add_modifier(Modifier::BOLD);
+
+    let mut text = vec![Line::from(vec![
+        Span::styled("Total:        ", label_style),
+        Span::styled(matrix.total(metric).to_string(), value_style),
+    ])];
+
+    if let Some((day, hour, value)) = matrix.peak(metric) {
+        text.push(Line::from(vec![
+            Span::styled("Peak:         ", label_style),
+            Span::styled(format!("{} {:02}:00", WEEKDAYS[day], hour), value_style),
+        ]));
+        text.push(Line::from(vec![
+            Span::styled("Peak count:   ", label_style),
+            Span::styled(value.to_string(), value_style),
+        ]));
+    }
+    if let Some(day) = index_of_max(&matrix.day_totals(metric)) {
+        text.push(Line::from(vec![
+            Span::styled("Busiest day:  ", label_style),
+            Span::styled(WEEKDAYS[day], value_style),
+        ]));
+    }
+    if let Some(hour) = index_of_max(&matrix.hour_totals(metric)) {
+        text.push(Line::from(vec![
+            Span::styled("Busiest hour: ", label_style),
+            Span::styled(format!("{:02}:00", hour), value_style),
+        ]));
+    }
+
+    if let Some(err) = &app.rhythm.error {
+        text.push(Line::from(""));
+        text.push(Line::from(Span::styled(
+            format!("DB: {}", err),
+            Style::default().fg(Color::DarkGray),
+        )));
+    }
+
+    // Per-metric totals for context, regardless of which one is on the grid.
+    text.push(Line::from(""));
+    for m in [
+        RhythmMetric::Keys,
+        RhythmMetric::Clicks,
+        RhythmMetric::Scrolls,
+    ] {
+        let style = if m == metric {
+            Style::default().fg(Color::Cyan)
+        } else {
+            label_style
+        };
+        text.push(Line::from(Span::styled(
+            format!("{:<8} {}", m.label(), matrix.total(m)),
+            style,
+        )));
+    }
+
+    let summary = Paragraph::new(text).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(" Summary ")
+            .title_alignment(Alignment::Center),
+    );
+    f.render_widget(summary, area);
+}
+
+fn render_period_tabs(f: &mut Frame, period: TimePeriod, area: Rect) {
+    let periods = [
+        "Today",
+        "Yesterday",
+        "Week",
+        "Month",
+        "Year",
+        "All",
+        "Custom",
+    ];
+
+    let titles: Vec<Line> = periods
+        .iter()
+        .map(|t| Line::from(Span::styled(*t, Style::default().fg(Color::Gray))))
+        .collect();
+
+    let selected_index = match period {
+        TimePeriod::Today => 0,
+        TimePeriod::Yesterday => 1,
+        TimePeriod::Week => 2,
+        TimePeriod::Month => 3,
+        TimePeriod::Year => 4,
+        TimePeriod::All => 5,
+        TimePeriod::Custom => 6,
+    };
+
+    let tabs = Tabs::new(titles)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(" Time Period (h/l: Cycle | /: Custom Date) "),
+        )
+        .select(selected_index)
+        .style(Style::default().fg(Color::White))
+        .highlight_style(
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+        );
+
+    f.render_widget(tabs, area);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pulse(date: &str, keys: u64, uptime: u64) -> PulseResponse {
+        PulseResponse {
+            id: 1,
+            date: date.to_string(),
+            keys: Some(keys),
+            clicks: Some(0),
+            download_mb: None,
+            upload_mb: None,
+            uptime_seconds: Some(uptime),
+            scrolls: None,
+            distance_miles: None,
+            auto_pulse: None,
+            client_version: None,
+        }
+    }
+
+    #[test]
+    fn test_from_hourly_buckets_by_weekday() {
+        // 2024-01-01 was a Monday, 2024-01-03 a Wednesday.
+        let rows = vec![
+            HourlyActivity {
+                day: "2024-01-01".to_string(),
+                hour: 9,
+                keys: 100,
+                clicks: 10,
+                scrolls: 1,
+            },
+            HourlyActivity {
+                day: "2024-01-08".to_string(),
+                hour: 9,
+                keys: 50,
+                clicks: 0,
+                scrolls: 0,
+            },
+            HourlyActivity {
+                day: "2024-01-03".to_string(),
+                hour: 23,
+                keys: 7,
+                clicks: 0,
+                scrolls: 0,
+            },
+        ];
+        let matrix = RhythmMatrix::from_hourly(&rows);
+        assert_eq!(matrix.keys[0][9], 150);
+        assert_eq!(matrix.clicks[0][9], 10);
+        assert_eq!(matrix.keys[2][23], 7);
+        assert_eq!(matrix.peak(RhythmMetric::Keys), Some((0, 9, 150)));
+        assert_eq!(matrix.total(RhythmMetric::Keys), 157);
+    }
+
+    #[test]
+    fn test_from_pulses_spreads_over_uptime() {
+        // Two hours of uptime ending Monday 2024-01-01 12:00 local time.
+        let pulses = vec![pulse("2024-01-01 12:00:00", 200, 7200)];
+        let matrix = RhythmMatrix::from_pulses(&pulses, |_| true);
+        assert_eq!(matrix.keys[0][10], 100);
+        assert_eq!(matrix.keys[0][11], 100);
+        assert_eq!(matrix.total(RhythmMetric::Keys), 200);
+
+        let filtered = RhythmMatrix::from_pulses(&pulses, |_| false);
+        assert_eq!(filtered.total(RhythmMetric::Keys), 0);
+    }
+}
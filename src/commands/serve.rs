@@ -0,0 +1,204 @@
+use crate::client::WhatpulseClient;
+use crate::db::Database;
+use crate::http::{self, Request};
+use crate::realtime::{RealtimeData, RealtimeEvent, RealtimeHub};
+use crate::tui::period_utils::{is_date_in_period, parse_period_string};
+use anyhow::{Context, Result};
+use serde::Serialize;
+use serde_json::json;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::broadcast;
+
+const JSON: &str = "application/json";
+
+struct ServerState {
+    client: WhatpulseClient,
+    hub: RealtimeHub,
+}
+
+enum Response {
+    Json(&'static str, serde_json::Value),
+    EventStream,
+}
+
+fn error(status: &'static str, message: impl ToString) -> Response {
+    Response::Json(status, json!({ "error": message.to_string() }))
+}
+
+fn ok(value: impl Serialize) -> Response {
+    match serde_json::to_value(value) {
+        Ok(v) => Response::Json("200 OK", v),
+        Err(e) => error("500 Internal Server Error", e),
+    }
+}
+
+/// Runs a `Database` query off the async runtime.
+async fn query_db<T, F>(f: F) -> Response
+where
+    T: Serialize + Send + 'static,
+    F: FnOnce(&Database) -> Result<T> + Send + 'static,
+{
+    let res = tokio::task::spawn_blocking(move || -> Result<T> {
+        let db = Database::new()?;
+        f(&db)
+    })
+    .await;
+    match res {
+        Ok(Ok(value)) => ok(value),
+        Ok(Err(e)) => error("500 Internal Server Error", e),
+        Err(e) => error("500 Internal Server Error", e),
+    }
+}
+
+async fn route(req: &Request, state: &ServerState) -> Response {
+    if req.method != "GET" {
+        return error("405 Method Not Allowed", "only GET is supported");
+    }
+
+    let period = req.param("period").unwrap_or("all").to_string();
+    let (time_period, picker) = match parse_period_string(&period) {
+        Ok(parsed) => parsed,
+        Err(e) => return error("400 Bad Request", e),
+    };
+
+    match req.path.as_str() {
+        "/api/user" => match state.client.get_user().await {
+            Ok(user) => ok(user),
+            Err(e) => error("502 Bad Gateway", e),
+        },
+        "/api/pulses" => match state.client.get_pulses().await {
+            Ok(pulses) => {
+                let pulses: Vec<_> = pulses
+                    .into_iter()
+                    .filter(|p| {
+                        p.day()
+                            .is_some_and(|d| is_date_in_period(d, time_period, &picker))
+                    })
+                    .collect();
+                ok(pulses)
+            }
+            Err(e) => error("502 Bad Gateway", e),
+        },
+        "/api/computers" => match state.client.get_computers().await {
+            Ok(computers) => ok(computers),
+            Err(e) => error("502 Bad Gateway", e),
+        },
+        "/api/apps" => query_db(move |db| db.get_app_stats(&period)).await,
+        "/api/network" => query_db(move |db| db.get_network_stats(&period)).await,
+        "/api/mouse" => query_db(move |db| db.get_mouse_stats(&period)).await,
+        "/api/heatmap" => query_db(move |db| db.get_heatmap_stats(&period)).await,
+        "/api/daily" => query_db(move |db| db.get_daily_totals(&period)).await,
+        "/api/realtime" => ok(state.hub.state().latest),
+        "/api/realtime/stream" => Response::EventStream,
+        _ => error("404 Not Found", "unknown endpoint"),
+    }
+}
+
+/// Formats one Server-Sent Event carrying a realtime update.
+pub fn sse_event(data: &RealtimeData) -> String {
+    let payload = serde_json::to_string(data).unwrap_or_else(|_| "null".to_string());
+    format!("event: realtime\ndata: {}\n\n", payload)
+}
+
+async fn stream_realtime(mut stream: TcpStream, state: &ServerState) -> Result<()> {
+    let mut rx = state.hub.subscribe();
+    stream
+        .write_all(
+            b"HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-cache\r\nConnection: keep-alive\r\n\r\n",
+        )
+        .await?;
+
+    if let Some(data) = state.hub.state().latest {
+        stream.write_all(sse_event(&data).as_bytes()).await?;
+    }
+
+    // Comments keep proxies from timing out and surface dead clients on write.
+    let mut keep_alive = tokio::time::interval(Duration::from_secs(15));
+    loop {
+        tokio::select! {
+            msg = rx.recv() => match msg {
+                Ok(RealtimeEvent::Update(data)) => stream.write_all(sse_event(&data).as_bytes()).await?,
+                Ok(_) => continue,
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+            _ = keep_alive.tick() => stream.write_all(b": keep-alive\n\n").await?,
+        }
+    }
+    Ok(())
+}
+
+async fn handle_connection(mut stream: TcpStream, state: Arc<ServerState>) -> Result<()> {
+    let request = http::read_request(&mut stream).await?;
+    log::info!("serve: {} {}", request.method, request.path);
+
+    match route(&request, &state).await {
+        Response::Json(status, body) => {
+            http::write_response(&mut stream, status, JSON, &body.to_string()).await
+        }
+        Response::EventStream => stream_realtime(stream, &state).await,
+    }
+}
+
+pub async fn execute(client: &WhatpulseClient, listen: &str) -> Result<()> {
+    let listener = TcpListener::bind(listen)
+        .await
+        .with_context(|| format!("Failed to listen on {}", listen))?;
+    println!(
+        "Serving wtfpulse API on http://{}/api",
+        listener.local_addr()?
+    );
+    println!("Press Ctrl+C to exit.");
+
+    let state = Arc::new(ServerState {
+        client: client.clone(),
+        hub: RealtimeHub::spawn(),
+    });
+
+    loop {
+        tokio::select! {
+            accepted = listener.accept() => match accepted {
+                Ok((stream, _)) => {
+                    let state = state.clone();
+                    tokio::spawn(async move {
+                        if let Err(e) = handle_connection(stream, state).await {
+                            log::debug!("serve: request failed: {}", e);
+                        }
+                    });
+                }
+                Err(e) => log::warn!("Accept failed: {}", e),
+            },
+            _ = tokio::signal::ctrl_c() => {
+                println!("\nExiting...");
+                break;
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_sse_event_is_a_single_data_line() {
+        let data = RealtimeData {
+            unpulsed_keys: 10,
+            unpulsed_clicks: 2,
+            unpulsed_scrolls: 0,
+            keys_per_second: 1.5,
+            heatmap: HashMap::new(),
+            ..Default::default()
+        };
+        let event = sse_event(&data);
+        assert!(event.starts_with("event: realtime\ndata: {"));
+        assert!(event.ends_with("}\n\n"));
+        assert_eq!(event.matches('\n').count(), 3);
+        assert!(event.contains("\"keys_per_second\":1.5"));
+    }
+}
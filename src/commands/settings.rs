@@ -42,6 +42,7 @@ pub fn render_settings(f: &mut Frame, app: &App, area: Rect) {
         .constraints([
             Constraint::Length(3), // Refresh Rate
             Constraint::Length(3), // API Key
+            Constraint::Length(8), // Auto-pulse
             Constraint::Min(0),    // Instructions
         ])
         .split(area);
@@ -91,6 +92,8 @@ pub fn render_settings(f: &mut Frame, app: &App, area: Rect) {
 
     f.render_widget(Paragraph::new(key_text).block(key_block), chunks[1]);
 
+    render_auto_pulse(f, app, chunks[2]);
+
     // Instructions
     let mut instructions = vec![Line::from(Span::styled(
         "Controls:",
@@ -106,12 +109,56 @@ pub fn render_settings(f: &mut Frame, app: &App, area: Rect) {
             "  r: Cycle Refresh Rate (1s, 5s, 10s, 30s, 60s)",
         ));
         instructions.push(Line::from("  e: Edit API Key"));
+        instructions.push(Line::from("  d: Toggle Auto-pulse Dry Run"));
         instructions.push(Line::from("  S: Save Configuration"));
     }
 
     let instr_block = Block::default().borders(Borders::ALL).title(" Help ");
 
-    f.render_widget(Paragraph::new(instructions).block(instr_block), chunks[2]);
+    f.render_widget(Paragraph::new(instructions).block(instr_block), chunks[3]);
+}
+
+fn render_auto_pulse(f: &mut Frame, app: &App, area: Rect) {
+    let rules = &app.auto_pulse;
+    let mut lines = vec![Line::from(vec![
+        Span::raw("Rules: "),
+        Span::styled(
+            rules.describe(),
+            Style::default().fg(if rules.is_active() {
+                Color::Green
+            } else {
+                Color::DarkGray
+            }),
+        ),
+    ])];
+
+    // Newest first, as many as fit below the rules line.
+    let room = area.height.saturating_sub(3) as usize;
+    if rules.log.is_empty() {
+        lines.push(Line::from(Span::styled(
+            "No auto-pulses yet",
+            Style::default().fg(Color::DarkGray),
+        )));
+    }
+    for entry in rules.log.iter().rev().take(room) {
+        lines.push(Line::from(vec![
+            Span::styled(
+                format!("{} ", entry.at.format("%H:%M:%S")),
+                Style::default().fg(Color::DarkGray),
+            ),
+            Span::styled(
+                entry.to_string(),
+                Style::default().fg(if entry.dry_run {
+                    Color::Yellow
+                } else {
+                    Color::White
+                }),
+            ),
+        ]));
+    }
+
+    let block = Block::default().borders(Borders::ALL).title(" Auto-pulse ");
+    f.render_widget(Paragraph::new(lines).block(block), area);
 }
 
 pub fn handle_settings_key(app: &mut App, key: KeyEvent) -> bool {
@@ -177,6 +224,16 @@ pub fn handle_settings_key(app: &mut App, key: KeyEvent) -> bool {
                 app.refresh_rate = std::time::Duration::from_secs(next);
                 true
             }
+            KeyCode::Char('d') => {
+                let dry_run = !app.auto_pulse.dry_run;
+                app.auto_pulse.dry_run = dry_run;
+                app.config.auto_pulse.get_or_insert_default().dry_run = dry_run;
+                app.set_notification(format!(
+                    "Auto-pulse dry run {}",
+                    if dry_run { "on" } else { "off" }
+                ));
+                true
+            }
             KeyCode::Char('e') => {
                 app.is_editing_api_key = true;
                 app.api_key_input = app.config.api_key.clone().unwrap_or_default();
@@ -6,17 +6,26 @@ use crossterm::{
 };
 use ratatui::{Terminal, backend::CrosstermBackend};
 use std::io;
+use std::path::PathBuf;
+use std::time::Duration;
 use tokio::sync::mpsc;
 
 use crate::client::WhatpulseClient;
-use crate::commands::monitor::spawn_monitor_task;
+use crate::realtime::{self, PluginRequest, RealtimeHub};
 use crate::tui::{
-    app::{App, spawn_fetch},
+    app::{App, forward_realtime, spawn_fetch},
     event::start_event_listener,
     ui::draw,
 };
 
-pub async fn execute(client: &WhatpulseClient) -> Result<()> {
+pub async fn execute(client: &WhatpulseClient, replay: Option<PathBuf>, speed: f64) -> Result<()> {
+    // Load the recording before touching the terminal so errors stay readable.
+    let replaying = replay.is_some();
+    let hub = match replay {
+        Some(path) => RealtimeHub::replay(&path, speed)?,
+        None => RealtimeHub::spawn(),
+    };
+
     // 1. Setup Terminal
     enable_raw_mode()?;
     let mut stdout = io::stdout();
@@ -27,6 +36,12 @@ pub async fn execute(client: &WhatpulseClient) -> Result<()> {
     // 2. Setup App & Channels
     let (tx, mut rx) = mpsc::channel(10);
     let mut app = App::new(client.clone(), tx.clone());
+    if replaying {
+        // Rules would pulse the real client based on recorded data.
+        app.auto_pulse = Default::default();
+        // Replayed typing speed isn't a personal best.
+        app.wpm = realtime::wpm::WpmTracker::replaying();
+    }
 
     // 3. Spawn Event Listener
     start_event_listener(tx.clone());
@@ -34,14 +49,9 @@ pub async fn execute(client: &WhatpulseClient) -> Result<()> {
     // 4. Initial Data Fetch
     spawn_fetch(client.clone(), tx.clone());
 
-    // 5. Spawn Monitor Task (Real-time)
-    let tx_monitor = tx.clone();
-    let (monitor_tx, monitor_rx) = mpsc::channel(10);
-    app.set_monitor_tx(monitor_tx);
-
-    tokio::spawn(async move {
-        spawn_monitor_task(tx_monitor, monitor_rx).await;
-    });
+    // 5. Forward Real-time Events
+    app.set_monitor_tx(hub.commands());
+    tokio::spawn(forward_realtime(hub.clone(), tx.clone()));
 
     // 6. Main Loop
     loop {
@@ -64,6 +74,16 @@ pub async fn execute(client: &WhatpulseClient) -> Result<()> {
         DisableMouseCapture
     )?;
     terminal.show_cursor()?;
+    app.save_wpm_bests();
+
+    // The hub's connection goes away with the runtime, so the exit pulse
+    // gets its own.
+    if let Some(entry) = app.auto_pulse.check_exit(chrono::Local::now()) {
+        println!("{}", entry);
+        if !entry.dry_run {
+            realtime::send_once(PluginRequest::Pulse, Duration::from_secs(5)).await?;
+        }
+    }
 
     Ok(())
 }
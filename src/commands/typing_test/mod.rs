@@ -0,0 +1,674 @@
+use crate::commands::TuiPage;
+use crate::commands::keyboard::layouts::get_api_key_from_char;
+use crate::commands::keyboard::render_heatmap;
+use crate::config::TypingTestConfig;
+use crate::tui::app::App;
+use anyhow::{Context, Result, bail};
+use chrono::Local;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use directories::ProjectDirs;
+use rand::seq::IndexedRandom;
+use ratatui::{
+    Frame,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Wrap},
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+inventory::submit! {
+    TuiPage {
+        title: "Typing Test",
+        category: "Toys",
+        render: render_tui,
+        handle_key,
+        handle_mouse: crate::commands::default_handle_mouse,
+        priority: 31,
+    }
+}
+
+const DEFAULT_WORDS: usize = 25;
+const CHARS_PER_WORD: f64 = 5.0;
+/// Results shown in the history panel.
+const HISTORY_LEN: usize = 10;
+/// Keys need this many attempts before their error rate is listed.
+const MIN_KEY_ATTEMPTS: u32 = 3;
+
+const ENGLISH: &str = include_str!("words/english.txt");
+const CODE: &str = include_str!("words/code.txt");
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum WordSource {
+    English,
+    Code,
+    File(PathBuf),
+}
+
+impl WordSource {
+    pub fn name(&self) -> String {
+        match self {
+            WordSource::English => "english".to_string(),
+            WordSource::Code => "code".to_string(),
+            WordSource::File(path) => path.file_name().map_or_else(
+                || path.display().to_string(),
+                |n| n.to_string_lossy().into_owned(),
+            ),
+        }
+    }
+
+    pub fn load(&self) -> Result<Vec<String>> {
+        let words = match self {
+            WordSource::English => parse_words(ENGLISH),
+            WordSource::Code => parse_words(CODE),
+            WordSource::File(path) => parse_words(
+                &fs::read_to_string(path)
+                    .with_context(|| format!("Failed to read word list {}", path.display()))?,
+            ),
+        };
+        if words.is_empty() {
+            bail!("Word list '{}' is empty", self.name());
+        }
+        Ok(words)
+    }
+}
+
+/// Whitespace-separated words; lines starting with `#` are comments.
+pub fn parse_words(text: &str) -> Vec<String> {
+    text.lines()
+        .filter(|line| !line.trim_start().starts_with('#'))
+        .flat_map(str::split_whitespace)
+        .map(str::to_string)
+        .collect()
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TestResult {
+    /// Local time the test finished, "YYYY-MM-DD HH:MM".
+    pub finished_at: String,
+    pub source: String,
+    pub words: usize,
+    pub seconds: f64,
+    /// Correct characters only.
+    pub wpm: f64,
+    /// Every character keystroke, right or wrong.
+    pub raw_wpm: f64,
+    /// Share of keystrokes that were right, 0-100.
+    pub accuracy: f64,
+    /// Mistakes per expected key.
+    pub missed: BTreeMap<String, u32>,
+}
+
+fn results_path() -> Result<PathBuf> {
+    let proj_dirs = ProjectDirs::from("com", "wtfpulse", "wtfpulse")
+        .ok_or_else(|| anyhow::anyhow!("Could not determine home directory"))?;
+    Ok(proj_dirs.data_dir().join("typing_tests.ndjson"))
+}
+
+/// Appends a result to the local results file, one JSON object per line.
+pub fn save_result(result: &TestResult) -> Result<()> {
+    let path = results_path()?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create data directory at {:?}", parent))?;
+    }
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .with_context(|| format!("Failed to open typing results at {:?}", path))?;
+    writeln!(file, "{}", serde_json::to_string(result)?)?;
+    Ok(())
+}
+
+/// The most recent `limit` results, oldest first. Unreadable lines are skipped.
+pub fn load_results(limit: usize) -> Result<Vec<TestResult>> {
+    let path = results_path()?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read typing results at {:?}", path))?;
+    let results: Vec<TestResult> = content
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect();
+    Ok(results[results.len().saturating_sub(limit)..].to_vec())
+}
+
+#[derive(Debug, Default)]
+pub struct TypingTest {
+    pub sources: Vec<WordSource>,
+    pub source_index: usize,
+    pub word_count: usize,
+    pub target: Vec<char>,
+    pub typed: Vec<char>,
+    active: bool,
+    /// Set by the first keystroke.
+    started: Option<Instant>,
+    keystrokes: u32,
+    errors: u32,
+    key_attempts: HashMap<String, u32>,
+    key_errors: HashMap<String, u32>,
+    /// Keys pressed during the current (or last) test.
+    pub heatmap: HashMap<String, u64>,
+    pub last_result: Option<TestResult>,
+    /// Recent results, oldest first.
+    pub history: Vec<TestResult>,
+    pub error: Option<String>,
+}
+
+impl TypingTest {
+    pub fn new(config: Option<&TypingTestConfig>) -> Self {
+        let mut sources = vec![WordSource::English, WordSource::Code];
+        if let Some(path) = config.and_then(|c| c.words_file.clone()) {
+            sources.push(WordSource::File(path));
+        }
+        let (history, error) = match load_results(HISTORY_LEN) {
+            Ok(history) => (history, None),
+            Err(e) => (Vec::new(), Some(e.to_string())),
+        };
+        Self {
+            sources,
+            word_count: config.and_then(|c| c.words).unwrap_or(DEFAULT_WORDS).max(1),
+            history,
+            error,
+            ..Default::default()
+        }
+    }
+
+    pub fn source(&self) -> &WordSource {
+        &self.sources[self.source_index]
+    }
+
+    /// Whether a prompt is being typed; keys then belong to the test.
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+
+    /// Starts a test with random words from the selected source.
+    pub fn start(&mut self) {
+        match self.source().load() {
+            Ok(words) => {
+                let mut rng = rand::rng();
+                let prompt: Vec<&str> = (0..self.word_count)
+                    .filter_map(|_| words.choose(&mut rng).map(String::as_str))
+                    .collect();
+                self.start_with(&prompt.join(" "));
+            }
+            Err(e) => self.error = Some(e.to_string()),
+        }
+    }
+
+    pub fn start_with(&mut self, prompt: &str) {
+        *self = Self {
+            sources: std::mem::take(&mut self.sources),
+            source_index: self.source_index,
+            word_count: self.word_count,
+            target: prompt.chars().collect(),
+            active: true,
+            last_result: self.last_result.take(),
+            history: std::mem::take(&mut self.history),
+            ..Default::default()
+        };
+    }
+
+    pub fn cancel(&mut self) {
+        self.active = false;
+        self.started = None;
+    }
+
+    pub fn type_char(&mut self, c: char) {
+        let Some(&expected) = self.target.get(self.typed.len()) else {
+            return;
+        };
+        self.started.get_or_insert_with(Instant::now);
+        self.keystrokes += 1;
+        *self.heatmap.entry(get_api_key_from_char(c)).or_insert(0) += 1;
+
+        let key = get_api_key_from_char(expected);
+        *self.key_attempts.entry(key.clone()).or_insert(0) += 1;
+        if c != expected {
+            self.errors += 1;
+            *self.key_errors.entry(key).or_insert(0) += 1;
+        }
+        self.typed.push(c);
+    }
+
+    pub fn backspace(&mut self) {
+        if self.typed.pop().is_some() {
+            *self.heatmap.entry("BACKSPACE".to_string()).or_insert(0) += 1;
+        }
+    }
+
+    pub fn is_complete(&self) -> bool {
+        !self.target.is_empty() && self.typed.len() == self.target.len()
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.started.map_or(Duration::ZERO, |s| s.elapsed())
+    }
+
+    /// Ends the test and returns its score.
+    pub fn finish(&mut self) -> TestResult {
+        self.active = false;
+        let result = self.score(self.elapsed());
+        self.last_result = Some(result.clone());
+        self.history.push(result.clone());
+        if self.history.len() > HISTORY_LEN {
+            self.history.remove(0);
+        }
+        result
+    }
+
+    /// Scores the test as if it took `elapsed`.
+    pub fn score(&self, elapsed: Duration) -> TestResult {
+        let minutes = elapsed.as_secs_f64() / 60.0;
+        let per_minute = |chars: f64| {
+            // Under a second the rate is mostly noise from the first key.
+            if elapsed >= Duration::from_secs(1) {
+                chars / CHARS_PER_WORD / minutes
+            } else {
+                0.0
+            }
+        };
+        let correct = self
+            .typed
+            .iter()
+            .zip(&self.target)
+            .filter(|(typed, target)| typed == target)
+            .count();
+        TestResult {
+            finished_at: Local::now().format("%Y-%m-%d %H:%M").to_string(),
+            source: self.source().name(),
+            words: self.target.split(|c| *c == ' ').count(),
+            seconds: elapsed.as_secs_f64(),
+            wpm: per_minute(correct as f64),
+            raw_wpm: per_minute(self.keystrokes as f64),
+            accuracy: self.accuracy(),
+            missed: self
+                .key_errors
+                .iter()
+                .map(|(k, v)| (k.clone(), *v))
+                .collect(),
+        }
+    }
+
+    pub fn accuracy(&self) -> f64 {
+        if self.keystrokes == 0 {
+            return 100.0;
+        }
+        (self.keystrokes - self.errors) as f64 / self.keystrokes as f64 * 100.0
+    }
+
+    /// Error rate per expected key, worst first.
+    pub fn key_error_rates(&self) -> Vec<(String, f64)> {
+        let mut rates: Vec<(String, f64)> = self
+            .key_attempts
+            .iter()
+            .filter(|(_, attempts)| **attempts >= MIN_KEY_ATTEMPTS)
+            .filter_map(|(key, attempts)| {
+                let errors = *self.key_errors.get(key)?;
+                Some((key.clone(), errors as f64 / *attempts as f64 * 100.0))
+            })
+            .collect();
+        rates.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        rates
+    }
+}
+
+fn handle_key(app: &mut App, key: KeyEvent) -> bool {
+    let test = &mut app.typing_test;
+    if test.is_active() {
+        match key.code {
+            KeyCode::Esc => test.cancel(),
+            KeyCode::Backspace => test.backspace(),
+            // Shortcuts like Ctrl+W aren't typing.
+            KeyCode::Char(_)
+                if key
+                    .modifiers
+                    .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) => {}
+            KeyCode::Char(c) => {
+                test.type_char(c);
+                if test.is_complete() {
+                    let result = test.finish();
+                    if let Err(e) = save_result(&result) {
+                        log::warn!("Failed to save typing test result: {}", e);
+                    }
+                }
+            }
+            _ => {}
+        }
+        // Everything else is swallowed so typing can't switch pages.
+        return true;
+    }
+
+    match key.code {
+        KeyCode::Enter | KeyCode::Char(' ') => {
+            test.error = None;
+            test.start();
+            true
+        }
+        KeyCode::Char('w') => {
+            test.source_index = (test.source_index + 1) % test.sources.len();
+            true
+        }
+        _ => false,
+    }
+}
+
+fn render_tui(f: &mut Frame, app: &App, area: Rect) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3), // Status
+            Constraint::Length(6), // Prompt
+            Constraint::Length(8), // Results
+            Constraint::Min(5),    // Heatmap
+        ])
+        .split(area);
+
+    render_status(f, app, chunks[0]);
+    render_prompt(f, app, chunks[1]);
+
+    let result_chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage(35),
+            Constraint::Percentage(25),
+            Constraint::Percentage(40),
+        ])
+        .split(chunks[2]);
+    render_result(f, app, result_chunks[0]);
+    render_key_errors(f, app, result_chunks[1]);
+    render_history(f, app, result_chunks[2]);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!(" Test Heatmap ({}) ", app.keyboard.layout));
+    let inner = block.inner(chunks[3]);
+    f.render_widget(block, chunks[3]);
+    let keys = app.keyboard.layout.get_keys(app.keyboard.geometry);
+    render_heatmap(f, &keys, &app.typing_test.heatmap, inner);
+}
+
+fn render_status(f: &mut Frame, app: &App, area: Rect) {
+    let test = &app.typing_test;
+    let mut spans = vec![
+        Span::styled("Words: ", Style::default().fg(Color::Cyan)),
+        Span::raw(format!("{} x {}", test.source().name(), test.word_count)),
+    ];
+    if let Some(err) = &test.error {
+        spans.push(Span::styled(
+            format!(" | {}", err),
+            Style::default().fg(Color::Red),
+        ));
+    } else if test.is_active() {
+        let elapsed = test.elapsed();
+        spans.push(Span::raw(format!(
+            " | {:.1}s | {:.0} WPM | {:.1}% accuracy",
+            elapsed.as_secs_f64(),
+            test.score(elapsed).wpm,
+            test.accuracy()
+        )));
+        spans.push(Span::styled(
+            " | Esc: cancel",
+            Style::default().fg(Color::DarkGray),
+        ));
+    } else {
+        spans.push(Span::styled(
+            " | Enter: start, 'w': word list",
+            Style::default().fg(Color::DarkGray),
+        ));
+    }
+
+    let p = Paragraph::new(Line::from(spans)).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(" Typing Test "),
+    );
+    f.render_widget(p, area);
+}
+
+fn render_prompt(f: &mut Frame, app: &App, area: Rect) {
+    let test = &app.typing_test;
+    let block = Block::default().borders(Borders::ALL).title(" Prompt ");
+
+    if test.target.is_empty() || !test.is_active() && test.last_result.is_none() {
+        let p = Paragraph::new("Press Enter to start a test. The timer starts with the first key.")
+            .style(Style::default().fg(Color::DarkGray))
+            .block(block);
+        f.render_widget(p, area);
+        return;
+    }
+
+    let spans: Vec<Span> = test
+        .target
+        .iter()
+        .enumerate()
+        .map(|(i, &expected)| match test.typed.get(i) {
+            Some(&typed) if typed == expected => {
+                Span::styled(expected.to_string(), Style::default().fg(Color::Green))
+            }
+            // Show what was expected, so spaces typed wrong stay visible.
+            Some(_) => Span::styled(
+                expected.to_string(),
+                Style::default().fg(Color::White).bg(Color::Red),
+            ),
+            None if i == test.typed.len() && test.is_active() => Span::styled(
+                expected.to_string(),
+                Style::default().add_modifier(Modifier::REVERSED),
+            ),
+            None => Span::styled(expected.to_string(), Style::default().fg(Color::DarkGray)),
+        })
+        .collect();
+
+    let p = Paragraph::new(Line::from(spans))
+        .wrap(Wrap { trim: false })
+        .block(block);
+    f.render_widget(p, area);
+}
+
+fn render_result(f: &mut Frame, app: &App, area: Rect) {
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(" Last Result ");
+    let Some(result) = &app.typing_test.last_result else {
+        f.render_widget(Paragraph::new("No test finished yet.").block(block), area);
+        return;
+    };
+
+    let label = Style::default().fg(Color::Yellow);
+    let text = vec![
+        Line::from(vec![
+            Span::styled("WPM: ", label),
+            Span::styled(
+                format!("{:.0}", result.wpm),
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::raw(format!(" (raw {:.0})", result.raw_wpm)),
+        ]),
+        Line::from(vec![
+            Span::styled("Accuracy: ", label),
+            Span::raw(format!("{:.1}%", result.accuracy)),
+        ]),
+        Line::from(vec![
+            Span::styled("Time: ", label),
+            Span::raw(format!("{:.1}s, {} words", result.seconds, result.words)),
+        ]),
+        Line::from(vec![
+            Span::styled("Words: ", label),
+            Span::raw(result.source.clone()),
+        ]),
+    ];
+    f.render_widget(Paragraph::new(text).block(block), area);
+}
+
+fn render_key_errors(f: &mut Frame, app: &App, area: Rect) {
+    let block = Block::default().borders(Borders::ALL).title(" Key Errors ");
+    let visible = area.height.saturating_sub(2) as usize;
+    let rates = app.typing_test.key_error_rates();
+    let lines: Vec<Line> = if rates.is_empty() {
+        let message = if app.typing_test.errors == 0 {
+            "No mistakes"
+        } else {
+            "Too few tries per key yet"
+        };
+        vec![Line::from(Span::styled(
+            message,
+            Style::default().fg(Color::DarkGray),
+        ))]
+    } else {
+        rates
+            .iter()
+            .take(visible)
+            .map(|(key, rate)| {
+                Line::from(vec![
+                    Span::styled(format!("{:<10}", key), Style::default().fg(Color::Cyan)),
+                    Span::raw(format!("{:>5.1}%", rate)),
+                ])
+            })
+            .collect()
+    };
+    f.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+fn render_history(f: &mut Frame, app: &App, area: Rect) {
+    let history = &app.typing_test.history;
+    let best = history.iter().map(|r| r.wpm).fold(0.0, f64::max);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!(" Recent Results (best {:.0} WPM) ", best));
+    let visible = area.height.saturating_sub(2) as usize;
+
+    let lines: Vec<Line> = history
+        .iter()
+        .rev()
+        .take(visible)
+        .map(|r| {
+            Line::from(vec![
+                Span::styled(
+                    format!("{} ", r.finished_at),
+                    Style::default().fg(Color::DarkGray),
+                ),
+                Span::raw(format!(
+                    "{:>4.0} WPM {:>5.1}% {}",
+                    r.wpm, r.accuracy, r.source
+                )),
+            ])
+        })
+        .collect();
+    f.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn typed(prompt: &str, keys: &str) -> TypingTest {
+        let mut test = TypingTest {
+            sources: vec![WordSource::English],
+            ..Default::default()
+        };
+        test.start_with(prompt);
+        for c in keys.chars() {
+            if c == '<' {
+                test.backspace();
+            } else {
+                test.type_char(c);
+            }
+        }
+        test
+    }
+
+    #[test]
+    fn test_scores_wpm_accuracy_and_key_errors() {
+        // One wrong 'e', corrected.
+        let test = typed("the tree", "the trw<ee");
+        assert!(test.is_complete());
+        let result = test.score(Duration::from_secs(6));
+        // 8 correct chars in 6s = 1.6 words / 0.1 min.
+        assert!((result.wpm - 16.0).abs() < 1e-9);
+        assert!((result.raw_wpm - 18.0).abs() < 1e-9);
+        assert!((result.accuracy - 8.0 / 9.0 * 100.0).abs() < 1e-9);
+        assert_eq!(result.words, 2);
+        assert_eq!(result.missed.get("E"), Some(&1));
+        // Four attempts at E, one wrong; T was typed only twice.
+        assert_eq!(test.key_error_rates(), vec![("E".to_string(), 25.0)]);
+
+        assert_eq!(test.heatmap.get("BACKSPACE"), Some(&1));
+        assert_eq!(test.heatmap.get("W"), Some(&1));
+        assert_eq!(test.heatmap.get("SPACE"), Some(&1));
+    }
+
+    #[test]
+    fn test_typing_stops_at_the_end_of_the_prompt() {
+        let mut test = typed("ab", "abc");
+        assert_eq!(test.typed, vec!['a', 'b']);
+        test.finish();
+        assert!(!test.is_active());
+        assert_eq!(test.history.len(), 1);
+        // A new test keeps the history and the last result.
+        test.start_with("cd");
+        assert!(test.typed.is_empty() && test.heatmap.is_empty());
+        assert!(test.last_result.is_some());
+        assert_eq!(test.history.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_question_mark_goes_to_the_test() {
+        use crate::client::WhatpulseClient;
+        use crate::tui::app::Action;
+
+        let fake_token = "eyJhbGciOiJIUzI1NiIsInR5cCI6IkpXVCJ9.eyJzdWIiOiIxMjM0NSJ9.signature";
+        let client = WhatpulseClient::new(fake_token).await.unwrap();
+        let (tx, _rx) = tokio::sync::mpsc::channel(10);
+        let mut app = App::new(client, tx);
+        app.nav.current_tab = crate::commands::get_pages()
+            .iter()
+            .position(|p| p.title == "Typing Test")
+            .unwrap();
+
+        let key = |c| Action::Key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE));
+        app.update(key('?')).await;
+        assert!(app.show_help, "help still works outside a test");
+        app.update(key('?')).await;
+
+        app.typing_test.start_with("a?b");
+        app.update(key('a')).await;
+        app.update(key('?')).await;
+        assert!(!app.show_help);
+        assert_eq!(app.typing_test.typed, vec!['a', '?']);
+
+        // Modified keys are shortcuts, not typing.
+        for modifiers in [KeyModifiers::CONTROL, KeyModifiers::ALT] {
+            app.update(Action::Key(KeyEvent::new(KeyCode::Char('b'), modifiers)))
+                .await;
+        }
+        assert_eq!(app.typing_test.typed, vec!['a', '?']);
+        assert!(app.typing_test.is_active());
+    }
+
+    #[test]
+    fn test_word_lists() {
+        assert_eq!(
+            parse_words("# comment\nfoo  bar\n\n  baz\n"),
+            vec!["foo", "bar", "baz"]
+        );
+        for source in [WordSource::English, WordSource::Code] {
+            let words = source.load().unwrap();
+            assert!(words.len() > 100, "{} is too short", source.name());
+            assert!(words.iter().all(|w| !w.starts_with('#')));
+        }
+        assert!(
+            WordSource::File("/nonexistent/words.txt".into())
+                .load()
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,44 @@
+use crate::db::Database;
+use crate::key_mapping::qt_key_name;
+use crate::tui::period_utils::{get_display_period, parse_period_string};
+use anyhow::{Context, Result};
+
+/// Lists key ids in the local database that have no key name, so they can
+/// be added to the mapping.
+pub fn execute(period: &str) -> Result<()> {
+    let (time_period, _) = parse_period_string(period)?;
+    let counts = Database::new()
+        .and_then(|db| db.get_key_id_counts(period))
+        .context("Reading key counts from the local database")?;
+
+    let total: u64 = counts.iter().map(|(_, count)| count).sum();
+    let unmapped: Vec<&(i64, u64)> = counts
+        .iter()
+        .filter(|(id, _)| qt_key_name(*id).is_none())
+        .collect();
+
+    println!(
+        "\nUnmapped key ids ({}, {} ids in the local database)",
+        get_display_period(time_period),
+        counts.len()
+    );
+    println!("──────────────────────────────────────────────");
+    if unmapped.is_empty() {
+        println!("Every key id has a name.");
+        return Ok(());
+    }
+    println!("{:>12}  {:>12}  {:>12}", "Id", "Hex", "Presses");
+    for (id, count) in &unmapped {
+        println!("{:>12}  {:>#12x}  {:>12}", id, id, count);
+    }
+    let unmapped_total: u64 = unmapped.iter().map(|(_, count)| count).sum();
+    println!("──────────────────────────────────────────────");
+    println!(
+        "{} ids, {} of {} presses ({:.2}%), show as UNKNOWN_<id>.",
+        unmapped.len(),
+        unmapped_total,
+        total,
+        unmapped_total as f64 / total.max(1) as f64 * 100.0
+    );
+    Ok(())
+}
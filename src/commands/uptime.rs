@@ -1,7 +1,10 @@
 use crate::client::PulseResponse;
 use crate::commands::TuiPage;
-use crate::tui::app::{App, SelectionStep, TimePeriod};
-use crate::tui::period_utils::{cycle_period_next, cycle_period_prev, handle_date_picker_key};
+use crate::tui::app::{App, TimePeriod};
+use crate::tui::period_utils::{
+    cycle_period_next, cycle_period_prev, handle_date_picker_key, is_date_in_period,
+    open_date_picker,
+};
 use chrono::{Datelike, Days, Local, Months, NaiveDate};
 use crossterm::event::{KeyCode, KeyEvent};
 use ratatui::{
@@ -41,28 +44,12 @@ fn handle_uptime_key(app: &mut App, key: KeyEvent) -> bool {
         }
         KeyCode::Char('/') => {
             app.uptime_period = TimePeriod::Custom;
-            app.date_picker.open = true;
-            app.date_picker.selection_step = SelectionStep::Start;
-            if app.date_picker.start_date.is_none() {
-                app.date_picker.current_selection = chrono::Local::now().date_naive();
-            } else {
-                app.date_picker.current_selection = app.date_picker.start_date.unwrap();
-            }
+            open_date_picker(&mut app.date_picker);
             true
         }
-        KeyCode::Enter => {
-            if app.uptime_period == TimePeriod::Custom {
-                app.date_picker.open = true;
-                app.date_picker.selection_step = SelectionStep::Start;
-                if app.date_picker.start_date.is_none() {
-                    app.date_picker.current_selection = chrono::Local::now().date_naive();
-                } else {
-                    app.date_picker.current_selection = app.date_picker.start_date.unwrap();
-                }
-                true
-            } else {
-                false
-            }
+        KeyCode::Enter if app.uptime_period == TimePeriod::Custom => {
+            open_date_picker(&mut app.date_picker);
+            true
         }
         _ => false,
     }
@@ -78,38 +65,6 @@ fn parse_pulse_date(date_str: &str) -> NaiveDate {
     }
 }
 
-fn is_in_period(
-    date: NaiveDate,
-    period: TimePeriod,
-    date_picker: &crate::tui::app::DatePickerState,
-) -> bool {
-    let now = Local::now().date_naive();
-    match period {
-        TimePeriod::Today => date == now,
-        TimePeriod::Yesterday => date == now.pred_opt().unwrap(),
-        TimePeriod::Week => {
-            let week_ago = now.checked_sub_days(Days::new(7)).unwrap();
-            date >= week_ago && date <= now
-        }
-        TimePeriod::Month => {
-            let month_ago = now.checked_sub_months(Months::new(1)).unwrap();
-            date >= month_ago && date <= now
-        }
-        TimePeriod::Year => {
-            let year_ago = now.checked_sub_months(Months::new(12)).unwrap();
-            date >= year_ago && date <= now
-        }
-        TimePeriod::All => true,
-        TimePeriod::Custom => {
-            if let (Some(start), Some(end)) = (date_picker.start_date, date_picker.end_date) {
-                date >= start && date <= end
-            } else {
-                false
-            }
-        }
-    }
-}
-
 fn filter_pulses<'a>(
     pulses: &'a [PulseResponse],
     period: TimePeriod,
@@ -119,7 +74,7 @@ fn filter_pulses<'a>(
         .iter()
         .filter(|p| {
             let date = parse_pulse_date(&p.date);
-            is_in_period(date, period, date_picker)
+            is_date_in_period(date, period, date_picker)
         })
         .collect()
 }
@@ -393,7 +348,7 @@ fn render_uptime(f: &mut Frame, app: &App, area: Rect) {
             // Reboot detected
             let date = parse_pulse_date(&pulse.date);
 
-            if is_in_period(date, app.uptime_period, &app.date_picker) {
+            if is_date_in_period(date, app.uptime_period, &app.date_picker) {
                 // Find weekday of this pulse
                 let weekday = date.weekday(); // Mon=0, Sun=6
                 let day_str = match weekday {
@@ -1,6 +1,14 @@
 use crate::client::{PulseResponse, WhatpulseClient};
 use crate::commands::TuiPage;
-use crate::tui::app::{App, SelectionStep, TimePeriod};
+use crate::commands::forecast::{
+    ForecastMetric, MAX_HORIZON_DAYS, MilestoneEta, default_milestones, overview_etas, project,
+    series_from_daily_totals, series_from_pulses,
+};
+use crate::tui::app::{App, CompareBaseline, TimePeriod};
+use crate::tui::period_utils::{
+    get_display_period, handle_date_picker_state_key, open_date_picker, period_range,
+    previous_range,
+};
 use anyhow::Result;
 use chrono::{Days, Local, Months, NaiveDate};
 use crossterm::event::{KeyCode, KeyEvent};
@@ -9,7 +17,7 @@ use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Paragraph, Sparkline, Tabs},
+    widgets::{Block, Borders, Cell, Paragraph, Row, Sparkline, Table, Tabs},
 };
 
 inventory::submit! {
@@ -29,6 +37,11 @@ fn handle_key(app: &mut App, key: KeyEvent) -> bool {
         return true;
     }
 
+    if app.compare.picker_b.open {
+        handle_date_picker_state_key(&mut app.compare.picker_b, key);
+        return true;
+    }
+
     match key.code {
         KeyCode::Char('h') | KeyCode::Char('[') => {
             app.dashboard_period = match app.dashboard_period {
@@ -56,29 +69,33 @@ fn handle_key(app: &mut App, key: KeyEvent) -> bool {
         }
         KeyCode::Char('/') => {
             app.dashboard_period = TimePeriod::Custom;
-            app.date_picker.open = true;
-            app.date_picker.selection_step = SelectionStep::Start;
-            if app.date_picker.start_date.is_none() {
-                app.date_picker.current_selection = chrono::Local::now().date_naive();
-            } else {
-                app.date_picker.current_selection = app.date_picker.start_date.unwrap();
-            }
+            open_date_picker(&mut app.date_picker);
             true
         }
-        KeyCode::Enter => {
-            if app.dashboard_period == TimePeriod::Custom {
-                app.date_picker.open = true;
-
-                app.date_picker.selection_step = SelectionStep::Start;
-                if app.date_picker.start_date.is_none() {
-                    app.date_picker.current_selection = chrono::Local::now().date_naive();
-                } else {
-                    app.date_picker.current_selection = app.date_picker.start_date.unwrap();
-                }
-                true
-            } else {
-                false
-            }
+        KeyCode::Enter if app.dashboard_period == TimePeriod::Custom => {
+            open_date_picker(&mut app.date_picker);
+            true
+        }
+        KeyCode::Char('f') => {
+            app.forecast.method = app.forecast.method.next();
+            true
+        }
+        KeyCode::Char('c') if !app.client.is_local() => {
+            app.compare.enabled = !app.compare.enabled;
+            true
+        }
+        KeyCode::Char('b') if app.compare.enabled => {
+            app.compare.baseline = match app.compare.baseline {
+                CompareBaseline::PreviousPeriod => CompareBaseline::YearAgo,
+                CompareBaseline::YearAgo => CompareBaseline::Custom,
+                CompareBaseline::Custom => CompareBaseline::PreviousPeriod,
+            };
+            true
+        }
+        KeyCode::Char('B') if app.compare.enabled => {
+            app.compare.baseline = CompareBaseline::Custom;
+            open_date_picker(&mut app.compare.picker_b);
+            true
         }
         _ => false,
     }
@@ -91,6 +108,10 @@ pub async fn execute(client: &WhatpulseClient) -> Result<()> {
     Ok(())
 }
 
+fn pulse_date(pulse: &PulseResponse) -> NaiveDate {
+    pulse.day().unwrap_or(NaiveDate::MIN)
+}
+
 fn filter_pulses<'a>(
     pulses: &'a [PulseResponse],
     period: TimePeriod,
@@ -101,17 +122,7 @@ fn filter_pulses<'a>(
     pulses
         .iter()
         .filter(|p| {
-            // Try to parse ISO string first, fallback if needed
-            // Assuming format like "2023-01-01T12:00:00" or similar
-            let date = if let Ok(dt) =
-                chrono::NaiveDateTime::parse_from_str(&p.date, "%Y-%m-%d %H:%M:%S")
-            {
-                dt.date()
-            } else if let Ok(dt) = chrono::DateTime::parse_from_rfc3339(&p.date) {
-                dt.date_naive()
-            } else {
-                NaiveDate::from_ymd_opt(1970, 1, 1).unwrap()
-            };
+            let date = pulse_date(p);
 
             match period {
                 TimePeriod::Today => date == now,
@@ -159,20 +170,358 @@ pub fn render_tui(f: &mut Frame, app: &App, area: Rect) {
         .constraints(constraints)
         .split(area);
 
-    let content_chunks = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Length(15), // User Stats
-            Constraint::Min(10),    // Pulse Graph
-        ])
-        .split(chunks[0]);
+    if app.compare.enabled && !is_local {
+        render_compare(f, app, chunks[0]);
+    } else {
+        let content_chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(15), // User Stats
+                Constraint::Min(10),    // Pulse Graph
+            ])
+            .split(chunks[0]);
 
-    render_user_stats(f, app, content_chunks[0]);
-    render_pulse_graph(f, app, content_chunks[1]);
+        let bottom_chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([
+                Constraint::Min(0),     // Pulse Graph
+                Constraint::Length(38), // Forecast
+            ])
+            .split(content_chunks[1]);
+
+        render_user_stats(f, app, content_chunks[0]);
+        render_pulse_graph(f, app, bottom_chunks[0]);
+        render_forecast(f, app, bottom_chunks[1]);
+    }
 
     if !is_local {
         render_period_selector(f, app, chunks[1]);
     }
+
+    if app.compare.picker_b.open {
+        crate::tui::ui::render_date_picker_state(
+            f,
+            &app.compare.picker_b,
+            " Compare: Range B ",
+            area,
+        );
+    }
+}
+
+/// Projected daily keys for the Forecast box, empty without history. Made
+/// once per method and day; loading pulses or daily totals clears it.
+fn keys_projection(app: &App) -> Vec<(NaiveDate, f64)> {
+    let today = Local::now().date_naive();
+    let key = (app.forecast.method, today);
+    let mut cache = app.forecast.projection.borrow_mut();
+    if let Some((cached_key, projection)) = cache.as_ref()
+        && *cached_key == key
+    {
+        return projection.clone();
+    }
+
+    let mut series = Vec::new();
+    if !app.client.is_local() {
+        series = series_from_pulses(&app.recent_pulses, ForecastMetric::Keys, today);
+    }
+    if series.is_empty() {
+        series = series_from_daily_totals(&app.forecast.daily_totals, ForecastMetric::Keys, today);
+    }
+    let projection = project(&series, app.forecast.method, MAX_HORIZON_DAYS);
+    *cache = Some((key, projection.clone()));
+    projection
+}
+
+fn render_forecast(f: &mut Frame, app: &App, area: Rect) {
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(" Forecast (f: Method) ");
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let Some(current) = app.user_stats.as_ref().and_then(|u| u.totals.keys) else {
+        f.render_widget(Paragraph::new("Waiting for totals..."), inner);
+        return;
+    };
+
+    let projection = keys_projection(app);
+    if projection.is_empty() {
+        f.render_widget(
+            Paragraph::new("No daily history yet.").style(Style::default().fg(Color::DarkGray)),
+            inner,
+        );
+        return;
+    }
+
+    let mut milestones: Vec<u64> = app
+        .config
+        .milestones
+        .clone()
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|m| *m > current)
+        .collect();
+    if milestones.is_empty() {
+        milestones = default_milestones(current);
+    }
+    milestones.sort_unstable();
+    milestones.truncate(3);
+
+    let (per_day, etas) = overview_etas(&projection, current, &milestones);
+
+    let mut lines = vec![
+        Line::from(Span::styled(
+            app.forecast.method.label(),
+            Style::default().fg(Color::Cyan),
+        )),
+        Line::from(format!("~{:.0} keys/day", per_day)),
+        Line::from(""),
+    ];
+    for (milestone, eta) in etas {
+        let when = match eta {
+            MilestoneEta::Reached => "reached".to_string(),
+            MilestoneEta::On(date, days) => format!("{} ({}d)", date, days),
+            MilestoneEta::NotWithinHorizon => "> 10 years".to_string(),
+        };
+        lines.push(Line::from(vec![
+            Span::styled(
+                format!("{:>11} ", milestone),
+                Style::default().fg(Color::Yellow),
+            ),
+            Span::raw(when),
+        ]));
+    }
+
+    f.render_widget(Paragraph::new(lines), inner);
+}
+
+type DateRange = Option<(NaiveDate, NaiveDate)>;
+
+/// Totals over a set of pulses, used for the compare view.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PeriodTotals {
+    pub keys: u64,
+    pub clicks: u64,
+    pub download_mb: f64,
+    pub upload_mb: f64,
+    pub uptime_seconds: u64,
+    pub scrolls: u64,
+}
+
+impl PeriodTotals {
+    /// Sums pulses whose date falls within `range` (`None` means all time).
+    pub fn from_pulses(pulses: &[PulseResponse], range: DateRange) -> Self {
+        pulses
+            .iter()
+            .filter(|p| match range {
+                Some((start, end)) => {
+                    let date = pulse_date(p);
+                    date >= start && date <= end
+                }
+                None => true,
+            })
+            .fold(Self::default(), |mut acc, p| {
+                acc.keys += p.keys.unwrap_or(0);
+                acc.clicks += p.clicks.unwrap_or(0);
+                acc.download_mb += p.download_mb.unwrap_or(0.0);
+                acc.upload_mb += p.upload_mb.unwrap_or(0.0);
+                acc.uptime_seconds += p.uptime_seconds.unwrap_or(0);
+                acc.scrolls += p.scrolls.unwrap_or(0);
+                acc
+            })
+    }
+}
+
+/// Resolves the (A, B) ranges for compare mode. A is the selected dashboard
+/// period; B is derived from it unless a custom second range was picked.
+fn compare_ranges(app: &App) -> (DateRange, DateRange) {
+    let a = period_range(app.dashboard_period, &app.date_picker);
+    let b = match app.compare.baseline {
+        CompareBaseline::PreviousPeriod => a.map(previous_range),
+        CompareBaseline::YearAgo => a.and_then(|(start, end)| {
+            Some((
+                start.checked_sub_months(Months::new(12))?,
+                end.checked_sub_months(Months::new(12))?,
+            ))
+        }),
+        CompareBaseline::Custom => {
+            match (
+                app.compare.picker_b.start_date,
+                app.compare.picker_b.end_date,
+            ) {
+                (Some(start), Some(end)) => Some((start, end)),
+                _ => None,
+            }
+        }
+    };
+    (a, b)
+}
+
+fn range_label(range: DateRange) -> String {
+    match range {
+        Some((start, end)) if start == end => start.to_string(),
+        Some((start, end)) => format!("{} → {}", start, end),
+        None => "All time".to_string(),
+    }
+}
+
+/// Change from `b` to `a` as (absolute, percent); percent is `None` when `b` is zero.
+fn delta(a: f64, b: f64) -> (f64, Option<f64>) {
+    let diff = a - b;
+    let pct = if b.abs() > f64::EPSILON {
+        Some(diff / b * 100.0)
+    } else {
+        None
+    };
+    (diff, pct)
+}
+
+fn render_compare(f: &mut Frame, app: &App, area: Rect) {
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(" Compare (c: Exit | b: Baseline | B: Pick Range B) ");
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    if let Some(err) = &app.pulses_error {
+        f.render_widget(
+            Paragraph::new(format!("Error: {}", err)).style(Style::default().fg(Color::Red)),
+            inner,
+        );
+        return;
+    }
+
+    if app.pulses_loading && app.recent_pulses.is_empty() {
+        f.render_widget(Paragraph::new("Loading pulses..."), inner);
+        return;
+    }
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(0)])
+        .split(inner);
+
+    let (range_a, range_b) = compare_ranges(app);
+    let baseline_label = match app.compare.baseline {
+        CompareBaseline::PreviousPeriod => "Previous period",
+        CompareBaseline::YearAgo => "Same period last year",
+        CompareBaseline::Custom => "Custom range",
+    };
+
+    let label_style = Style::default().fg(Color::Gray);
+    let header = vec![
+        Line::from(vec![
+            Span::styled("A  ", Style::default().fg(Color::Cyan)),
+            Span::styled(
+                format!("{:<22}", get_display_period(app.dashboard_period)),
+                label_style,
+            ),
+            Span::raw(range_label(range_a)),
+        ]),
+        Line::from(vec![
+            Span::styled("B  ", Style::default().fg(Color::Magenta)),
+            Span::styled(format!("{:<22}", baseline_label), label_style),
+            Span::raw(match range_b {
+                Some(_) => range_label(range_b),
+                None if app.compare.baseline == CompareBaseline::Custom => {
+                    "(Press B to select dates)".to_string()
+                }
+                None => "(Not available for this period)".to_string(),
+            }),
+        ]),
+    ];
+    f.render_widget(Paragraph::new(header), chunks[0]);
+
+    let a = PeriodTotals::from_pulses(&app.recent_pulses, range_a);
+    let b = range_b.map(|r| PeriodTotals::from_pulses(&app.recent_pulses, Some(r)));
+
+    // (label, value A, value B, unit, decimals)
+    let metrics: [(&str, f64, Option<f64>, &str, usize); 6] = [
+        ("Keys", a.keys as f64, b.map(|b| b.keys as f64), "", 0),
+        ("Clicks", a.clicks as f64, b.map(|b| b.clicks as f64), "", 0),
+        (
+            "Download",
+            a.download_mb,
+            b.map(|b| b.download_mb),
+            " MB",
+            2,
+        ),
+        ("Upload", a.upload_mb, b.map(|b| b.upload_mb), " MB", 2),
+        (
+            "Uptime",
+            a.uptime_seconds as f64 / 3600.0,
+            b.map(|b| b.uptime_seconds as f64 / 3600.0),
+            " h",
+            1,
+        ),
+        (
+            "Scrolls",
+            a.scrolls as f64,
+            b.map(|b| b.scrolls as f64),
+            "",
+            0,
+        ),
+    ];
+
+    let rows: Vec<Row> = metrics
+        .iter()
+        .map(|&(label, value_a, value_b, unit, decimals)| {
+            let fmt = |v: f64| format!("{:.*}{}", decimals, v, unit);
+            let mut cells = vec![
+                Cell::from(label).style(Style::default().fg(Color::Cyan)),
+                Cell::from(fmt(value_a)),
+            ];
+            match value_b {
+                Some(value_b) => {
+                    let (diff, pct) = delta(value_a, value_b);
+                    let (arrow, color) = if diff > 0.0 {
+                        ("▲", Color::Green)
+                    } else if diff < 0.0 {
+                        ("▼", Color::Red)
+                    } else {
+                        ("=", Color::DarkGray)
+                    };
+                    cells.push(Cell::from(fmt(value_b)));
+                    cells.push(
+                        Cell::from(format!("{} {:+.*}{}", arrow, decimals, diff, unit))
+                            .style(Style::default().fg(color)),
+                    );
+                    cells.push(
+                        Cell::from(match pct {
+                            Some(p) => format!("{:+.1}%", p),
+                            None => "n/a".to_string(),
+                        })
+                        .style(Style::default().fg(color)),
+                    );
+                }
+                None => {
+                    cells.extend(["-", "-", "-"].map(Cell::from));
+                }
+            }
+            Row::new(cells)
+        })
+        .collect();
+
+    let header_style = Style::default()
+        .fg(Color::Yellow)
+        .add_modifier(Modifier::BOLD);
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(10),
+            Constraint::Percentage(25),
+            Constraint::Percentage(25),
+            Constraint::Percentage(25),
+            Constraint::Min(8),
+        ],
+    )
+    .header(
+        Row::new(["Metric", "A", "B", "Change", "%"].map(Cell::from))
+            .style(header_style)
+            .bottom_margin(1),
+    );
+
+    f.render_widget(table, chunks[1]);
 }
 
 fn render_period_selector(f: &mut Frame, app: &App, area: Rect) {
@@ -514,5 +863,55 @@ mod tests {
         let content = format!("{:?}", buffer);
         assert!(content.contains("TestUser"));
         assert!(content.contains("Time Period"));
+
+        // Case 3: Compare mode
+        app.compare.enabled = true;
+        app.dashboard_period = TimePeriod::Week;
+        terminal
+            .draw(|f| {
+                render_tui(f, &app, f.area());
+            })
+            .unwrap();
+        let content = format!("{:?}", terminal.backend().buffer());
+        assert!(content.contains("Compare"));
+    }
+
+    #[test]
+    fn test_period_totals_and_delta() {
+        let pulse = |date: &str, keys: u64| PulseResponse {
+            id: 1,
+            date: date.to_string(),
+            keys: Some(keys),
+            clicks: Some(keys / 2),
+            download_mb: Some(1.5),
+            upload_mb: None,
+            uptime_seconds: Some(3600),
+            scrolls: Some(10),
+            distance_miles: None,
+            auto_pulse: None,
+            client_version: None,
+        };
+        let pulses = vec![
+            pulse("2024-03-10 12:00:00", 100),
+            pulse("2024-03-11 12:00:00", 300),
+            pulse("2024-03-20 12:00:00", 1000),
+        ];
+
+        let day = |d: u32| NaiveDate::from_ymd_opt(2024, 3, d).unwrap();
+        let current = (day(10), day(11));
+        let a = PeriodTotals::from_pulses(&pulses, Some(current));
+        assert_eq!(a.keys, 400);
+        assert_eq!(a.clicks, 200);
+        assert_eq!(a.uptime_seconds, 7200);
+        assert!((a.download_mb - 3.0).abs() < 1e-9);
+
+        assert_eq!(previous_range(current), (day(8), day(9)));
+        let b = PeriodTotals::from_pulses(&pulses, Some(previous_range(current)));
+        assert_eq!(b, PeriodTotals::default());
+
+        assert_eq!(PeriodTotals::from_pulses(&pulses, None).keys, 1400);
+
+        assert_eq!(delta(150.0, 100.0), (50.0, Some(50.0)));
+        assert_eq!(delta(5.0, 0.0), (5.0, None));
     }
 }
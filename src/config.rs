@@ -8,6 +8,39 @@ use std::path::PathBuf;
 pub struct AppConfig {
     pub api_key: Option<String>,
     pub refresh_rate_seconds: Option<u64>,
+    /// Key-count milestones shown with an ETA on the Overview page.
+    pub milestones: Option<Vec<u64>>,
+    /// Rules for pulsing automatically while the TUI runs.
+    pub auto_pulse: Option<AutoPulseConfig>,
+    /// Options for the Typing Test page.
+    pub typing_test: Option<TypingTestConfig>,
+}
+
+/// `[auto_pulse]` section. Every rule is optional; with none set nothing is
+/// pulsed automatically.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[serde(default)]
+pub struct AutoPulseConfig {
+    /// Pulse once unpulsed keys reach this count.
+    pub unpulsed_keys: Option<u64>,
+    /// Local times of day to pulse at, as "HH:MM".
+    pub times: Vec<String>,
+    /// Pulse when the TUI exits.
+    pub on_exit: bool,
+    /// Log the pulses the rules would send without sending them.
+    pub dry_run: bool,
+    /// Minimum minutes between automatic pulses (default 5).
+    pub cooldown_minutes: Option<u64>,
+}
+
+/// `[typing_test]` section.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[serde(default)]
+pub struct TypingTestConfig {
+    /// Whitespace-separated word list offered next to the bundled ones.
+    pub words_file: Option<PathBuf>,
+    /// Words per test (default 25).
+    pub words: Option<usize>,
 }
 
 impl AppConfig {
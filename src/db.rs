@@ -1,10 +1,11 @@
 use anyhow::{Context, Result};
 use directories::BaseDirs;
 use rusqlite::{Connection, OpenFlags, OptionalExtension};
+use serde::Serialize;
 use std::collections::HashMap;
 use std::path::PathBuf;
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize)]
 pub struct MouseStats {
     pub clicks: u64,
     pub scrolls: u64,
@@ -12,7 +13,7 @@ pub struct MouseStats {
     pub clicks_by_button: HashMap<i64, u64>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct AppStats {
     pub name: String,
     pub keys: u64,
@@ -22,13 +23,32 @@ pub struct AppStats {
     pub upload_mb: f64,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct NetworkStats {
     pub interface: String,
     pub download_mb: f64,
     pub upload_mb: f64,
 }
 
+#[derive(Debug, Clone, Default)]
+pub struct HourlyActivity {
+    pub day: String,
+    pub hour: u32,
+    pub keys: u64,
+    pub clicks: u64,
+    pub scrolls: u64,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DailyTotals {
+    pub day: String,
+    pub keys: u64,
+    pub clicks: u64,
+    pub scrolls: u64,
+    pub download_mb: f64,
+    pub upload_mb: f64,
+}
+
 pub struct Database {
     path: PathBuf,
 }
@@ -176,11 +196,23 @@ impl Database {
     }
 
     pub fn get_heatmap_stats(&self, period: &str) -> Result<HashMap<String, u64>> {
+        let mut map = HashMap::new();
+        for (key_id, count) in self.get_key_id_counts(period)? {
+            let key_name = crate::key_mapping::map_key_id_to_name(key_id);
+            // Several ids share a name (shifted symbols, legacy codes), so sum them up
+            *map.entry(key_name).or_insert(0) += count;
+        }
+
+        Ok(map)
+    }
+
+    /// Presses per raw key id for the period, most pressed first.
+    pub fn get_key_id_counts(&self, period: &str) -> Result<Vec<(i64, u64)>> {
         let conn = self.get_connection()?;
         let where_clause = self.get_where_clause(period);
 
         let sql = format!(
-            "SELECT key, SUM(count) as total_count FROM keypress_frequency {} GROUP BY key",
+            "SELECT key, SUM(count) as total_count FROM keypress_frequency {} GROUP BY key ORDER BY total_count DESC",
             where_clause
         );
 
@@ -188,18 +220,22 @@ impl Database {
         let rows = stmt.query_map([], |row| {
             let key_id: i64 = row.get(0)?;
             let count: i64 = row.get(1)?;
-            Ok((key_id, count))
+            Ok((key_id, count.max(0) as u64))
         })?;
 
-        let mut map = HashMap::new();
-        for row in rows {
-            let (key_id, count) = row?;
-            let key_name = crate::key_mapping::map_key_id_to_name(key_id);
-            // Some keys might be duplicates in mapping (e.g. left/right shift?), so we sum them up
-            *map.entry(key_name).or_insert(0) += count as u64;
-        }
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    }
 
-        Ok(map)
+    /// Total keys for the period from the hourly `keypresses` table, or the
+    /// per-key `keypress_frequency` table on databases without it.
+    pub fn get_key_count(&self, period: &str) -> Result<u64> {
+        let conn = self.get_connection()?;
+        let where_clause = self.get_where_clause(period);
+
+        let table = key_table(&conn)?.context("Database has no key count tables")?;
+        let sql = format!("SELECT SUM(count) FROM {} {}", table, where_clause);
+        let count: Option<i64> = conn.query_row(&sql, [], |row| row.get(0))?;
+        Ok(count.unwrap_or(0).max(0) as u64)
     }
 
     pub fn get_mouse_heatmap_grid(
@@ -385,7 +421,7 @@ impl Database {
         }
 
         let mut result: Vec<AppStats> = map.into_values().collect();
-        result.sort_by(|a, b| b.keys.cmp(&a.keys));
+        result.sort_by_key(|a| std::cmp::Reverse(a.keys));
         Ok(result)
     }
 
@@ -429,6 +465,137 @@ impl Database {
         Ok(result)
     }
 
+    /// Per day/hour input totals from the hourly `keypresses`, `mouseclicks` and
+    /// `mousescrolls` tables. Older client databases only store daily rows, so
+    /// tables without an `hour` column are skipped; if none qualify this errors
+    /// and callers fall back to pulse history.
+    pub fn get_hourly_activity(&self, period: &str) -> Result<Vec<HourlyActivity>> {
+        let conn = self.get_connection()?;
+        let where_clause = self.get_where_clause(period);
+
+        let mut map: HashMap<(String, u32), HourlyActivity> = HashMap::new();
+        let mut found_hourly_table = false;
+
+        for table in ["keypresses", "mouseclicks", "mousescrolls"] {
+            if !table_has_column(&conn, table, "hour")? {
+                continue;
+            }
+            found_hourly_table = true;
+
+            let sql = format!(
+                "SELECT day, hour, SUM(count) FROM {} {} GROUP BY day, hour",
+                table, where_clause
+            );
+            let mut stmt = conn.prepare(&sql)?;
+            let rows = stmt.query_map([], |row| {
+                let day: String = row.get(0)?;
+                let hour: i64 = row.get(1)?;
+                let count: i64 = row.get(2)?;
+                Ok((day, hour, count))
+            })?;
+
+            for row in rows {
+                let (day, hour, count) = row?;
+                if !(0..24).contains(&hour) {
+                    continue;
+                }
+                let entry =
+                    map.entry((day.clone(), hour as u32))
+                        .or_insert_with(|| HourlyActivity {
+                            day,
+                            hour: hour as u32,
+                            ..Default::default()
+                        });
+                let count = count.max(0) as u64;
+                match table {
+                    "keypresses" => entry.keys += count,
+                    "mouseclicks" => entry.clicks += count,
+                    _ => entry.scrolls += count,
+                }
+            }
+        }
+
+        if !found_hourly_table {
+            return Err(anyhow::anyhow!("Database has no hourly input tables"));
+        }
+
+        let mut result: Vec<HourlyActivity> = map.into_values().collect();
+        result.sort_by(|a, b| (&a.day, a.hour).cmp(&(&b.day, b.hour)));
+        Ok(result)
+    }
+
+    /// Per-day totals across the input and bandwidth tables, ordered by day.
+    pub fn get_daily_totals(&self, period: &str) -> Result<Vec<DailyTotals>> {
+        let conn = self.get_connection()?;
+        let where_clause = self.get_where_clause(period);
+
+        let mut map: std::collections::BTreeMap<String, DailyTotals> =
+            std::collections::BTreeMap::new();
+
+        // Keys come from the same table as `get_key_count`, so the forecast
+        // and the Keyboard page agree. Tables the client doesn't keep are
+        // skipped.
+        let tables = [
+            (key_table(&conn)?, Input::Keys),
+            (Some("mouseclicks"), Input::Clicks),
+            (Some("mousescrolls"), Input::Scrolls),
+        ];
+        for (table, input) in tables {
+            let Some(table) = table else { continue };
+            if !table_has_column(&conn, table, "count")? {
+                continue;
+            }
+            let sql = format!(
+                "SELECT day, SUM(count) FROM {} {} GROUP BY day",
+                table, where_clause
+            );
+            let mut stmt = conn.prepare(&sql)?;
+            let rows = stmt.query_map([], |row| {
+                let day: String = row.get(0)?;
+                let count: i64 = row.get(1)?;
+                Ok((day, count))
+            })?;
+
+            for row in rows {
+                let (day, count) = row?;
+                let entry = map.entry(day.clone()).or_insert_with(|| DailyTotals {
+                    day,
+                    ..Default::default()
+                });
+                let count = count.max(0) as u64;
+                match input {
+                    Input::Keys => entry.keys += count,
+                    Input::Clicks => entry.clicks += count,
+                    Input::Scrolls => entry.scrolls += count,
+                }
+            }
+        }
+
+        let sql_bandwidth = format!(
+            "SELECT day, SUM(download), SUM(upload) FROM network_interface_bandwidth {} GROUP BY day",
+            where_clause
+        );
+        // Bandwidth tracking can be disabled in the client, so tolerate a missing table.
+        if let Ok(mut stmt) = conn.prepare(&sql_bandwidth) {
+            let rows = stmt.query_map([], |row| {
+                let day: String = row.get(0)?;
+                let down: i64 = row.get(1)?;
+                let up: i64 = row.get(2)?;
+                Ok((day, down, up))
+            })?;
+            for (day, down, up) in rows.flatten() {
+                let entry = map.entry(day.clone()).or_insert_with(|| DailyTotals {
+                    day,
+                    ..Default::default()
+                });
+                entry.download_mb += (down as f64) / 1024.0 / 1024.0;
+                entry.upload_mb += (up as f64) / 1024.0 / 1024.0;
+            }
+        }
+
+        Ok(map.into_values().collect())
+    }
+
     pub fn debug_tables(&self) -> Result<Vec<String>> {
         let conn = self.get_connection()?;
         let mut stmt = conn.prepare("SELECT name FROM sqlite_master WHERE type='table'")?;
@@ -442,10 +609,69 @@ impl Database {
     }
 }
 
+#[derive(Clone, Copy)]
+enum Input {
+    Keys,
+    Clicks,
+    Scrolls,
+}
+
+/// The table key totals are read from: the hourly `keypresses` table, or
+/// the per-key `keypress_frequency` table on databases without it.
+fn key_table(conn: &Connection) -> Result<Option<&'static str>> {
+    for table in ["keypresses", "keypress_frequency"] {
+        if table_has_column(conn, table, "count")? {
+            return Ok(Some(table));
+        }
+    }
+    Ok(None)
+}
+
+fn table_has_column(conn: &Connection, table: &str, column: &str) -> Result<bool> {
+    let mut stmt = conn.prepare(&format!("PRAGMA table_info({})", table))?;
+    let names = stmt.query_map([], |row| row.get::<_, String>(1))?;
+    for name in names {
+        if name? == column {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_key_count_falls_back_to_frequency_table() {
+        let path = std::env::temp_dir().join(format!("wtfpulse-keys-{}.db", std::process::id()));
+        let conn = Connection::open(&path).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE keypress_frequency(day TEXT, key INTEGER, count INTEGER);
+             INSERT INTO keypress_frequency VALUES ('2020-01-01', 65, 40), ('2020-01-02', 66, 2);",
+        )
+        .unwrap();
+        let db = Database { path: path.clone() };
+        assert_eq!(db.get_key_count("all").unwrap(), 42);
+        assert_eq!(db.get_key_count("custom:2020-01-02:2020-01-02").unwrap(), 2);
+        assert_eq!(db.get_key_count("today").unwrap(), 0);
+
+        conn.execute_batch(
+            "CREATE TABLE keypresses(day TEXT, hour INT, count INT);
+             INSERT INTO keypresses VALUES ('2020-01-01', 9, 100);",
+        )
+        .unwrap();
+        assert_eq!(db.get_key_count("all").unwrap(), 100);
+
+        // Daily totals read keys from the same table and skip the missing
+        // mouse tables.
+        let totals = db.get_daily_totals("all").unwrap();
+        assert_eq!(totals.len(), 1);
+        assert_eq!((totals[0].keys, totals[0].clicks), (100, 0));
+        drop(conn);
+        std::fs::remove_file(&path).unwrap();
+    }
+
     #[test]
     fn test_inspect_tables() {
         // This test requires a real DB, so it might fail on CI without one.
@@ -0,0 +1,83 @@
+//! Minimal HTTP/1.1 helpers for the local servers (`serve`, `serve-metrics`).
+//! Requests are assumed to be small GETs from trusted local tools, so there is
+//! no keep-alive, chunking or request body support.
+
+use anyhow::{Result, anyhow};
+use std::collections::HashMap;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Request {
+    pub method: String,
+    pub path: String,
+    pub query: HashMap<String, String>,
+}
+
+impl Request {
+    /// Parses the request line, e.g. `GET /api/apps?period=week HTTP/1.1`.
+    pub fn parse(head: &str) -> Result<Self> {
+        let line = head.lines().next().unwrap_or_default();
+        let mut parts = line.split_whitespace();
+        let method = parts.next().ok_or_else(|| anyhow!("Empty request"))?;
+        let target = parts
+            .next()
+            .ok_or_else(|| anyhow!("Missing request target"))?;
+
+        let (path, query) = target.split_once('?').unwrap_or((target, ""));
+        let query = url::form_urlencoded::parse(query.as_bytes())
+            .into_owned()
+            .collect();
+
+        Ok(Self {
+            method: method.to_string(),
+            path: path.to_string(),
+            query,
+        })
+    }
+
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.query.get(name).map(String::as_str)
+    }
+}
+
+pub async fn read_request(stream: &mut TcpStream) -> Result<Request> {
+    let mut buf = [0u8; 4096];
+    let n = stream.read(&mut buf).await?;
+    Request::parse(&String::from_utf8_lossy(&buf[..n]))
+}
+
+pub async fn write_response(
+    stream: &mut TcpStream,
+    status: &str,
+    content_type: &str,
+    body: &str,
+) -> Result<()> {
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        content_type,
+        body.len(),
+        body
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parses_path_and_query() {
+        let req =
+            Request::parse("GET /api/apps?period=custom%3A2025-01-01%3A2025-01-31&x HTTP/1.1\r\n")
+                .unwrap();
+        assert_eq!(req.method, "GET");
+        assert_eq!(req.path, "/api/apps");
+        assert_eq!(req.param("period"), Some("custom:2025-01-01:2025-01-31"));
+        assert_eq!(req.param("x"), Some(""));
+        assert!(Request::parse("").is_err());
+    }
+}
@@ -1,86 +1,441 @@
+//! Key names. The WhatPulse database stores Qt key codes; everything else
+//! in wtfpulse (heatmaps, layouts, the realtime feed) uses WhatPulse's key
+//! names such as `LEFTSHIFT` or `NUMPAD7`.
+
+use crate::commands::keyboard::layouts::get_api_key_from_char;
+
+/// Set on keys typed on the numeric keypad (`Qt::KeypadModifier`).
+const QT_KEYPAD: i64 = 0x2000_0000;
+/// `Qt::Key_F1`; F2 to F35 follow.
+const QT_F1: i64 = 0x0100_0030;
+
+/// Qt key codes that aren't plain characters, with their key names. Where
+/// several codes share a name the first is the one [`qt_key_for_name`]
+/// returns.
+const QT_KEYS: &[(i64, &str)] = &[
+    (0x0100_0000, "ESCAPE"),
+    (0x0100_0001, "TAB"),
+    (0x0100_0002, "TAB"), // Backtab, Shift+Tab
+    (0x0100_0003, "BACKSPACE"),
+    (0x0100_0004, "RETURN"),
+    (0x0100_0005, "NUMPADENTER"), // Key_Enter is the keypad's
+    (0x0100_0006, "INSERT"),
+    (0x0100_0007, "DELETE"),
+    (0x0100_0008, "PAUSE"),
+    (0x0100_0009, "PRINT"),
+    (0x0100_000a, "SYSREQ"),
+    (0x0100_000b, "CLEAR"),
+    (0x0100_0010, "HOME"),
+    (0x0100_0011, "END"),
+    (0x0100_0012, "LEFT"),
+    (0x0100_0013, "UP"),
+    (0x0100_0014, "RIGHT"),
+    (0x0100_0015, "DOWN"),
+    (0x0100_0016, "PAGEUP"),
+    (0x0100_0017, "PAGEDOWN"),
+    // Qt has one code for both Shift, Ctrl and Alt keys; see `sideless`.
+    (0x0100_0020, "SHIFT"),
+    (0x0100_0021, "CONTROL"),
+    (0x0100_0022, "META"),
+    (0x0100_0023, "ALT"),
+    (0x0100_0024, "CAPSLOCK"),
+    (0x0100_0025, "NUMLOCK"),
+    (0x0100_0026, "SCROLLLOCK"),
+    (0x0100_0053, "LEFTWINDOWS"),
+    (0x0100_0054, "RIGHTWINDOWS"),
+    (0x0100_0055, "MENU"),
+    (0x0100_0058, "HELP"),
+    (0x0100_1103, "RIGHTALT"), // AltGr
+    // Media and browser keys
+    (0x0100_0061, "BROWSERBACK"),
+    (0x0100_0062, "BROWSERFORWARD"),
+    (0x0100_0063, "BROWSERSTOP"),
+    (0x0100_0064, "BROWSERREFRESH"),
+    (0x0100_0070, "VOLUMEDOWN"),
+    (0x0100_0071, "VOLUMEMUTE"),
+    (0x0100_0072, "VOLUMEUP"),
+    (0x0100_0080, "MEDIAPLAY"),
+    (0x0100_0081, "MEDIASTOP"),
+    (0x0100_0082, "MEDIAPREVIOUS"),
+    (0x0100_0083, "MEDIANEXT"),
+    (0x0100_0084, "MEDIARECORD"),
+    (0x0100_0085, "MEDIAPAUSE"),
+    (0x0100_0086, "MEDIAPLAYPAUSE"),
+    (0x0100_0090, "BROWSERHOME"),
+    (0x0100_0091, "BROWSERFAVORITES"),
+    (0x0100_0092, "BROWSERSEARCH"),
+    (0x0100_0093, "STANDBY"),
+    (0x0100_00a0, "LAUNCHMAIL"),
+    (0x0100_00a1, "LAUNCHMEDIA"),
+    (0x0100_00a2, "LAUNCHAPP1"),
+    (0x0100_00a3, "LAUNCHAPP2"),
+    (0x0100_00cb, "CALCULATOR"),
+    // Japanese and Korean input keys
+    (0x0100_1121, "KANJI"),
+    (0x0100_1122, "MUHENKAN"),
+    (0x0100_1123, "HENKAN"),
+    (0x0100_1127, "KANA"), // Hiragana/Katakana
+    (0x0100_112a, "ZENKAKUHANKAKU"),
+    (0x0100_1131, "HANGUL"),
+    (0x0100_1134, "HANJA"),
+    // Characters whose key is named differently, shifted ones after the
+    // key's own character.
+    (0x20, "SPACE"),
+    (0x27, "QUOTE"),
+    (0x22, "QUOTE"),
+    (0x2c, "COMMA"),
+    (0x3c, "LESS"), // ISO's own key, as QMK's KC_NUBS; also Shift+, on US boards
+    (0x2d, "MINUS"),
+    (0x5f, "MINUS"),
+    (0x2e, "PERIOD"),
+    (0x3e, "PERIOD"),
+    (0x2f, "SLASH"),
+    (0x3f, "SLASH"),
+    (0x3b, "SEMICOLON"),
+    (0x3a, "SEMICOLON"),
+    (0x3d, "EQUAL"),
+    (0x2b, "EQUAL"),
+    (0x5b, "BRACKETLEFT"),
+    (0x7b, "BRACKETLEFT"),
+    (0x5c, "BACKSLASH"),
+    (0x7c, "BACKSLASH"),
+    (0x5d, "BRACKETRIGHT"),
+    (0x7d, "BRACKETRIGHT"),
+    (0x60, "GRAVE"),
+    (0x7e, "GRAVE"),
+    (0xc7, "CEDILLA"),
+    (0xd1, "NTILDE"),
+    (0xa5, "YEN"),
+    (0xdf, "SS"), // ß, as layouts upper-case it
+    // Older databases store Windows virtual keys for these.
+    (8, "BACKSPACE"),
+    (9, "TAB"),
+    (13, "RETURN"),
+    (20, "CAPSLOCK"),
+    (27, "ESCAPE"),
+];
+
+/// Dead keys, named after the key that types their accent on its own.
+const QT_DEAD_KEYS: &[(i64, char)] = &[
+    (0x0100_1250, '`'),
+    (0x0100_1251, '´'),
+    (0x0100_1252, '^'),
+    (0x0100_1253, '~'),
+    (0x0100_1254, '¯'),
+    (0x0100_1255, '˘'),
+    (0x0100_1256, '˙'),
+    (0x0100_1257, '¨'),
+    (0x0100_1258, '°'),
+    (0x0100_1259, '˝'),
+    (0x0100_125a, 'ˇ'),
+    (0x0100_125b, '¸'),
+    (0x0100_125c, '˛'),
+];
+
+/// Keypad keys by the character Qt reports for them.
+const KEYPAD: &[(char, &str)] = &[
+    ('/', "NUMPADDIVIDE"),
+    ('*', "NUMPADMULTIPLY"),
+    ('-', "NUMPADSUBTRACT"),
+    ('+', "NUMPADADD"),
+    ('.', "NUMPADDECIMAL"),
+    (',', "NUMPADDECIMAL"),
+    ('=', "NUMPADEQUAL"),
+];
+
+/// Key name for a key id from the WhatPulse database, or `UNKNOWN_<id>`.
 pub fn map_key_id_to_name(id: i64) -> String {
-    match id {
-        // Qt Key Codes (WhatPulse uses these)
-        16777216 => "ESCAPE".to_string(),
-        16777217 => "TAB".to_string(),
-        16777219 => "BACKSPACE".to_string(),
-        16777220 => "RETURN".to_string(),
-        16777221 => "ENTER".to_string(),
-        16777222 => "INSERT".to_string(),
-        16777223 => "DELETE".to_string(),
-        16777232 => "HOME".to_string(),
-        16777233 => "END".to_string(),
-        16777234 => "LEFT".to_string(),
-        16777235 => "UP".to_string(),
-        16777236 => "RIGHT".to_string(),
-        16777237 => "DOWN".to_string(),
-        16777238 => "PAGEUP".to_string(),
-        16777239 => "PAGEDOWN".to_string(),
-        16777252 => "CAPSLOCK".to_string(),
-        16777264..=16777275 => format!("F{}", id - 16777263), // F1-F12
-
-        // Legacy / ASCII mappings
-        8 => "BACKSPACE".to_string(),
-        9 => "TAB".to_string(),
-        13 => "RETURN".to_string(),
-        20 => "CAPSLOCK".to_string(),
-        27 => "ESCAPE".to_string(),
-        32 => "SPACE".to_string(),
-        33 => "1".to_string(),     // ! -> 1
-        34 => "QUOTE".to_string(), // "
-        35 => "3".to_string(),     // # -> 3
-        36 => "4".to_string(),     // $ -> 4
-        37 => "5".to_string(),     // % -> 5
-        38 => "7".to_string(),     // & -> 7
-        39 => "QUOTE".to_string(), // '
-        40 => "9".to_string(),     // ( -> 9
-        41 => "0".to_string(),     // ) -> 0
-        42 => "8".to_string(),     // * -> 8
-        43 => "EQUAL".to_string(), // + -> =
-        44 => "COMMA".to_string(),
-        45 => "MINUS".to_string(),
-        46 => "PERIOD".to_string(),
-        47 => "SLASH".to_string(),
-        48..=57 => ((id as u8) as char).to_string(), // 0-9
-        58 => "SEMICOLON".to_string(),               // : -> ;
-        59 => "SEMICOLON".to_string(),
-        60 => "COMMA".to_string(), // < -> ,
-        61 => "EQUAL".to_string(),
-        62 => "PERIOD".to_string(),                  // > -> .
-        63 => "SLASH".to_string(),                   // ? -> /
-        64 => "2".to_string(),                       // @ -> 2
-        65..=90 => ((id as u8) as char).to_string(), // A-Z
-        91 => "BRACKETLEFT".to_string(),
-        92 => "BACKSLASH".to_string(),
-        93 => "BRACKETRIGHT".to_string(),
-        94 => "6".to_string(),     // ^ -> 6
-        95 => "MINUS".to_string(), // _ -> -
-        96 => "GRAVE".to_string(),
-        97..=122 => ((id as u8 - 32) as char).to_string(), // a-z -> A-Z
-        123 => "BRACKETLEFT".to_string(),                  // { -> [
-        124 => "BACKSLASH".to_string(),                    // | -> \
-        125 => "BRACKETRIGHT".to_string(),                 // } -> ]
-        126 => "GRAVE".to_string(),                        // ~ -> `
-        // Function keys (approximate mapping if WhatPulse stores them as such)
-        // Modifiers?
-        // WhatPulse usually stores modifiers separately or not in keypress_frequency same way?
-        // Assuming standard ASCII mapping for now.
+    qt_key_name(id).unwrap_or_else(|| format!("UNKNOWN_{}", id))
+}
+
+/// Key name for a Qt key code, if it has one.
+pub fn qt_key_name(id: i64) -> Option<String> {
+    if id & QT_KEYPAD != 0 {
+        return keypad_name(id & !QT_KEYPAD);
+    }
+    if let Some((_, name)) = QT_KEYS.iter().find(|(code, _)| *code == id) {
+        return Some(name.to_string());
+    }
+    if let Some((_, c)) = QT_DEAD_KEYS.iter().find(|(code, _)| *code == id) {
+        return Some(get_api_key_from_char(*c));
+    }
+    if (QT_F1..QT_F1 + 35).contains(&id) {
+        return Some(format!("F{}", id - QT_F1 + 1));
+    }
+    // Anything else below the special keys is the character it types,
+    // named as layouts name it (shifted digits fold to their digit).
+    let c = char::from_u32(u32::try_from(id).ok()?)?;
+    (!c.is_control() && !c.is_whitespace()).then(|| get_api_key_from_char(c))
+}
+
+fn keypad_name(id: i64) -> Option<String> {
+    let c = u32::try_from(id).ok().and_then(char::from_u32);
+    if let Some(digit) = c.filter(char::is_ascii_digit) {
+        return Some(format!("NUMPAD{}", digit));
+    }
+    if let Some((_, name)) = KEYPAD.iter().find(|(k, _)| Some(*k) == c) {
+        return Some(name.to_string());
+    }
+    // Enter and, with Num Lock off, the navigation keys.
+    match qt_key_name(id)?.as_str() {
+        "RETURN" => Some("NUMPADENTER".to_string()),
+        name => Some(name.to_string()),
+    }
+}
+
+/// The Qt key code [`map_key_id_to_name`] turns into `name`.
+pub fn qt_key_for_name(name: &str) -> Option<i64> {
+    if let Some(rest) = name.strip_prefix("NUMPAD") {
+        let mut chars = rest.chars();
+        if let (Some(digit), None) = (chars.next(), chars.next())
+            && digit.is_ascii_digit()
+        {
+            return Some(QT_KEYPAD | digit as i64);
+        }
+        if let Some((c, _)) = KEYPAD.iter().find(|(_, n)| *n == name) {
+            return Some(QT_KEYPAD | *c as i64);
+        }
+    }
+    if let Some((code, _)) = QT_KEYS.iter().find(|(_, n)| *n == name) {
+        return Some(*code);
+    }
+    if let Some(n) = name.strip_prefix('F').and_then(|n| n.parse::<i64>().ok())
+        && (1..=35).contains(&n)
+    {
+        return Some(QT_F1 + n - 1);
+    }
+    let mut chars = name.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Some(c as i64),
+        _ => None,
+    }
+}
+
+/// The name a Qt key code gives a sided modifier: Qt can't tell left
+/// Shift from right Shift, so counts for both land on `SHIFT`.
+pub fn sideless(name: &str) -> Option<&'static str> {
+    match name {
+        "LEFTSHIFT" | "RIGHTSHIFT" => Some("SHIFT"),
+        "LEFTCONTROL" | "RIGHTCONTROL" => Some("CONTROL"),
+        "LEFTALT" | "RIGHTALT" => Some("ALT"),
+        "LEFTWINDOWS" | "RIGHTWINDOWS" => Some("META"),
+        _ => None,
+    }
+}
+
+/// WhatPulse key name for a QMK keycode such as `KC_LSFT`. Tap-hold and
+/// modded keycodes (`LT(1, KC_SPC)`, `LSFT_T(KC_A)`, `LCTL(KC_C)`) map to the
+/// key they send. Layer switches, `KC_TRNS`, `KC_NO` and firmware keycodes
+/// have no WhatPulse name.
+pub fn map_qmk_keycode(code: &str) -> Option<String> {
+    let code = code.trim();
+    if let Some(open) = code.find('(') {
+        let inner = code[open + 1..].trim_end_matches(')');
+        let last = inner.rsplit(',').next()?.trim();
+        return if last.starts_with("KC_") {
+            map_qmk_keycode(last)
+        } else {
+            None
+        };
+    }
+    let name = code.strip_prefix("KC_")?;
+    let mapped = match name {
+        _ if name.len() == 1 && name.chars().all(|c| c.is_ascii_alphanumeric()) => name,
+        "ENT" | "ENTER" => "RETURN",
+        "ESC" | "ESCAPE" => "ESCAPE",
+        "BSPC" | "BSPACE" | "BACKSPACE" => "BACKSPACE",
+        "TAB" => "TAB",
+        "SPC" | "SPACE" => "SPACE",
+        "MINS" | "MINUS" => "MINUS",
+        "EQL" | "EQUAL" => "EQUAL",
+        "LBRC" | "LBRACKET" | "LEFT_BRACKET" => "BRACKETLEFT",
+        "RBRC" | "RBRACKET" | "RIGHT_BRACKET" => "BRACKETRIGHT",
+        "BSLS" | "BSLASH" | "BACKSLASH" | "NUHS" | "NONUS_HASH" => "BACKSLASH",
+        "NUBS" | "NONUS_BACKSLASH" => "LESS",
+        "SCLN" | "SCOLON" | "SEMICOLON" => "SEMICOLON",
+        "QUOT" | "QUOTE" => "QUOTE",
+        "GRV" | "GRAVE" => "GRAVE",
+        "COMM" | "COMMA" => "COMMA",
+        "DOT" => "PERIOD",
+        "SLSH" | "SLASH" => "SLASH",
+        "CAPS" | "CAPSLOCK" | "CAPS_LOCK" => "CAPSLOCK",
+        "PSCR" | "PSCREEN" | "PRINT_SCREEN" => "PRINT",
+        "SCRL" | "SLCK" | "SCROLLLOCK" | "SCROLL_LOCK" => "SCROLLLOCK",
+        "PAUS" | "PAUSE" | "BRK" => "PAUSE",
+        "INS" | "INSERT" => "INSERT",
+        "HOME" => "HOME",
+        "PGUP" => "PAGEUP",
+        "DEL" | "DELETE" => "DELETE",
+        "END" => "END",
+        "PGDN" | "PGDOWN" => "PAGEDOWN",
+        "RGHT" | "RIGHT" => "RIGHT",
+        "LEFT" => "LEFT",
+        "DOWN" => "DOWN",
+        "UP" => "UP",
+        "APP" | "APPLICATION" => "MENU",
+        "LCTL" | "LCTRL" | "LEFT_CTRL" => "LEFTCONTROL",
+        "RCTL" | "RCTRL" | "RIGHT_CTRL" => "RIGHTCONTROL",
+        "LSFT" | "LSHIFT" | "LEFT_SHIFT" => "LEFTSHIFT",
+        "RSFT" | "RSHIFT" | "RIGHT_SHIFT" => "RIGHTSHIFT",
+        "LALT" | "LOPT" | "LEFT_ALT" => "LEFTALT",
+        "RALT" | "ROPT" | "ALGR" | "RIGHT_ALT" => "RIGHTALT",
+        "LGUI" | "LWIN" | "LCMD" | "LEFT_GUI" => "LEFTWINDOWS",
+        "RGUI" | "RWIN" | "RCMD" | "RIGHT_GUI" => "RIGHTWINDOWS",
+        "NUM" | "NLCK" | "NUMLOCK" | "NUM_LOCK" => "NUMLOCK",
+        "PSLS" | "KP_SLASH" => "NUMPADDIVIDE",
+        "PAST" | "KP_ASTERISK" => "NUMPADMULTIPLY",
+        "PMNS" | "KP_MINUS" => "NUMPADSUBTRACT",
+        "PPLS" | "KP_PLUS" => "NUMPADADD",
+        "PENT" | "KP_ENTER" => "NUMPADENTER",
+        "PDOT" | "KP_DOT" => "NUMPADDECIMAL",
+        "INT1" | "RO" => "RO",
+        "INT3" | "JYEN" => "YEN",
+        "INT4" | "HENK" => "HENKAN",
+        "INT5" | "MHEN" => "MUHENKAN",
+        "INT2" | "KANA" => "KANA",
+        "MUTE" | "AUDIO_MUTE" => "VOLUMEMUTE",
+        "VOLU" | "AUDIO_VOL_UP" => "VOLUMEUP",
+        "VOLD" | "AUDIO_VOL_DOWN" => "VOLUMEDOWN",
+        "MNXT" | "MEDIA_NEXT_TRACK" => "MEDIANEXT",
+        "MPRV" | "MEDIA_PREV_TRACK" => "MEDIAPREVIOUS",
+        "MSTP" | "MEDIA_STOP" => "MEDIASTOP",
+        "MPLY" | "MEDIA_PLAY_PAUSE" => "MEDIAPLAYPAUSE",
+        "MSEL" | "MEDIA_SELECT" => "LAUNCHMEDIA",
+        "MAIL" => "LAUNCHMAIL",
+        "CALC" | "CALCULATOR" => "CALCULATOR",
+        "WSCH" | "WWW_SEARCH" => "BROWSERSEARCH",
+        "WHOM" | "WWW_HOME" => "BROWSERHOME",
+        "WBAK" | "WWW_BACK" => "BROWSERBACK",
+        "WFWD" | "WWW_FORWARD" => "BROWSERFORWARD",
+        "WSTP" | "WWW_STOP" => "BROWSERSTOP",
+        "WREF" | "WWW_REFRESH" => "BROWSERREFRESH",
+        "WFAV" | "WWW_FAVORITES" => "BROWSERFAVORITES",
         other => {
-            // Try to convert to char if printable
-            if let Some(c) = std::char::from_u32(other as u32) {
-                let s = c.to_string().to_uppercase();
-                // Special mappings for international keys to match API naming
-                match s.as_str() {
-                    "Ç" => return "CEDILLA".to_string(),
-                    "Ñ" => return "NTILDE".to_string(),
-                    _ => {}
-                }
+            if let Some(n) = other
+                .strip_prefix('F')
+                .and_then(|n| n.parse::<u8>().ok())
+                .filter(|n| (1..=24).contains(n))
+            {
+                return Some(format!("F{}", n));
+            }
+            let digit = other
+                .strip_prefix('P')
+                .or_else(|| other.strip_prefix("KP_"))
+                .filter(|d| d.len() == 1 && d.chars().all(|c| c.is_ascii_digit()))?;
+            return Some(format!("NUMPAD{}", digit));
+        }
+    };
+    Some(mapped.to_string())
+}
+
+/// WhatPulse key name for a written key legend such as `Backspace` or
+/// `Page Up`. Shift, Ctrl, Alt and Win come back without a side (`SHIFT`,
+/// `CONTROL`, `ALT`, `WINDOWS`); single characters are left to the layout's
+/// own character mapping.
+pub fn map_legend(legend: &str) -> Option<String> {
+    let legend = legend.trim().to_lowercase();
+    let mapped = match legend.as_str() {
+        "esc" | "escape" => "ESCAPE",
+        "tab" => "TAB",
+        "caps" | "caps lock" | "capslock" => "CAPSLOCK",
+        "backspace" | "back space" | "bksp" | "bkspc" | "←bksp" => "BACKSPACE",
+        "enter" | "return" | "ent" => "RETURN",
+        "space" | "spacebar" => "SPACE",
+        "shift" | "⇧" => "SHIFT",
+        "ctrl" | "control" | "⌃" => "CONTROL",
+        "alt" | "option" | "opt" | "⌥" => "ALT",
+        "altgr" | "alt gr" => "RIGHTALT",
+        "win" | "super" | "gui" | "meta" | "cmd" | "command" | "⌘" => "WINDOWS",
+        "menu" | "app" | "apps" => "MENU",
+        "insert" | "ins" => "INSERT",
+        "delete" | "del" => "DELETE",
+        "home" => "HOME",
+        "end" => "END",
+        "page up" | "pgup" | "pg up" | "pageup" => "PAGEUP",
+        "page down" | "pgdn" | "pg dn" | "pagedown" => "PAGEDOWN",
+        "up" | "↑" => "UP",
+        "down" | "↓" => "DOWN",
+        "left" | "←" => "LEFT",
+        "right" | "→" => "RIGHT",
+        "print screen" | "prtsc" | "prt sc" | "print" | "prtscn" => "PRINT",
+        "scroll lock" | "scrlk" | "scroll" => "SCROLLLOCK",
+        "pause" | "pause break" | "break" => "PAUSE",
+        "num lock" | "numlock" | "num" => "NUMLOCK",
+        other => {
+            let n = other.strip_prefix('f')?.parse::<u8>().ok()?;
+            return (1..=24).contains(&n).then(|| format!("F{}", n));
+        }
+    };
+    Some(mapped.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::keyboard::geometry::Geometry;
+    use crate::commands::keyboard::layouts::KeyboardLayout;
+
+    fn round_trip(name: &str) -> Option<String> {
+        qt_key_for_name(name).and_then(qt_key_name)
+    }
+
+    #[test]
+    fn test_qt_codes_round_trip() {
+        for (code, name) in QT_KEYS {
+            assert_eq!(qt_key_name(*code).as_deref(), Some(*name), "{:#x}", code);
+            assert_eq!(round_trip(name).as_deref(), Some(*name));
+        }
+        for (code, _) in QT_DEAD_KEYS {
+            let name = qt_key_name(*code).unwrap();
+            assert_eq!(round_trip(&name), Some(name));
+        }
+        for name in [
+            "F1",
+            "F13",
+            "F24",
+            "NUMPAD0",
+            "NUMPAD9",
+            "NUMPADDECIMAL",
+            "Ä",
+        ] {
+            assert_eq!(round_trip(name).as_deref(), Some(name));
+        }
+
+        assert_eq!(map_key_id_to_name(0x0100_0020), "SHIFT");
+        assert_eq!(map_key_id_to_name(0x0100_003c), "F13");
+        assert_eq!(map_key_id_to_name(0x2000_0037), "NUMPAD7");
+        assert_eq!(map_key_id_to_name(0x2000_0000 | 0x0100_0005), "NUMPADENTER");
+        assert_eq!(map_key_id_to_name(0x0100_1252), "6"); // dead ^, as US maps it
+        assert_eq!(map_key_id_to_name(0x0100_0086), "MEDIAPLAYPAUSE");
+        assert_eq!(map_key_id_to_name(0x21), "1");
+        // ISO's < key has one name whether it comes from Qt or QMK.
+        assert_eq!(map_key_id_to_name(0x3c), "LESS");
+        assert_eq!(map_qmk_keycode("KC_NUBS").as_deref(), Some("LESS"));
+        assert_eq!(map_key_id_to_name(0x61), "A");
+        assert_eq!(map_key_id_to_name(0x0100_ffff), "UNKNOWN_16842751");
+    }
 
-                if c.is_alphanumeric() {
-                    return s;
+    #[test]
+    fn test_every_drawn_key_has_a_qt_code() {
+        // JIS's ro key types a character that another key types too, and
+        // Qt reports it as that; ISO's < key is also Shift+, on US boards.
+        let shared = ["LESS", "RO"];
+        for layout in KeyboardLayout::all() {
+            for (_, geometry) in Geometry::PRESETS {
+                for key in layout.get_keys(geometry) {
+                    let name = sideless(&key.json_key).unwrap_or(&key.json_key);
+                    if shared.contains(&name) {
+                        continue;
+                    }
+                    assert_eq!(
+                        round_trip(name).as_deref(),
+                        Some(name),
+                        "{} on {}",
+                        key.json_key,
+                        layout
+                    );
                 }
             }
-            format!("UNKNOWN_{}", other)
         }
     }
 }
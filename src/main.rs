@@ -8,8 +8,10 @@ mod client;
 mod commands;
 pub mod config;
 pub mod db;
+mod http;
 
 pub mod key_mapping;
+pub mod realtime;
 pub mod tui;
 
 use client::WhatpulseClient;
@@ -58,6 +60,9 @@ async fn main() -> Result<()> {
         _ => WhatpulseClient::new_local()?,
     };
 
-    let command = args.command.unwrap_or(Commands::Tui);
+    let command = args.command.unwrap_or(Commands::Tui {
+        replay: None,
+        speed: 1.0,
+    });
     command.execute(&client).await
 }
@@ -0,0 +1,274 @@
+//! Automatic pulses driven by the `[auto_pulse]` config rules.
+//!
+//! The engine only decides; callers send the pulse (through the hub while the
+//! TUI runs, or a one-off connection on exit) unless the entry is a dry run.
+
+use crate::config::AutoPulseConfig;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Local, NaiveTime, TimeZone};
+use std::collections::VecDeque;
+use std::fmt;
+
+const DEFAULT_COOLDOWN_MINUTES: u64 = 5;
+/// Auto-pulse entries kept for display.
+pub const LOG_CAPACITY: usize = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PulseTrigger {
+    UnpulsedKeys(u64),
+    Schedule(NaiveTime),
+    Exit,
+}
+
+impl fmt::Display for PulseTrigger {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PulseTrigger::UnpulsedKeys(keys) => write!(f, "{} unpulsed keys", keys),
+            PulseTrigger::Schedule(time) => write!(f, "scheduled at {}", time.format("%H:%M")),
+            PulseTrigger::Exit => write!(f, "exit"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AutoPulseEntry {
+    pub at: DateTime<Local>,
+    pub trigger: PulseTrigger,
+    /// Logged only; no pulse was sent.
+    pub dry_run: bool,
+}
+
+impl fmt::Display for AutoPulseEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Auto-pulse ({})", self.trigger)?;
+        if self.dry_run {
+            write!(f, " [dry run]")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct AutoPulse {
+    threshold: Option<u64>,
+    times: Vec<NaiveTime>,
+    on_exit: bool,
+    pub dry_run: bool,
+    cooldown: Duration,
+    /// The threshold fires once per crossing; it re-arms when the count
+    /// drops below it again (i.e. after the pulse went through).
+    armed: bool,
+    last_pulse: Option<DateTime<Local>>,
+    last_check: Option<DateTime<Local>>,
+    /// Most recent auto-pulses, oldest first.
+    pub log: VecDeque<AutoPulseEntry>,
+}
+
+impl AutoPulse {
+    pub fn from_config(config: &AutoPulseConfig) -> Result<Self> {
+        let times = config
+            .times
+            .iter()
+            .map(|t| {
+                NaiveTime::parse_from_str(t.trim(), "%H:%M")
+                    .with_context(|| format!("Invalid auto_pulse time '{}', expected HH:MM", t))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let cooldown = config.cooldown_minutes.unwrap_or(DEFAULT_COOLDOWN_MINUTES);
+        Ok(Self {
+            threshold: config.unpulsed_keys,
+            times,
+            on_exit: config.on_exit,
+            dry_run: config.dry_run,
+            cooldown: Duration::minutes(cooldown as i64),
+            armed: true,
+            ..Default::default()
+        })
+    }
+
+    /// Whether any rule is configured.
+    pub fn is_active(&self) -> bool {
+        self.threshold.is_some() || !self.times.is_empty() || self.on_exit
+    }
+
+    /// One-line summary of the configured rules.
+    pub fn describe(&self) -> String {
+        let mut rules = Vec::new();
+        if let Some(threshold) = self.threshold {
+            rules.push(format!("at {} unpulsed keys", threshold));
+        }
+        if !self.times.is_empty() {
+            let times: Vec<String> = self
+                .times
+                .iter()
+                .map(|t| t.format("%H:%M").to_string())
+                .collect();
+            rules.push(format!("daily at {}", times.join(", ")));
+        }
+        if self.on_exit {
+            rules.push("on exit".to_string());
+        }
+        if rules.is_empty() {
+            return "Off".to_string();
+        }
+        let mut summary = rules.join("; ");
+        if self.dry_run {
+            summary.push_str(" (dry run)");
+        }
+        summary
+    }
+
+    /// Checks the threshold rule against a realtime update.
+    pub fn check_update(
+        &mut self,
+        unpulsed_keys: i64,
+        now: DateTime<Local>,
+    ) -> Option<AutoPulseEntry> {
+        let threshold = self.threshold?;
+        let keys = unpulsed_keys.max(0) as u64;
+        if keys < threshold {
+            self.armed = true;
+            return None;
+        }
+        if !self.armed || self.cooling_down(now) {
+            return None;
+        }
+        self.armed = false;
+        Some(self.fire(PulseTrigger::UnpulsedKeys(keys), now))
+    }
+
+    /// Checks the time-of-day rules; call periodically. Fires for a time that
+    /// passed since the previous call.
+    pub fn check_schedule(&mut self, now: DateTime<Local>) -> Option<AutoPulseEntry> {
+        let last = self.last_check.replace(now)?;
+        let due = self.times.iter().copied().find(|time| {
+            // Look at yesterday too, in case the check straddles midnight.
+            [now.date_naive().pred_opt(), Some(now.date_naive())]
+                .into_iter()
+                .flatten()
+                .filter_map(|day| Local.from_local_datetime(&day.and_time(*time)).earliest())
+                .any(|at| at > last && at <= now)
+        })?;
+        if self.cooling_down(now) {
+            return None;
+        }
+        Some(self.fire(PulseTrigger::Schedule(due), now))
+    }
+
+    /// The exit rule; ignores the cooldown since it's the last chance.
+    pub fn check_exit(&mut self, now: DateTime<Local>) -> Option<AutoPulseEntry> {
+        if !self.on_exit {
+            return None;
+        }
+        Some(self.fire(PulseTrigger::Exit, now))
+    }
+
+    fn cooling_down(&self, now: DateTime<Local>) -> bool {
+        self.last_pulse
+            .is_some_and(|last| now - last < self.cooldown)
+    }
+
+    fn fire(&mut self, trigger: PulseTrigger, now: DateTime<Local>) -> AutoPulseEntry {
+        let entry = AutoPulseEntry {
+            at: now,
+            trigger,
+            dry_run: self.dry_run,
+        };
+        log::info!("{}", entry);
+        self.last_pulse = Some(now);
+        if self.log.len() == LOG_CAPACITY {
+            self.log.pop_front();
+        }
+        self.log.push_back(entry.clone());
+        entry
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(h: u32, m: u32) -> DateTime<Local> {
+        Local.with_ymd_and_hms(2025, 3, 1, h, m, 0).unwrap()
+    }
+
+    fn engine(config: AutoPulseConfig) -> AutoPulse {
+        AutoPulse::from_config(&config).unwrap()
+    }
+
+    #[test]
+    fn test_threshold_fires_once_per_crossing() {
+        let mut rules = engine(AutoPulseConfig {
+            unpulsed_keys: Some(1000),
+            cooldown_minutes: Some(0),
+            ..Default::default()
+        });
+        assert!(rules.check_update(999, at(10, 0)).is_none());
+        let entry = rules.check_update(1200, at(10, 1)).unwrap();
+        assert_eq!(entry.trigger, PulseTrigger::UnpulsedKeys(1200));
+        assert!(!entry.dry_run);
+        // Still above until the pulse lands.
+        assert!(rules.check_update(1300, at(10, 2)).is_none());
+        assert!(rules.check_update(0, at(10, 3)).is_none());
+        assert!(rules.check_update(1000, at(10, 4)).is_some());
+        assert_eq!(rules.log.len(), 2);
+    }
+
+    #[test]
+    fn test_cooldown_delays_the_next_pulse() {
+        let mut rules = engine(AutoPulseConfig {
+            unpulsed_keys: Some(10),
+            cooldown_minutes: Some(5),
+            ..Default::default()
+        });
+        assert!(rules.check_update(10, at(10, 0)).is_some());
+        rules.check_update(0, at(10, 1));
+        assert!(rules.check_update(10, at(10, 2)).is_none());
+        // Still armed, so it fires once the cooldown is over.
+        assert!(rules.check_update(10, at(10, 6)).is_some());
+    }
+
+    #[test]
+    fn test_schedule_fires_when_time_passes() {
+        let mut rules = engine(AutoPulseConfig {
+            times: vec!["12:00".to_string(), "00:00".to_string()],
+            ..Default::default()
+        });
+        assert!(rules.check_schedule(at(11, 58)).is_none());
+        assert!(rules.check_schedule(at(11, 59)).is_none());
+        let entry = rules.check_schedule(at(12, 0)).unwrap();
+        assert_eq!(
+            entry.trigger,
+            PulseTrigger::Schedule(NaiveTime::from_hms_opt(12, 0, 0).unwrap())
+        );
+        assert!(rules.check_schedule(at(12, 1)).is_none());
+
+        // Across midnight.
+        rules.check_schedule(at(23, 59));
+        let next_day = Local.with_ymd_and_hms(2025, 3, 2, 0, 0, 30).unwrap();
+        assert!(rules.check_schedule(next_day).is_some());
+    }
+
+    #[test]
+    fn test_dry_run_and_exit() {
+        let mut rules = engine(AutoPulseConfig {
+            on_exit: true,
+            dry_run: true,
+            ..Default::default()
+        });
+        assert!(rules.is_active());
+        assert_eq!(rules.describe(), "on exit (dry run)");
+        let entry = rules.check_exit(at(18, 0)).unwrap();
+        assert!(entry.dry_run);
+        assert_eq!(entry.to_string(), "Auto-pulse (exit) [dry run]");
+
+        assert!(!AutoPulse::default().is_active());
+        assert!(
+            AutoPulse::from_config(&AutoPulseConfig {
+                times: vec!["25:00".to_string()],
+                ..Default::default()
+            })
+            .is_err()
+        );
+    }
+}
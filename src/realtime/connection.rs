@@ -0,0 +1,341 @@
+use super::protocol::{ClientMessage, PluginRequest, StatusData, parse_client_message, parse_rate};
+use super::{MonitorCommand, Publisher, RealtimeData, RealtimeEvent};
+use futures_util::{SinkExt, StreamExt};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+use tokio_tungstenite::{connect_async, tungstenite::protocol::Message};
+
+const BACKOFF_BASE: Duration = Duration::from_secs(1);
+const BACKOFF_MAX: Duration = Duration::from_secs(60);
+/// Delays are spread by up to ±20% so several instances don't retry in lockstep.
+const BACKOFF_JITTER: f64 = 0.2;
+/// How often to ping the client. A ping still unanswered at the next beat
+/// means the connection is dead.
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(15);
+/// Reconnect if the client stops sending `update-status` for this long.
+const STALE_AFTER: Duration = Duration::from_secs(60);
+
+/// Exponential backoff: 1s, 2s, 4s ... capped at 60s, with jitter.
+#[derive(Debug, Default)]
+struct Backoff {
+    attempt: u32,
+}
+
+impl Backoff {
+    /// `jitter` in [0, 1) picks a point in the ±`BACKOFF_JITTER` band.
+    fn delay(attempt: u32, jitter: f64) -> Duration {
+        let exp = BACKOFF_BASE.as_secs_f64() * 2f64.powi(attempt.min(16) as i32);
+        let capped = exp.min(BACKOFF_MAX.as_secs_f64());
+        let factor = 1.0 + BACKOFF_JITTER * (2.0 * jitter - 1.0);
+        Duration::from_secs_f64(capped * factor)
+    }
+
+    /// Returns the attempt number (1-based) and how long to wait before it.
+    fn next(&mut self) -> (u32, Duration) {
+        let delay = Self::delay(self.attempt, rand::random::<f64>());
+        self.attempt += 1;
+        (self.attempt, delay)
+    }
+
+    fn reset(&mut self) {
+        self.attempt = 0;
+    }
+}
+
+fn request(request: PluginRequest) -> Message {
+    Message::Text(request.to_json().into())
+}
+
+fn realtime_data(status: StatusData) -> RealtimeData {
+    let rates = status.realtime.unwrap_or_default();
+    let unpulsed = status.unpulsed.unwrap_or_default();
+    let rate = |r: Option<String>| r.as_deref().and_then(parse_rate).unwrap_or(0.0);
+    RealtimeData {
+        unpulsed_keys: unpulsed.keys.unwrap_or(0) as i64,
+        unpulsed_clicks: unpulsed.clicks.unwrap_or(0) as i64,
+        unpulsed_scrolls: unpulsed.scrolls.unwrap_or(0) as i64,
+        unpulsed_download: unpulsed.download.unwrap_or(0),
+        unpulsed_upload: unpulsed.upload.unwrap_or(0),
+        unpulsed_uptime: unpulsed.uptime.unwrap_or(0),
+        unpulsed_distance_miles: unpulsed.distance_miles.unwrap_or(0.0),
+        keys_per_second: rates.keys.unwrap_or(0.0),
+        clicks_per_second: rates.clicks.unwrap_or(0.0),
+        download_bytes_per_second: rate(rates.download),
+        upload_bytes_per_second: rate(rates.upload),
+        heatmap: status.heatmap.unwrap_or_default(),
+        account_totals: status.account_totals.map(Box::new),
+    }
+}
+
+/// Turns one text frame from the client into a hub event.
+pub(super) fn parse_message(text: &str) -> RealtimeEvent {
+    match parse_client_message(text) {
+        Ok(ClientMessage::UpdateStatus(status)) => RealtimeEvent::Update(realtime_data(*status)),
+        Ok(ClientMessage::Notice { action, message }) => RealtimeEvent::Notice { action, message },
+        Ok(ClientMessage::Unknown { action, .. }) => {
+            RealtimeEvent::Debug(format!("Unknown Action: {}", action))
+        }
+        Err(e) => RealtimeEvent::Debug(format!("JSON Parse Error: {} | Raw: {}", e, text)),
+    }
+}
+
+/// Opens a short-lived connection, sends `req` and waits up to `timeout` for
+/// the client's reply. Returns the reply message, if one arrived in time.
+pub(super) async fn send_once(
+    url: &str,
+    req: PluginRequest,
+    timeout: Duration,
+) -> anyhow::Result<Option<String>> {
+    let (mut ws, _) = tokio::time::timeout(timeout, connect_async(url))
+        .await
+        .map_err(|_| anyhow::anyhow!("Timed out connecting to {}", url))??;
+    ws.send(request(PluginRequest::Identify)).await?;
+    ws.send(request(req)).await?;
+
+    let reply = tokio::time::timeout(timeout, async {
+        while let Some(Ok(msg)) = ws.next().await {
+            if let Message::Text(text) = msg
+                && let RealtimeEvent::Notice { action, message } = parse_message(&text)
+                && action == req.action()
+            {
+                return Some(message);
+            }
+        }
+        None
+    })
+    .await
+    .unwrap_or(None);
+
+    let _ = ws.close(None).await;
+    Ok(reply)
+}
+
+/// Connects to the client, identifies as a plugin and publishes updates,
+/// reconnecting with backoff until the command channel closes. Commands sent
+/// while disconnected are dropped.
+pub(super) async fn run(hub: Publisher, url: &str, mut rx_cmd: mpsc::Receiver<MonitorCommand>) {
+    let mut backoff = Backoff::default();
+    loop {
+        hub.publish(RealtimeEvent::Connecting);
+        let reason = match connect_async(url).await {
+            Ok((ws_stream, _)) => {
+                hub.publish(RealtimeEvent::Connected);
+                let (mut write, mut read) = ws_stream.split();
+
+                // Handshake: Identify as plugin
+                if let Err(e) = write.send(request(PluginRequest::Identify)).await {
+                    hub.publish(RealtimeEvent::Error(format!("Handshake failed: {}", e)));
+                }
+
+                let mut heartbeat = tokio::time::interval(HEARTBEAT_INTERVAL);
+                heartbeat.tick().await;
+                let mut awaiting_pong = false;
+                let mut last_update = Instant::now();
+
+                loop {
+                    tokio::select! {
+                        msg = read.next() => match msg {
+                            Some(Ok(Message::Text(text))) => {
+                                let is_update = hub.publish_frame(&text);
+                                if is_update {
+                                    last_update = Instant::now();
+                                    // Only a session that delivers data counts as recovered.
+                                    backoff.reset();
+                                }
+                            }
+                            Some(Ok(Message::Pong(_))) => awaiting_pong = false,
+                            Some(Ok(Message::Close(frame))) => {
+                                break match frame {
+                                    Some(f) if !f.reason.is_empty() => {
+                                        format!("Closed by client: {}", f.reason)
+                                    }
+                                    _ => "Closed by client".to_string(),
+                                };
+                            }
+                            Some(Err(e)) => break e.to_string(),
+                            None => break "Connection closed".to_string(),
+                            _ => {}
+                        },
+                        _ = heartbeat.tick() => {
+                            if awaiting_pong {
+                                break "Heartbeat timed out".to_string();
+                            }
+                            if last_update.elapsed() >= STALE_AFTER {
+                                break format!("No updates for {}s", STALE_AFTER.as_secs());
+                            }
+                            if let Err(e) = write.send(Message::Ping(Vec::new().into())).await {
+                                break format!("Ping failed: {}", e);
+                            }
+                            awaiting_pong = true;
+                        }
+                        cmd = rx_cmd.recv() => {
+                            let Some(command) = cmd else {
+                                return;
+                            };
+                            let req = match command {
+                                MonitorCommand::Pulse => PluginRequest::Pulse,
+                                MonitorCommand::OpenWindow => PluginRequest::OpenWindow,
+                            };
+                            if let Err(e) = write.send(request(req)).await {
+                                hub.publish(RealtimeEvent::Debug(format!("Send failed: {}", e)));
+                            }
+                        }
+                    }
+                }
+            }
+            Err(e) => e.to_string(),
+        };
+        hub.publish(RealtimeEvent::Disconnected(Some(reason)));
+
+        let (attempt, delay) = backoff.next();
+        hub.publish(RealtimeEvent::Reconnecting { attempt, delay });
+        let retry = tokio::time::sleep(delay);
+        tokio::pin!(retry);
+        loop {
+            tokio::select! {
+                _ = &mut retry => break,
+                cmd = rx_cmd.recv() => match cmd {
+                    Some(command) => hub.publish(RealtimeEvent::Debug(format!(
+                        "Not connected, dropped {:?}",
+                        command
+                    ))),
+                    None => return,
+                },
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::realtime::RealtimeHub;
+
+    #[tokio::test]
+    async fn test_stops_once_the_hub_is_dropped() {
+        let (hub, rx_cmd) = RealtimeHub::new();
+        let mut events = hub.subscribe();
+        // Nothing listens on the discard port, so this sits in backoff.
+        let task = tokio::spawn(run(hub.publisher(), "ws://127.0.0.1:9", rx_cmd));
+        while !matches!(
+            events.recv().await.unwrap(),
+            RealtimeEvent::Reconnecting { .. }
+        ) {}
+        drop(hub);
+        tokio::time::timeout(Duration::from_secs(5), task)
+            .await
+            .expect("connection task kept running")
+            .unwrap();
+    }
+
+    #[test]
+    fn test_deserialize_update_status() {
+        let json = r#"{
+            "action": "update-status",
+            "data": {
+                "account-totals": null,
+                "realtime": {
+                    "keys": "1,23",
+                    "clicks": "0.45"
+                },
+                "unpulsed": {
+                    "keys": 100,
+                    "clicks": 50
+                }
+            }
+        }"#;
+
+        match parse_message(json) {
+            RealtimeEvent::Update(data) => {
+                assert_eq!(data.keys_per_second, 1.23);
+                assert_eq!(data.clicks_per_second, 0.45);
+                assert_eq!(data.unpulsed_keys, 100);
+                assert_eq!(data.unpulsed_clicks, 50);
+                assert_eq!(data.unpulsed_scrolls, 0);
+                assert!(data.account_totals.is_none());
+            }
+            other => panic!("unexpected event {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_full_frame_maps_every_field() {
+        let json = include_str!("fixtures/update_status_full.json");
+        match parse_message(json) {
+            RealtimeEvent::Update(data) => {
+                assert_eq!(data.unpulsed_download, 3444883866);
+                assert_eq!(data.unpulsed_uptime, 22346);
+                assert_eq!(data.unpulsed_distance_miles, 0.412);
+                assert_eq!(data.download_bytes_per_second, 21.0 * 1024.0);
+                assert_eq!(data.upload_bytes_per_second, 3.0 * 1024.0);
+                assert_eq!(data.account_totals.unwrap().keys, Some(90808209));
+            }
+            other => panic!("unexpected event {:?}", other),
+        }
+        assert!(matches!(
+            parse_message(include_str!("fixtures/pulse_notice.json")),
+            RealtimeEvent::Notice { .. }
+        ));
+        assert!(matches!(
+            parse_message(include_str!("fixtures/unknown_action.json")),
+            RealtimeEvent::Debug(_)
+        ));
+    }
+
+    #[test]
+    fn test_backoff_grows_and_caps() {
+        // jitter 0.5 is the midpoint of the band, i.e. no jitter.
+        assert_eq!(Backoff::delay(0, 0.5), Duration::from_secs(1));
+        assert_eq!(Backoff::delay(3, 0.5), Duration::from_secs(8));
+        assert_eq!(Backoff::delay(10, 0.5), BACKOFF_MAX);
+        assert_eq!(Backoff::delay(u32::MAX, 0.5), BACKOFF_MAX);
+
+        let low = Backoff::delay(2, 0.0).as_secs_f64();
+        let high = Backoff::delay(2, 0.999).as_secs_f64();
+        assert!((low - 3.2).abs() < 1e-9);
+        assert!(high < 4.8 && high > 4.79);
+
+        let mut backoff = Backoff::default();
+        assert_eq!(backoff.next().0, 1);
+        assert_eq!(backoff.next().0, 2);
+        backoff.reset();
+        assert_eq!(backoff.next().0, 1);
+    }
+
+    #[tokio::test]
+    async fn test_send_once_waits_for_the_reply() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+            let mut actions = Vec::new();
+            while actions.len() < 2 {
+                if let Some(Ok(Message::Text(text))) = ws.next().await {
+                    let value: serde_json::Value = serde_json::from_str(&text).unwrap();
+                    actions.push(value["action"].as_str().unwrap().to_string());
+                }
+            }
+            ws.send(Message::Text(
+                include_str!("fixtures/pulse_notice.json").into(),
+            ))
+            .await
+            .unwrap();
+            actions
+        });
+
+        let reply = send_once(&url, PluginRequest::Pulse, Duration::from_secs(5))
+            .await
+            .unwrap();
+        assert_eq!(reply.as_deref(), Some("Pulse executed."));
+        assert_eq!(server.await.unwrap(), vec!["identify", "pulse"]);
+    }
+
+    #[test]
+    fn test_serialize_request() {
+        assert_eq!(
+            request(PluginRequest::OpenWindow),
+            Message::Text(r#"{"source":"plugin","action":"open-window"}"#.into())
+        );
+    }
+}
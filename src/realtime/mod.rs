@@ -0,0 +1,346 @@
+//! Shared realtime feed from the local WhatPulse client.
+//!
+//! [`RealtimeHub`] owns the single WebSocket connection to the client and fans
+//! parsed updates and connection changes out to any number of subscribers
+//! (TUI, metrics exporter, API server, CLI monitor) over a broadcast channel.
+//! The frames can come from the live client or from a recording
+//! (see [`recording`]).
+
+pub mod auto_pulse;
+mod connection;
+pub mod protocol;
+pub mod recording;
+pub mod wpm;
+
+pub use protocol::{AccountTotals, PluginRequest};
+pub use recording::Recorder;
+
+use anyhow::Result;
+use chrono::{DateTime, Local};
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::{broadcast, mpsc};
+
+pub const CLIENT_WS_URL: &str = "ws://127.0.0.1:3489";
+
+/// Subscribers that fall this far behind skip ahead (`RecvError::Lagged`).
+const EVENT_CAPACITY: usize = 256;
+/// Connection log entries kept in [`HubState`].
+pub const LOG_CAPACITY: usize = 20;
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RealtimeData {
+    pub unpulsed_keys: i64,
+    pub unpulsed_clicks: i64,
+    pub unpulsed_scrolls: i64,
+    /// Bytes since the last pulse.
+    pub unpulsed_download: u64,
+    pub unpulsed_upload: u64,
+    /// Seconds since the last pulse.
+    pub unpulsed_uptime: u64,
+    pub unpulsed_distance_miles: f64,
+    pub keys_per_second: f64,
+    pub clicks_per_second: f64,
+    pub download_bytes_per_second: f64,
+    pub upload_bytes_per_second: f64,
+    pub heatmap: HashMap<String, u64>,
+    /// Lifetime totals as of the last pulse; only sent by newer clients.
+    pub account_totals: Option<Box<AccountTotals>>,
+}
+
+#[derive(Debug, Clone)]
+pub enum MonitorCommand {
+    Pulse,
+    OpenWindow,
+}
+
+#[derive(Debug, Clone)]
+pub enum RealtimeEvent {
+    Connecting,
+    Connected,
+    /// The connection dropped or could not be opened; the hub retries.
+    Disconnected(Option<String>),
+    /// Waiting `delay` before connection attempt number `attempt`.
+    Reconnecting {
+        attempt: u32,
+        delay: Duration,
+    },
+    /// Connected, but the client rejected or failed part of the session.
+    Error(String),
+    Update(RealtimeData),
+    /// A message from the client, e.g. the reply to a pulse request.
+    Notice {
+        action: String,
+        message: String,
+    },
+    /// Messages the hub could not interpret, kept for debugging.
+    Debug(String),
+}
+
+impl RealtimeEvent {
+    /// Connection-log line for events that change the connection state.
+    pub fn log_message(&self) -> Option<String> {
+        match self {
+            RealtimeEvent::Connected => Some("Connected".to_string()),
+            RealtimeEvent::Disconnected(reason) => Some(format!(
+                "Disconnected: {}",
+                reason.as_deref().unwrap_or("unknown reason")
+            )),
+            RealtimeEvent::Reconnecting { attempt, delay } => {
+                Some(format!("Retry #{} in {:.1}s", attempt, delay.as_secs_f64()))
+            }
+            RealtimeEvent::Error(error) => Some(format!("Error: {}", error)),
+            RealtimeEvent::Notice { action, message } => Some(format!("{}: {}", action, message)),
+            RealtimeEvent::Connecting | RealtimeEvent::Update(_) | RealtimeEvent::Debug(_) => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ConnectionLogEntry {
+    pub at: DateTime<Local>,
+    pub message: String,
+}
+
+/// Last known state, for consumers that poll instead of subscribing.
+#[derive(Debug, Clone, Default)]
+pub struct HubState {
+    pub connected: bool,
+    pub last_error: Option<String>,
+    pub latest: Option<RealtimeData>,
+    /// Reconnect attempts since the hub started.
+    pub reconnects: u32,
+    /// Most recent connection changes, oldest first.
+    pub log: VecDeque<ConnectionLogEntry>,
+}
+
+/// Sends one request to the local client over its own connection, for
+/// callers without a running hub. Returns the client's reply, if any.
+pub async fn send_once(request: PluginRequest, timeout: Duration) -> Result<Option<String>> {
+    connection::send_once(CLIENT_WS_URL, request, timeout)
+        .await
+        .map_err(|e| {
+            anyhow::anyhow!(
+                "Could not reach the WhatPulse client at {}: {}",
+                CLIENT_WS_URL,
+                e
+            )
+        })
+}
+
+#[derive(Clone)]
+pub struct RealtimeHub {
+    publisher: Publisher,
+    commands: mpsc::Sender<MonitorCommand>,
+}
+
+/// The publishing half of a hub, held by the task feeding it. It has no
+/// command sender, so the task sees its command channel close once every
+/// [`RealtimeHub`] handle is gone.
+#[derive(Clone)]
+struct Publisher {
+    events: broadcast::Sender<RealtimeEvent>,
+    state: Arc<Mutex<HubState>>,
+    recorder: Arc<Mutex<Option<Recorder>>>,
+}
+
+impl RealtimeHub {
+    /// Creates a hub without a connection. The returned receiver yields
+    /// commands sent through [`RealtimeHub::commands`].
+    pub fn new() -> (Self, mpsc::Receiver<MonitorCommand>) {
+        let (events, _) = broadcast::channel(EVENT_CAPACITY);
+        let (commands, rx_cmd) = mpsc::channel(10);
+        let hub = Self {
+            publisher: Publisher {
+                events,
+                state: Arc::new(Mutex::new(HubState::default())),
+                recorder: Arc::new(Mutex::new(None)),
+            },
+            commands,
+        };
+        (hub, rx_cmd)
+    }
+
+    /// Creates a hub and starts its connection to the local client. The
+    /// connection task reconnects until every handle to the hub (and every
+    /// sender from [`RealtimeHub::commands`]) is dropped.
+    pub fn spawn() -> Self {
+        let (hub, rx_cmd) = Self::new();
+        tokio::spawn(connection::run(hub.publisher(), CLIENT_WS_URL, rx_cmd));
+        hub
+    }
+
+    /// Creates a hub fed from a recording made with `monitor --record`,
+    /// played back `speed` times faster than it was recorded.
+    pub fn replay(path: &Path, speed: f64) -> Result<Self> {
+        let frames = recording::load(path)?;
+        let (hub, rx_cmd) = Self::new();
+        tokio::spawn(recording::replay(hub.publisher(), frames, speed, rx_cmd));
+        Ok(hub)
+    }
+
+    fn publisher(&self) -> Publisher {
+        self.publisher.clone()
+    }
+
+    /// Saves every raw frame received from now on.
+    pub fn record(&self, recorder: Recorder) {
+        if let Ok(mut slot) = self.publisher.recorder.lock() {
+            *slot = Some(recorder);
+        }
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<RealtimeEvent> {
+        self.publisher.events.subscribe()
+    }
+
+    /// Subscribes and returns the state at that moment, so consumers that
+    /// start after the connection attempt still see its outcome. An event may
+    /// appear in both.
+    pub fn subscribe_with_state(&self) -> (HubState, broadcast::Receiver<RealtimeEvent>) {
+        let rx = self.subscribe();
+        (self.state(), rx)
+    }
+
+    pub fn commands(&self) -> mpsc::Sender<MonitorCommand> {
+        self.commands.clone()
+    }
+
+    pub fn state(&self) -> HubState {
+        self.publisher
+            .state
+            .lock()
+            .map(|s| s.clone())
+            .unwrap_or_default()
+    }
+
+    pub fn publish_frame(&self, text: &str) -> bool {
+        self.publisher.publish_frame(text)
+    }
+
+    pub fn publish(&self, event: RealtimeEvent) {
+        self.publisher.publish(event)
+    }
+}
+
+impl Publisher {
+    /// Records and parses a raw text frame from the client, then publishes
+    /// the result. Returns whether it was a stats update.
+    fn publish_frame(&self, text: &str) -> bool {
+        if let Ok(mut slot) = self.recorder.lock()
+            && let Some(recorder) = slot.as_mut()
+            && let Err(e) = recorder.write(text)
+        {
+            // Keep the session going; a full disk shouldn't take it down.
+            log::warn!("Recording stopped: {}", e);
+            *slot = None;
+        }
+        let event = connection::parse_message(text);
+        let is_update = matches!(event, RealtimeEvent::Update(_));
+        self.publish(event);
+        is_update
+    }
+
+    /// Records `event` in the hub state and broadcasts it to subscribers.
+    fn publish(&self, event: RealtimeEvent) {
+        if let Ok(mut state) = self.state.lock() {
+            if let Some(message) = event.log_message() {
+                if state.log.len() == LOG_CAPACITY {
+                    state.log.pop_front();
+                }
+                state.log.push_back(ConnectionLogEntry {
+                    at: Local::now(),
+                    message,
+                });
+            }
+            match &event {
+                RealtimeEvent::Connected => {
+                    state.connected = true;
+                    state.last_error = None;
+                }
+                RealtimeEvent::Disconnected(error) => {
+                    state.connected = false;
+                    state.last_error = error.clone();
+                }
+                RealtimeEvent::Reconnecting { .. } => state.reconnects += 1,
+                RealtimeEvent::Error(error) => state.last_error = Some(error.clone()),
+                RealtimeEvent::Update(data) => state.latest = Some(data.clone()),
+                RealtimeEvent::Connecting
+                | RealtimeEvent::Notice { .. }
+                | RealtimeEvent::Debug(_) => {}
+            }
+        }
+        // No subscribers is not an error.
+        let _ = self.events.send(event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn data(keys: i64) -> RealtimeData {
+        RealtimeData {
+            unpulsed_keys: keys,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fans_out_to_every_subscriber() {
+        let (hub, _rx_cmd) = RealtimeHub::new();
+        let mut a = hub.subscribe();
+        let mut b = hub.subscribe();
+
+        hub.publish(RealtimeEvent::Connected);
+        hub.publish(RealtimeEvent::Update(data(7)));
+
+        for rx in [&mut a, &mut b] {
+            assert!(matches!(rx.recv().await.unwrap(), RealtimeEvent::Connected));
+            match rx.recv().await.unwrap() {
+                RealtimeEvent::Update(d) => assert_eq!(d.unpulsed_keys, 7),
+                other => panic!("unexpected event {:?}", other),
+            }
+        }
+    }
+
+    #[test]
+    fn test_tracks_connection_state() {
+        let (hub, _rx_cmd) = RealtimeHub::new();
+        hub.publish(RealtimeEvent::Connected);
+        hub.publish(RealtimeEvent::Update(data(3)));
+        assert!(hub.state().connected);
+        assert_eq!(hub.state().latest.unwrap().unpulsed_keys, 3);
+
+        hub.publish(RealtimeEvent::Disconnected(Some("refused".to_string())));
+        let state = hub.state();
+        assert!(!state.connected);
+        assert_eq!(state.last_error.as_deref(), Some("refused"));
+        // The last update stays available while reconnecting.
+        assert!(state.latest.is_some());
+
+        hub.publish(RealtimeEvent::Reconnecting {
+            attempt: 1,
+            delay: Duration::from_secs(1),
+        });
+        let state = hub.state();
+        assert_eq!(state.reconnects, 1);
+        let log: Vec<&str> = state.log.iter().map(|e| e.message.as_str()).collect();
+        assert_eq!(
+            log,
+            vec!["Connected", "Disconnected: refused", "Retry #1 in 1.0s"]
+        );
+    }
+
+    #[test]
+    fn test_log_is_bounded() {
+        let (hub, _rx_cmd) = RealtimeHub::new();
+        for _ in 0..LOG_CAPACITY + 5 {
+            hub.publish(RealtimeEvent::Connected);
+        }
+        assert_eq!(hub.state().log.len(), LOG_CAPACITY);
+    }
+}
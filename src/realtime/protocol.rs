@@ -0,0 +1,346 @@
+//! Typed model of the WhatPulse client's plugin WebSocket protocol.
+//!
+//! The client mixes numbers, numeric strings ("21534236") and localized
+//! decimals ("2,17") for the same kind of field depending on the message, so
+//! counters are parsed leniently and kept optional where older clients omit
+//! them.
+
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Requests a plugin can send to the client.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PluginRequest {
+    Identify,
+    Pulse,
+    OpenWindow,
+}
+
+impl PluginRequest {
+    pub fn action(self) -> &'static str {
+        match self {
+            PluginRequest::Identify => "identify",
+            PluginRequest::Pulse => "pulse",
+            PluginRequest::OpenWindow => "open-window",
+        }
+    }
+
+    pub fn to_json(self) -> String {
+        #[derive(Serialize)]
+        struct Request {
+            source: &'static str,
+            action: &'static str,
+        }
+        serde_json::to_string(&Request {
+            source: "plugin",
+            action: self.action(),
+        })
+        .unwrap_or_default()
+    }
+}
+
+/// Messages received from the client.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ClientMessage {
+    /// Periodic stats push (every ~2 seconds after identifying).
+    UpdateStatus(Box<StatusData>),
+    /// Any action carrying a human-readable `msg`/`message`, e.g. the
+    /// acknowledgement of a pulse request.
+    Notice { action: String, message: String },
+    /// Well-formed but not modelled; kept for debugging.
+    Unknown { action: String, raw: Value },
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct StatusData {
+    #[serde(rename = "account-totals")]
+    pub account_totals: Option<AccountTotals>,
+    pub realtime: Option<RealtimeRates>,
+    pub unpulsed: Option<UnpulsedStats>,
+    pub heatmap: Option<HashMap<String, u64>>,
+}
+
+/// Lifetime totals as of the last pulse. Download/upload are in MB.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+pub struct AccountTotals {
+    #[serde(default, deserialize_with = "lenient_u64")]
+    pub keys: Option<u64>,
+    #[serde(default, deserialize_with = "lenient_u64")]
+    pub clicks: Option<u64>,
+    #[serde(default, deserialize_with = "lenient_u64")]
+    pub scrolls: Option<u64>,
+    #[serde(default, deserialize_with = "lenient_u64")]
+    pub download: Option<u64>,
+    #[serde(default, deserialize_with = "lenient_u64")]
+    pub upload: Option<u64>,
+    #[serde(default, deserialize_with = "lenient_u64")]
+    pub uptime: Option<u64>,
+    #[serde(default, deserialize_with = "lenient_f64")]
+    pub distance_miles: Option<f64>,
+    #[serde(default)]
+    pub ranks: HashMap<String, Value>,
+}
+
+impl AccountTotals {
+    /// Rank for a stat, e.g. `rank("keys")` reads `rank_keys`.
+    pub fn rank(&self, stat: &str) -> Option<u64> {
+        lenient_value_u64(self.ranks.get(&format!("rank_{}", stat))?)
+    }
+}
+
+/// Current per-second rates. Bandwidth arrives pre-formatted ("21KB/s").
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct RealtimeRates {
+    #[serde(default, deserialize_with = "lenient_f64")]
+    pub keys: Option<f64>,
+    #[serde(default, deserialize_with = "lenient_f64")]
+    pub clicks: Option<f64>,
+    pub download: Option<String>,
+    pub upload: Option<String>,
+}
+
+/// Stats accumulated since the last pulse. Bandwidth is in bytes.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct UnpulsedStats {
+    #[serde(default, deserialize_with = "lenient_u64")]
+    pub keys: Option<u64>,
+    #[serde(default, deserialize_with = "lenient_u64")]
+    pub clicks: Option<u64>,
+    #[serde(default, deserialize_with = "lenient_u64")]
+    pub scrolls: Option<u64>,
+    #[serde(default, deserialize_with = "lenient_u64")]
+    pub download: Option<u64>,
+    #[serde(default, deserialize_with = "lenient_u64")]
+    pub upload: Option<u64>,
+    #[serde(default, deserialize_with = "lenient_u64")]
+    pub uptime: Option<u64>,
+    #[serde(default, deserialize_with = "lenient_f64")]
+    pub distance_miles: Option<f64>,
+}
+
+/// Parses localized decimals such as "2,17" or "2.17".
+pub fn parse_localized_float(s: &str) -> Option<f64> {
+    s.trim().replace(',', ".").parse::<f64>().ok()
+}
+
+/// Parses a formatted rate such as "21KB/s" or "1,5 MB/s" into bytes/second.
+pub fn parse_rate(s: &str) -> Option<f64> {
+    let s = s.trim().trim_end_matches("/s").trim();
+    let split = s.find(|c: char| c.is_ascii_alphabetic()).unwrap_or(s.len());
+    let (number, unit) = s.split_at(split);
+    let value = parse_localized_float(number)?;
+    let multiplier = match unit.trim().to_ascii_uppercase().as_str() {
+        "" | "B" => 1.0,
+        "KB" => 1024.0,
+        "MB" => 1024.0 * 1024.0,
+        "GB" => 1024.0 * 1024.0 * 1024.0,
+        _ => return None,
+    };
+    Some(value * multiplier)
+}
+
+fn lenient_value_u64(value: &Value) -> Option<u64> {
+    match value {
+        Value::Number(n) => n.as_u64().or_else(|| n.as_f64().map(|f| f.max(0.0) as u64)),
+        // Counters may carry thousands separators or a fraction in some
+        // client versions.
+        Value::String(s) => {
+            let number: String = s
+                .trim()
+                .chars()
+                .filter(|c| !matches!(c, ',' | ' ' | '\u{a0}'))
+                .collect();
+            if number.contains('.') {
+                number.parse::<f64>().ok().map(|f| f.max(0.0) as u64)
+            } else {
+                number.parse().ok()
+            }
+        }
+        _ => None,
+    }
+}
+
+fn lenient_u64<'de, D: Deserializer<'de>>(d: D) -> Result<Option<u64>, D::Error> {
+    Ok(Option::<Value>::deserialize(d)?
+        .as_ref()
+        .and_then(lenient_value_u64))
+}
+
+fn lenient_f64<'de, D: Deserializer<'de>>(d: D) -> Result<Option<f64>, D::Error> {
+    Ok(match Option::<Value>::deserialize(d)? {
+        Some(Value::Number(n)) => n.as_f64(),
+        Some(Value::String(s)) => parse_localized_float(&s),
+        _ => None,
+    })
+}
+
+/// Parses one text frame. Errors only for frames that aren't JSON objects
+/// with an `action`.
+pub fn parse_client_message(text: &str) -> serde_json::Result<ClientMessage> {
+    #[derive(Deserialize)]
+    struct Envelope {
+        action: String,
+        #[serde(default)]
+        data: Value,
+    }
+
+    let raw: Value = serde_json::from_str(text)?;
+    let envelope: Envelope = serde_json::from_value(raw.clone())?;
+
+    if envelope.action == "update-status" {
+        let data: StatusData = serde_json::from_value(envelope.data)?;
+        return Ok(ClientMessage::UpdateStatus(Box::new(data)));
+    }
+
+    let message = ["msg", "message"].iter().find_map(|key| {
+        raw.get(key)
+            .or_else(|| raw.get("data").and_then(|d| d.get(key)))
+            .and_then(Value::as_str)
+    });
+    Ok(match message {
+        Some(message) => ClientMessage::Notice {
+            action: envelope.action,
+            message: message.to_string(),
+        },
+        None => ClientMessage::Unknown {
+            action: envelope.action,
+            raw,
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// One frame per message shape the client sends.
+    const FIXTURES: &[(&str, &str)] = &[
+        (
+            "update_status_full",
+            include_str!("fixtures/update_status_full.json"),
+        ),
+        (
+            "update_status_minimal",
+            include_str!("fixtures/update_status_minimal.json"),
+        ),
+        (
+            "update_status_numeric",
+            include_str!("fixtures/update_status_numeric.json"),
+        ),
+        ("pulse_notice", include_str!("fixtures/pulse_notice.json")),
+        (
+            "unknown_action",
+            include_str!("fixtures/unknown_action.json"),
+        ),
+    ];
+
+    fn fixture(name: &str) -> ClientMessage {
+        let (_, text) = FIXTURES.iter().find(|(n, _)| *n == name).unwrap();
+        parse_client_message(text).unwrap()
+    }
+
+    fn status(name: &str) -> StatusData {
+        match fixture(name) {
+            ClientMessage::UpdateStatus(data) => *data,
+            other => panic!("{} parsed as {:?}", name, other),
+        }
+    }
+
+    #[test]
+    fn test_every_fixture_parses() {
+        for (name, text) in FIXTURES {
+            assert!(
+                parse_client_message(text).is_ok(),
+                "fixture {} failed",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn test_full_update_status() {
+        let data = status("update_status_full");
+
+        let totals = data.account_totals.unwrap();
+        assert_eq!(totals.keys, Some(90808209));
+        assert_eq!(totals.clicks, Some(21534236));
+        assert_eq!(totals.download, Some(19425048));
+        assert_eq!(totals.distance_miles, Some(199.038));
+        assert_eq!(totals.rank("keys"), Some(448));
+        assert_eq!(totals.rank("scrolls"), None);
+
+        let realtime = data.realtime.unwrap();
+        assert_eq!(realtime.keys, Some(2.17));
+        assert_eq!(realtime.clicks, Some(0.1));
+        assert_eq!(
+            realtime.download.as_deref().and_then(parse_rate),
+            Some(21.0 * 1024.0)
+        );
+
+        let unpulsed = data.unpulsed.unwrap();
+        assert_eq!(unpulsed.keys, Some(22061));
+        assert_eq!(unpulsed.download, Some(3444883866));
+        assert_eq!(unpulsed.uptime, Some(22346));
+        assert_eq!(data.heatmap.unwrap().get("A"), Some(&12));
+    }
+
+    #[test]
+    fn test_minimal_update_status_has_no_totals() {
+        let data = status("update_status_minimal");
+        assert!(data.account_totals.is_none());
+        assert!(data.heatmap.is_none());
+        let unpulsed = data.unpulsed.unwrap();
+        assert_eq!(unpulsed.scrolls, None);
+        assert_eq!(unpulsed.keys, Some(100));
+    }
+
+    #[test]
+    fn test_numeric_and_string_fields_agree() {
+        let data = status("update_status_numeric");
+        let totals = data.account_totals.unwrap();
+        assert_eq!(totals.keys, Some(1234567));
+        assert_eq!(totals.rank("keys"), Some(12));
+        assert_eq!(data.realtime.unwrap().keys, Some(3.5));
+        let unpulsed = data.unpulsed.unwrap();
+        assert_eq!(unpulsed.keys, Some(1234));
+        assert_eq!(unpulsed.download, Some(1048576));
+        assert_eq!(unpulsed.scrolls, Some(12));
+        assert_eq!(unpulsed.uptime, Some(1234));
+    }
+
+    #[test]
+    fn test_notices_and_unknown_actions() {
+        assert_eq!(
+            fixture("pulse_notice"),
+            ClientMessage::Notice {
+                action: "pulse".to_string(),
+                message: "Pulse executed.".to_string(),
+            }
+        );
+        match fixture("unknown_action") {
+            ClientMessage::Unknown { action, raw } => {
+                assert_eq!(action, "profile-changed");
+                assert_eq!(raw["data"]["profile_id"], 2);
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+        assert!(parse_client_message("not json").is_err());
+        assert!(parse_client_message(r#"{"data": {}}"#).is_err());
+    }
+
+    #[test]
+    fn test_rates_and_requests() {
+        assert_eq!(parse_localized_float("2.17"), Some(2.17));
+        assert_eq!(parse_localized_float("2,17"), Some(2.17));
+        assert_eq!(parse_localized_float("invalid"), None);
+        assert_eq!(parse_rate("2KB/s"), Some(2048.0));
+        assert_eq!(parse_rate("1,5 MB/s"), Some(1.5 * 1024.0 * 1024.0));
+        assert_eq!(parse_rate("512B/s"), Some(512.0));
+        assert_eq!(parse_rate("fast"), None);
+        assert_eq!(
+            PluginRequest::Pulse.to_json(),
+            r#"{"source":"plugin","action":"pulse"}"#
+        );
+    }
+}
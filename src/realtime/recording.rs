@@ -0,0 +1,172 @@
+//! Recording and replay of raw client frames.
+//!
+//! Recordings are NDJSON, one `{"elapsed_ms": .., "frame": ".."}` line per
+//! text frame, with `elapsed_ms` counted from the start of the recording.
+//! Replay publishes the frames through the same parser as a live connection,
+//! so every consumer of the hub behaves exactly as it did while recording.
+
+use super::{MonitorCommand, Publisher, RealtimeEvent};
+use anyhow::{Context, Result, anyhow, bail};
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedFrame {
+    pub elapsed_ms: u64,
+    pub frame: String,
+}
+
+pub struct Recorder {
+    out: BufWriter<File>,
+    started: Instant,
+}
+
+impl Recorder {
+    pub fn create(path: &Path) -> Result<Self> {
+        let file = File::create(path)
+            .with_context(|| format!("Failed to create recording {}", path.display()))?;
+        Ok(Self {
+            out: BufWriter::new(file),
+            started: Instant::now(),
+        })
+    }
+
+    /// Appends one frame. Flushed immediately so an interrupted session
+    /// still leaves a usable file.
+    pub fn write(&mut self, frame: &str) -> Result<()> {
+        let line = serde_json::to_string(&RecordedFrame {
+            elapsed_ms: self.started.elapsed().as_millis() as u64,
+            frame: frame.to_string(),
+        })?;
+        writeln!(self.out, "{}", line)?;
+        self.out.flush()?;
+        Ok(())
+    }
+}
+
+/// Loads a recording, skipping blank lines.
+pub fn load(path: &Path) -> Result<Vec<RecordedFrame>> {
+    let file =
+        File::open(path).with_context(|| format!("Failed to open recording {}", path.display()))?;
+    let mut frames = Vec::new();
+    for (i, line) in BufReader::new(file).lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let frame: RecordedFrame = serde_json::from_str(&line)
+            .with_context(|| format!("{}:{}: invalid frame", path.display(), i + 1))?;
+        frames.push(frame);
+    }
+    if frames.is_empty() {
+        bail!("Recording {} contains no frames", path.display());
+    }
+    Ok(frames)
+}
+
+/// Parses a replay speed such as `4x`, `0.5x` or `2`.
+pub fn parse_speed(s: &str) -> Result<f64> {
+    let value: f64 = s
+        .trim()
+        .trim_end_matches(['x', 'X'])
+        .parse()
+        .map_err(|_| anyhow!("Invalid speed '{}', expected e.g. 4x", s))?;
+    if !value.is_finite() || value <= 0.0 {
+        bail!("Speed must be greater than zero");
+    }
+    Ok(value)
+}
+
+/// Delay before the first frame, so consumers started alongside the hub have
+/// subscribed by then.
+const LEAD_IN: Duration = Duration::from_millis(100);
+
+/// Publishes `frames` into the hub with their original spacing divided by
+/// `speed`, starting from the first frame. Commands (e.g. a manual pulse)
+/// have no client to go to and are only logged.
+pub(super) async fn replay(
+    hub: Publisher,
+    frames: Vec<RecordedFrame>,
+    speed: f64,
+    mut rx_cmd: mpsc::Receiver<MonitorCommand>,
+) {
+    hub.publish(RealtimeEvent::Connecting);
+    hub.publish(RealtimeEvent::Connected);
+
+    let started = tokio::time::Instant::now() + LEAD_IN;
+    let offset = frames.first().map_or(0, |f| f.elapsed_ms);
+    for frame in frames {
+        let elapsed = frame.elapsed_ms.saturating_sub(offset) as f64 / 1000.0;
+        let due = started + Duration::from_secs_f64(elapsed / speed);
+        loop {
+            tokio::select! {
+                _ = tokio::time::sleep_until(due) => break,
+                cmd = rx_cmd.recv() => match cmd {
+                    Some(command) => hub.publish(RealtimeEvent::Debug(format!(
+                        "Replay: ignoring {:?}",
+                        command
+                    ))),
+                    // Nothing is listening any more.
+                    None => return,
+                },
+            }
+        }
+        hub.publish_frame(&frame.frame);
+    }
+
+    hub.publish(RealtimeEvent::Disconnected(Some(
+        "Replay finished".to_string(),
+    )));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::realtime::RealtimeHub;
+
+    #[test]
+    fn test_parses_speeds() {
+        assert_eq!(parse_speed("4x").unwrap(), 4.0);
+        assert_eq!(parse_speed("0.5X").unwrap(), 0.5);
+        assert_eq!(parse_speed("2").unwrap(), 2.0);
+        assert!(parse_speed("0x").is_err());
+        assert!(parse_speed("fast").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_records_and_replays_through_the_parser() {
+        let path =
+            std::env::temp_dir().join(format!("wtfpulse-recording-{}.ndjson", std::process::id()));
+        let (live, _rx_cmd) = RealtimeHub::new();
+        live.record(Recorder::create(&path).unwrap());
+        live.publish_frame(include_str!("fixtures/update_status_minimal.json").trim());
+        live.publish_frame(include_str!("fixtures/pulse_notice.json").trim());
+        drop(live);
+
+        let frames = load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(frames.len(), 2);
+        assert!(frames[0].elapsed_ms <= frames[1].elapsed_ms);
+
+        let (hub, rx_cmd) = RealtimeHub::new();
+        let mut events = hub.subscribe();
+        replay(hub.publisher(), frames, 1000.0, rx_cmd).await;
+
+        let mut seen = Vec::new();
+        while let Ok(event) = events.try_recv() {
+            seen.push(event);
+        }
+        assert!(matches!(seen[1], RealtimeEvent::Connected));
+        match &seen[2] {
+            RealtimeEvent::Update(data) => assert_eq!(data.unpulsed_keys, 100),
+            other => panic!("unexpected event {:?}", other),
+        }
+        assert!(matches!(seen[3], RealtimeEvent::Notice { .. }));
+        assert!(matches!(seen[4], RealtimeEvent::Disconnected(_)));
+        assert_eq!(hub.state().latest.unwrap().unpulsed_keys, 100);
+    }
+}
@@ -0,0 +1,370 @@
+//! Typing speed from the realtime feed.
+//!
+//! Words are the usual 5 keystrokes. Gross WPM counts every key; net WPM is an
+//! estimate that discounts corrections: each BACKSPACE or DELETE press takes
+//! out itself and the character it removes. The client only sends counters, so
+//! both are derived from deltas between consecutive updates.
+
+use super::RealtimeData;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Local};
+use directories::ProjectDirs;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fs;
+use std::path::PathBuf;
+
+pub const CHARS_PER_WORD: f64 = 5.0;
+const CORRECTION_KEYS: [&str; 2] = ["BACKSPACE", "DELETE"];
+/// Net WPM samples kept for the history chart.
+const HISTORY_LEN: usize = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WpmWindow {
+    TenSeconds,
+    OneMinute,
+    FiveMinutes,
+}
+
+impl WpmWindow {
+    pub const ALL: [WpmWindow; 3] = [
+        WpmWindow::TenSeconds,
+        WpmWindow::OneMinute,
+        WpmWindow::FiveMinutes,
+    ];
+
+    pub fn duration(self) -> Duration {
+        match self {
+            WpmWindow::TenSeconds => Duration::seconds(10),
+            WpmWindow::OneMinute => Duration::minutes(1),
+            WpmWindow::FiveMinutes => Duration::minutes(5),
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            WpmWindow::TenSeconds => "10s",
+            WpmWindow::OneMinute => "1m",
+            WpmWindow::FiveMinutes => "5m",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WpmBest {
+    pub wpm: f64,
+    /// Local time it was set, "YYYY-MM-DD HH:MM".
+    pub achieved: String,
+}
+
+/// Best net WPM per window, kept in the data directory across sessions.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WpmBests {
+    pub ten_seconds: Option<WpmBest>,
+    pub one_minute: Option<WpmBest>,
+    pub five_minutes: Option<WpmBest>,
+}
+
+impl WpmBests {
+    pub fn get(&self, window: WpmWindow) -> Option<&WpmBest> {
+        match window {
+            WpmWindow::TenSeconds => self.ten_seconds.as_ref(),
+            WpmWindow::OneMinute => self.one_minute.as_ref(),
+            WpmWindow::FiveMinutes => self.five_minutes.as_ref(),
+        }
+    }
+
+    fn slot(&mut self, window: WpmWindow) -> &mut Option<WpmBest> {
+        match window {
+            WpmWindow::TenSeconds => &mut self.ten_seconds,
+            WpmWindow::OneMinute => &mut self.one_minute,
+            WpmWindow::FiveMinutes => &mut self.five_minutes,
+        }
+    }
+
+    pub fn load() -> Result<Self> {
+        let path = Self::path()?;
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read personal bests at {:?}", path))?;
+        toml::from_str(&content).with_context(|| "Failed to parse personal bests")
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let path = Self::path()?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create data directory at {:?}", parent))?;
+        }
+        let content =
+            toml::to_string_pretty(self).with_context(|| "Failed to serialize personal bests")?;
+        fs::write(&path, content)
+            .with_context(|| format!("Failed to write personal bests at {:?}", path))
+    }
+
+    fn path() -> Result<PathBuf> {
+        let proj_dirs = ProjectDirs::from("com", "wtfpulse", "wtfpulse")
+            .ok_or_else(|| anyhow::anyhow!("Could not determine home directory"))?;
+        Ok(proj_dirs.data_dir().join("wpm_bests.toml"))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Sample {
+    at: DateTime<Local>,
+    keys: u64,
+    corrections: u64,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct WpmTracker {
+    started: Option<DateTime<Local>>,
+    last_keys: Option<u64>,
+    last_corrections: Option<u64>,
+    /// Deltas covering the longest window, oldest first.
+    samples: VecDeque<Sample>,
+    /// Net 1m WPM after each update.
+    pub history: Vec<u64>,
+    pub bests: WpmBests,
+    /// A best improved since the last [`Self::save_bests`].
+    unsaved: bool,
+    /// Fed replayed frames, which are timed on arrival; bests aren't kept.
+    replaying: bool,
+}
+
+impl WpmTracker {
+    pub fn new(bests: WpmBests) -> Self {
+        Self {
+            bests,
+            ..Default::default()
+        }
+    }
+
+    /// A tracker for `--replay`: frames arrive at replay speed, so their WPM
+    /// is shown but never becomes a personal best.
+    pub fn replaying() -> Self {
+        Self {
+            replaying: true,
+            ..Default::default()
+        }
+    }
+
+    /// Feeds one realtime update. Returns true when a personal best improved.
+    /// Early in a session that's nearly every update, so this doesn't touch
+    /// the disk; call [`Self::save_bests`] now and then and at exit.
+    pub fn update(&mut self, data: &RealtimeData) -> bool {
+        self.update_at(data, Local::now())
+    }
+
+    /// Writes [`Self::bests`] if any improved since the last save.
+    pub fn save_bests(&mut self) -> Result<()> {
+        if self.unsaved {
+            self.bests.save()?;
+            self.unsaved = false;
+        }
+        Ok(())
+    }
+
+    fn update_at(&mut self, data: &RealtimeData, now: DateTime<Local>) -> bool {
+        self.started.get_or_insert(now);
+
+        let keys = data.unpulsed_keys.max(0) as u64;
+        let delta_keys = self.last_keys.map_or(0, |last| keys.saturating_sub(last));
+        self.last_keys = Some(keys);
+
+        // Older clients and some frames leave the heatmap out; that says
+        // nothing about corrections, so keep the previous count.
+        let mut delta_corrections = 0;
+        if !data.heatmap.is_empty() {
+            let corrections: u64 = CORRECTION_KEYS
+                .iter()
+                .filter_map(|key| data.heatmap.get(*key))
+                .sum();
+            delta_corrections = self
+                .last_corrections
+                .map_or(0, |last| corrections.saturating_sub(last));
+            self.last_corrections = Some(corrections);
+        }
+
+        if delta_keys > 0 {
+            self.samples.push_back(Sample {
+                at: now,
+                keys: delta_keys,
+                corrections: delta_corrections.min(delta_keys),
+            });
+        }
+        let horizon = now - WpmWindow::FiveMinutes.duration();
+        while self.samples.front().is_some_and(|s| s.at <= horizon) {
+            self.samples.pop_front();
+        }
+
+        self.history
+            .push(self.net_wpm_at(WpmWindow::OneMinute, now).round() as u64);
+        if self.history.len() > HISTORY_LEN {
+            self.history.remove(0);
+        }
+
+        if self.replaying {
+            return false;
+        }
+        let mut improved = false;
+        for window in WpmWindow::ALL {
+            // Only a full window counts, so a short burst can't set the 5m best.
+            if !self.covers(window, now) {
+                continue;
+            }
+            let wpm = self.net_wpm_at(window, now);
+            let slot = self.bests.slot(window);
+            if wpm > 0.0 && slot.as_ref().is_none_or(|best| wpm > best.wpm) {
+                *slot = Some(WpmBest {
+                    wpm,
+                    achieved: now.format("%Y-%m-%d %H:%M").to_string(),
+                });
+                improved = true;
+            }
+        }
+        self.unsaved |= improved;
+        improved
+    }
+
+    pub fn gross_wpm(&self, window: WpmWindow) -> f64 {
+        self.gross_wpm_at(window, Local::now())
+    }
+
+    pub fn net_wpm(&self, window: WpmWindow) -> f64 {
+        self.net_wpm_at(window, Local::now())
+    }
+
+    fn gross_wpm_at(&self, window: WpmWindow, now: DateTime<Local>) -> f64 {
+        let (keys, _) = self.totals(window, now);
+        self.per_minute(keys as f64, window, now)
+    }
+
+    fn net_wpm_at(&self, window: WpmWindow, now: DateTime<Local>) -> f64 {
+        let (keys, corrections) = self.totals(window, now);
+        let net = keys.saturating_sub(2 * corrections);
+        self.per_minute(net as f64, window, now)
+    }
+
+    fn totals(&self, window: WpmWindow, now: DateTime<Local>) -> (u64, u64) {
+        let since = now - window.duration();
+        self.samples
+            .iter()
+            .filter(|s| s.at > since && s.at <= now)
+            .fold((0, 0), |(k, c), s| (k + s.keys, c + s.corrections))
+    }
+
+    /// Words per minute over the window, or over the time since the first
+    /// update while that is still shorter.
+    fn per_minute(&self, keystrokes: f64, window: WpmWindow, now: DateTime<Local>) -> f64 {
+        let Some(started) = self.started else {
+            return 0.0;
+        };
+        let span = (now - started).min(window.duration());
+        let minutes = span.num_milliseconds() as f64 / 60_000.0;
+        if span < Duration::seconds(1) {
+            return 0.0;
+        }
+        keystrokes / CHARS_PER_WORD / minutes
+    }
+
+    fn covers(&self, window: WpmWindow, now: DateTime<Local>) -> bool {
+        self.started
+            .is_some_and(|started| now - started >= window.duration())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(seconds: i64) -> DateTime<Local> {
+        Local.with_ymd_and_hms(2025, 3, 1, 9, 0, 0).unwrap() + Duration::seconds(seconds)
+    }
+
+    fn frame(keys: i64, backspaces: u64) -> RealtimeData {
+        let mut data = RealtimeData {
+            unpulsed_keys: keys,
+            ..Default::default()
+        };
+        data.heatmap.insert("A".to_string(), keys as u64);
+        data.heatmap.insert("BACKSPACE".to_string(), backspaces);
+        data
+    }
+
+    #[test]
+    fn test_gross_and_net_over_windows() {
+        let mut wpm = WpmTracker::default();
+        // 5 keys a second, one of them a backspace: 60 gross, 36 net.
+        for s in 0..=10 {
+            wpm.update_at(&frame(5 * s, s as u64), at(s));
+        }
+        let now = at(10);
+        assert!((wpm.gross_wpm_at(WpmWindow::TenSeconds, now) - 60.0).abs() < 1e-9);
+        assert!((wpm.net_wpm_at(WpmWindow::TenSeconds, now) - 36.0).abs() < 1e-9);
+        // Longer windows use the time tracked so far.
+        assert!((wpm.gross_wpm_at(WpmWindow::FiveMinutes, now) - 60.0).abs() < 1e-9);
+
+        // Idle for 50 seconds: the 10s window empties, 1m averages it out.
+        let idle = at(60);
+        wpm.update_at(&frame(50, 10), idle);
+        assert_eq!(wpm.gross_wpm_at(WpmWindow::TenSeconds, idle), 0.0);
+        assert!((wpm.gross_wpm_at(WpmWindow::OneMinute, idle) - 10.0).abs() < 1e-9);
+        assert_eq!(wpm.history.len(), 12);
+    }
+
+    #[test]
+    fn test_pulses_and_missing_heatmaps_are_not_typing() {
+        let mut wpm = WpmTracker::default();
+        wpm.update_at(&frame(500, 40), at(0));
+        // A pulse resets the counters.
+        wpm.update_at(&frame(0, 0), at(1));
+        // No heatmap in this frame; corrections stay unknown, not zero.
+        wpm.update_at(
+            &RealtimeData {
+                unpulsed_keys: 10,
+                ..Default::default()
+            },
+            at(2),
+        );
+        wpm.update_at(&frame(20, 0), at(3));
+        let (keys, corrections) = wpm.totals(WpmWindow::OneMinute, at(3));
+        assert_eq!((keys, corrections), (20, 0));
+    }
+
+    #[test]
+    fn test_bests_need_a_full_window() {
+        let mut wpm = WpmTracker::default();
+        assert!(!wpm.update_at(&frame(0, 0), at(0)));
+        for s in 1..10 {
+            assert!(!wpm.update_at(&frame(10 * s, 0), at(s)));
+        }
+        assert!(wpm.update_at(&frame(100, 0), at(10)));
+        assert!(wpm.unsaved);
+        let best = wpm.bests.get(WpmWindow::TenSeconds).unwrap();
+        assert!((best.wpm - 120.0).abs() < 1e-9);
+        assert_eq!(best.achieved, "2025-03-01 09:00");
+        assert!(wpm.bests.get(WpmWindow::OneMinute).is_none());
+
+        // Slower typing doesn't replace it.
+        assert!(!wpm.update_at(&frame(101, 0), at(11)));
+
+        let saved = toml::to_string_pretty(&wpm.bests).unwrap();
+        assert_eq!(toml::from_str::<WpmBests>(&saved).unwrap(), wpm.bests);
+    }
+
+    #[test]
+    fn test_replays_set_no_bests() {
+        let mut wpm = WpmTracker::replaying();
+        for s in 0..=10 {
+            assert!(!wpm.update_at(&frame(10 * s, 0), at(s)));
+        }
+        assert!((wpm.net_wpm_at(WpmWindow::TenSeconds, at(10)) - 120.0).abs() < 1e-9);
+        assert_eq!(wpm.bests, WpmBests::default());
+        assert!(!wpm.unsaved);
+    }
+}
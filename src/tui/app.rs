@@ -1,23 +1,55 @@
 use crate::client::{ComputerResponse, PulseResponse, UserResponse, WhatpulseClient};
-use crate::commands::calorimetry::{EnergyStats, SwitchProfile, calculate_energy};
+use crate::commands::calorimetry::{
+    EnergyStats, MouseSwitchProfile, SwitchProfile, calculate_energy,
+};
 use crate::commands::get_pages;
 use crate::commands::keyboard::layouts::KeyboardLayout;
 use crate::commands::keyboard::layouts::get_api_key_from_char;
-use crate::db::{AppStats, MouseStats, NetworkStats};
+use crate::commands::rhythm::RhythmMatrix;
+use crate::commands::typing_test::TypingTest;
+use crate::db::{AppStats, DailyTotals, MouseStats, NetworkStats};
+use crate::realtime::auto_pulse::{AutoPulse, AutoPulseEntry};
+use crate::realtime::wpm::{WpmBests, WpmTracker};
+use crate::realtime::{ConnectionLogEntry, RealtimeEvent, RealtimeHub};
+pub use crate::realtime::{MonitorCommand, RealtimeData};
 pub use crate::tui::state::{
-    AppSortMode, AppsState, ExtendedMouseStats, KeyboardState, MouseState, NavigationState,
-    NetworkSortMode, NetworkState, ScrollMode, SortOrder, TimePeriod, UnitSystem,
+    AppSortMode, AppsState, CompareBaseline, CompareState, ExtendedMouseStats, ForecastState,
+    KeyCounts, KeyboardPanel, KeyboardState, LayoutLabState, MouseState, NavigationState,
+    NetworkSortMode, NetworkState, RhythmState, ScrollMode, SortOrder, TimePeriod, UnitSystem,
 };
 
 use anyhow::Result;
-use crossterm::event::{KeyCode, KeyEvent};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use tokio::sync::mpsc;
 
 use log::info;
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 
 use std::cell::RefCell;
 
+/// Samples kept for the Kinetic page sparklines.
+const HISTORY_LEN: usize = 100;
+
+/// How often improved WPM personal bests are written to disk.
+const WPM_SAVE_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60);
+
+fn push_history(history: &mut Vec<u64>, value: u64) {
+    history.push(value);
+    if history.len() > HISTORY_LEN {
+        history.remove(0);
+    }
+}
+
+/// Increase of an unpulsed counter since the previous update. The first
+/// update and pulses (the counter drops back) count as zero.
+fn counter_delta(previous: i64, current: i64) -> i64 {
+    if previous == 0 {
+        0
+    } else {
+        (current - previous).max(0)
+    }
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct KineticStats {
     pub current_power_watts: f64,
@@ -28,6 +60,7 @@ pub struct KineticStats {
     pub is_connected: bool,
     pub connection_error: Option<String>,
     pub last_keys: i64,
+    pub last_clicks: i64,
     pub last_scrolls: i64,
     pub last_velocity_mps: f64,
     pub last_update: Option<chrono::DateTime<chrono::Local>>,
@@ -37,17 +70,55 @@ pub struct KineticStats {
     pub unpulsed_clicks: i64,
     pub unpulsed_scrolls: i64,
     pub keys_per_second: f64,
+    pub clicks_per_second: f64,
+    /// Not sent by the client; derived from the unpulsed scroll count.
+    pub scrolls_per_second: f64,
+    pub download_bytes_per_second: f64,
+    pub upload_bytes_per_second: f64,
+    // Rates in hundredths per second, for sparklines
+    pub history_keys: Vec<u64>,
+    pub history_clicks: Vec<u64>,
+    pub history_scrolls: Vec<u64>,
+    pub peak_keys_per_second: f64,
+    pub peak_clicks_per_second: f64,
+    pub peak_scrolls_per_second: f64,
+    // Counted since the page started receiving updates
+    pub session_keys: u64,
+    pub session_clicks: u64,
+    pub session_scrolls: u64,
+    pub click_power_watts: f64,
+    pub click_work_joules: f64,
+    // Realtime connection history for the Kinetic page
+    pub connection_log: VecDeque<ConnectionLogEntry>,
+    pub reconnects: u32,
 }
 
 impl KineticStats {
-    pub fn update(&mut self, data: &RealtimeData, profile: &SwitchProfile) -> u32 {
+    pub fn update(
+        &mut self,
+        data: &RealtimeData,
+        profile: &SwitchProfile,
+        mouse_profile: &MouseSwitchProfile,
+    ) {
+        self.update_at(data, profile, mouse_profile, chrono::Local::now());
+    }
+
+    fn update_at(
+        &mut self,
+        data: &RealtimeData,
+        profile: &SwitchProfile,
+        mouse_profile: &MouseSwitchProfile,
+        now: chrono::DateTime<chrono::Local>,
+    ) {
         // Store raw data
         self.unpulsed_keys = data.unpulsed_keys;
         self.unpulsed_clicks = data.unpulsed_clicks;
         self.unpulsed_scrolls = data.unpulsed_scrolls;
         self.keys_per_second = data.keys_per_second;
+        self.clicks_per_second = data.clicks_per_second;
+        self.download_bytes_per_second = data.download_bytes_per_second;
+        self.upload_bytes_per_second = data.upload_bytes_per_second;
 
-        let now = chrono::Local::now();
         let dt = if let Some(last) = self.last_update {
             (now - last).num_milliseconds() as f64 / 1000.0
         } else {
@@ -55,28 +126,37 @@ impl KineticStats {
         };
         self.last_update = Some(now);
 
-        // Calculate Delta Keys
-        let delta = if self.last_keys == 0 {
-            0 // First update, don't jump
-        } else {
-            data.unpulsed_keys - self.last_keys
-        };
-
-        // Handle reset (if unpulsed keys drops to 0)
-        let delta = if delta < 0 { 0 } else { delta };
-
-        // Update last keys
+        // Counter deltas since the last update
+        let delta = counter_delta(self.last_keys, data.unpulsed_keys);
+        let delta_clicks = counter_delta(self.last_clicks, data.unpulsed_clicks);
+        let delta_scrolls = counter_delta(self.last_scrolls, data.unpulsed_scrolls);
         self.last_keys = data.unpulsed_keys;
-
-        // Calculate Delta Scrolls
-        let delta_scrolls = if self.last_scrolls == 0 {
-            0 // First update
-        } else {
-            data.unpulsed_scrolls - self.last_scrolls
-        };
-        let delta_scrolls = if delta_scrolls < 0 { 0 } else { delta_scrolls };
+        self.last_clicks = data.unpulsed_clicks;
         self.last_scrolls = data.unpulsed_scrolls;
 
+        self.session_keys += delta as u64;
+        self.session_clicks += delta_clicks as u64;
+        self.session_scrolls += delta_scrolls as u64;
+        if dt > 0.0 {
+            self.scrolls_per_second = delta_scrolls as f64 / dt;
+        }
+
+        self.peak_keys_per_second = self.peak_keys_per_second.max(data.keys_per_second);
+        self.peak_clicks_per_second = self.peak_clicks_per_second.max(data.clicks_per_second);
+        self.peak_scrolls_per_second = self.peak_scrolls_per_second.max(self.scrolls_per_second);
+        push_history(
+            &mut self.history_keys,
+            (data.keys_per_second * 100.0) as u64,
+        );
+        push_history(
+            &mut self.history_clicks,
+            (data.clicks_per_second * 100.0) as u64,
+        );
+        push_history(
+            &mut self.history_scrolls,
+            (self.scrolls_per_second * 100.0) as u64,
+        );
+
         // Force (N) * Distance (m) * Keys/s = Power (W)
         let power = profile.force_newtons * profile.distance_meters * data.keys_per_second;
 
@@ -95,29 +175,19 @@ impl KineticStats {
         let work_joules = profile.force_newtons * profile.distance_meters * (delta as f64);
         self.accumulated_work_joules += work_joules;
 
+        // Same for the mouse buttons
+        self.click_power_watts = mouse_profile.work_per_click() * data.clicks_per_second;
+        self.click_work_joules += mouse_profile.work_per_click() * delta_clicks as f64;
+
         // Update stats
         self.current_power_watts = power;
         self.peak_velocity_mps = self.peak_velocity_mps.max(velocity);
 
         // History for sparkline (scale up for visibility)
-        self.history_power.push((power * 1000.0) as u64); // mW for better resolution
-        if self.history_power.len() > 100 {
-            self.history_power.remove(0);
-        }
-
-        delta_scrolls as u32
+        push_history(&mut self.history_power, (power * 1000.0) as u64); // mW for better resolution
     }
 }
 
-#[derive(Debug, Clone)]
-pub struct RealtimeData {
-    pub unpulsed_keys: i64,
-    pub unpulsed_clicks: i64,
-    pub unpulsed_scrolls: i64,
-    pub keys_per_second: f64,
-    pub heatmap: HashMap<String, u64>,
-}
-
 pub enum Action {
     Tick,
     Quit,
@@ -132,10 +202,15 @@ pub enum Action {
     MouseHeatmapLoaded(Vec<Vec<u64>>),
     MouseHeatmapError(String),
     MouseStatsLoaded(Box<ExtendedMouseStats>),
+    KeyCountsLoaded(Result<KeyCounts>),
     AppStatsLoaded(Result<Vec<AppStats>>),
     NetworkStatsLoaded(Result<Vec<NetworkStats>>),
+    RhythmLoaded(Box<Result<RhythmMatrix>>),
+    DailyTotalsLoaded(Result<Vec<DailyTotals>>),
     WebSocketStatus(bool, Option<String>),
     RealtimeUpdate(RealtimeData),
+    ConnectionLog(VecDeque<ConnectionLogEntry>, u32),
+    ClientNotice(String),
     DebugInfo(String),
     TogglePopup,
     SelectLayout,
@@ -174,12 +249,6 @@ impl Default for DatePickerState {
     }
 }
 
-#[derive(Debug, Clone)]
-pub enum MonitorCommand {
-    Pulse,
-    OpenWindow,
-}
-
 pub struct App {
     pub user_stats: Option<UserResponse>,
     pub recent_pulses: Vec<PulseResponse>,
@@ -199,11 +268,19 @@ pub struct App {
     pub mouse: MouseState,
     pub keyboard: KeyboardState,
     pub apps: AppsState,
+    pub layout_lab: LayoutLabState,
     pub network: NetworkState,
+    pub rhythm: RhythmState,
 
     pub dashboard_period: TimePeriod,
     pub date_picker: DatePickerState,
+    pub compare: CompareState,
+    pub forecast: ForecastState,
     pub kinetic_stats: KineticStats,
+    pub wpm: WpmTracker,
+    wpm_saved: std::time::Instant,
+    pub typing_test: TypingTest,
+    pub auto_pulse: AutoPulse,
     pub unit_system: UnitSystem,
     pub data_source: String,
 
@@ -225,6 +302,13 @@ impl App {
         let config = crate::config::AppConfig::load().unwrap_or_default();
         let refresh_rate =
             std::time::Duration::from_secs(config.refresh_rate_seconds.unwrap_or(60));
+        let (auto_pulse, auto_pulse_error) = match &config.auto_pulse {
+            Some(rules) => match AutoPulse::from_config(rules) {
+                Ok(engine) => (engine, None),
+                Err(e) => (AutoPulse::default(), Some(e.to_string())),
+            },
+            None => (AutoPulse::default(), None),
+        };
 
         Self {
             user_stats: None,
@@ -234,7 +318,7 @@ impl App {
             user_loading: true,
             pulses_loading: true,
             computers_loading: true,
-            error: None,
+            error: auto_pulse_error.map(|e| format!("Auto-pulse disabled: {}", e)),
             pulses_error: None,
             client,
             tx,
@@ -246,11 +330,19 @@ impl App {
                 ..Default::default()
             },
             apps: AppsState::default(),
+            layout_lab: LayoutLabState::default(),
             network: NetworkState::default(),
+            rhythm: RhythmState::default(),
 
             dashboard_period: TimePeriod::All,
             date_picker: DatePickerState::default(),
+            compare: CompareState::default(),
+            forecast: ForecastState::default(),
             kinetic_stats: KineticStats::default(),
+            wpm: WpmTracker::new(WpmBests::load().unwrap_or_default()),
+            wpm_saved: std::time::Instant::now(),
+            typing_test: TypingTest::new(config.typing_test.as_ref()),
+            auto_pulse,
             unit_system: UnitSystem::Metric,
             data_source: String::new(),
 
@@ -272,6 +364,14 @@ impl App {
         self.notification = Some((message, std::time::Instant::now()));
     }
 
+    /// Writes improved WPM personal bests; also called when the TUI exits.
+    pub fn save_wpm_bests(&mut self) {
+        self.wpm_saved = std::time::Instant::now();
+        if let Err(e) = self.wpm.save_bests() {
+            log::warn!("Failed to save WPM personal bests: {}", e);
+        }
+    }
+
     pub fn set_monitor_tx(&mut self, tx: mpsc::Sender<MonitorCommand>) {
         self.monitor_tx = Some(tx);
     }
@@ -282,6 +382,18 @@ impl App {
         }
     }
 
+    /// Sends the pulse for an auto-pulse rule that fired (unless it's a dry
+    /// run) and tells the user.
+    async fn handle_auto_pulse(&mut self, entry: Option<AutoPulseEntry>) {
+        let Some(entry) = entry else {
+            return;
+        };
+        if !entry.dry_run {
+            self.trigger_pulse().await;
+        }
+        self.set_notification(entry.to_string());
+    }
+
     pub async fn trigger_open_window(&self) {
         if let Some(tx) = &self.monitor_tx {
             let _ = tx.send(MonitorCommand::OpenWindow).await;
@@ -292,6 +404,10 @@ impl App {
         &self.keyboard.profiles[self.keyboard.profile_index]
     }
 
+    pub fn current_mouse_profile(&self) -> &MouseSwitchProfile {
+        &self.mouse.switch_profiles[self.mouse.switch_profile_index]
+    }
+
     pub fn recalculate_energy(&mut self) {
         if let Some(keys) = self.user_stats.as_ref().and_then(|u| u.totals.keys) {
             let profile = self.current_profile();
@@ -355,10 +471,15 @@ impl App {
         match action {
             Action::Quit => return true,
             Action::Tick => {
+                let entry = self.auto_pulse.check_schedule(chrono::Local::now());
+                self.handle_auto_pulse(entry).await;
                 if self.last_refresh.elapsed() >= self.refresh_rate {
                     self.last_refresh = std::time::Instant::now();
                     let _ = self.tx.send(Action::Refresh).await;
                 }
+                if self.wpm_saved.elapsed() >= WPM_SAVE_INTERVAL {
+                    self.save_wpm_bests();
+                }
             }
             Action::Refresh => {
                 self.user_loading = true;
@@ -384,6 +505,8 @@ impl App {
                 match res {
                     Ok(pulses) => {
                         self.recent_pulses = pulses;
+                        self.rhythm.pulse_matrix.take();
+                        self.forecast.projection.take();
                         self.pulses_error = None;
                     }
                     Err(e) => self.pulses_error = Some(e.to_string()),
@@ -417,6 +540,10 @@ impl App {
                 self.mouse.stats = *stats;
                 self.recalculate_unpulsed();
             }
+            Action::KeyCountsLoaded(res) => match res {
+                Ok(counts) => self.keyboard.counts = Some(counts),
+                Err(e) => log::warn!("Failed to load key counts: {}", e),
+            },
             Action::AppStatsLoaded(res) => match res {
                 Ok(stats) => self.apps.stats = stats,
                 Err(e) => self.error = Some(format!("Failed to load app stats: {}", e)),
@@ -425,11 +552,40 @@ impl App {
                 Ok(stats) => self.network.stats = stats,
                 Err(e) => self.error = Some(format!("Failed to load network stats: {}", e)),
             },
+            Action::DailyTotalsLoaded(res) => match res {
+                Ok(totals) => {
+                    self.forecast.daily_totals = totals;
+                    self.forecast.projection.take();
+                }
+                Err(e) => log::warn!("Failed to load daily totals: {}", e),
+            },
+            Action::RhythmLoaded(res) => match *res {
+                Ok(matrix) => {
+                    self.rhythm.matrix = Some(matrix);
+                    self.rhythm.error = None;
+                }
+                Err(e) => {
+                    // Not fatal: the Rhythm page falls back to pulse history.
+                    self.rhythm.matrix = None;
+                    self.rhythm.error = Some(e.to_string());
+                }
+            },
             Action::WebSocketStatus(connected, error) => {
                 self.kinetic_stats.is_connected = connected;
                 self.kinetic_stats.connection_error = error;
             }
-            Action::RealtimeUpdate(data) => self.handle_realtime_update(data),
+            Action::RealtimeUpdate(data) => {
+                let entry = self
+                    .auto_pulse
+                    .check_update(data.unpulsed_keys, chrono::Local::now());
+                self.handle_realtime_update(data);
+                self.handle_auto_pulse(entry).await;
+            }
+            Action::ConnectionLog(log, reconnects) => {
+                self.kinetic_stats.connection_log = log;
+                self.kinetic_stats.reconnects = reconnects;
+            }
+            Action::ClientNotice(msg) => self.set_notification(msg),
             Action::DebugInfo(msg) => self.kinetic_stats.debug_info = Some(msg),
             Action::PopupSelect => self.handle_popup_select(),
             Action::TogglePopup => self.handle_toggle_popup(),
@@ -457,7 +613,7 @@ impl App {
             return false;
         }
 
-        if key.code == KeyCode::Char('?') {
+        if key.code == KeyCode::Char('?') && !self.is_typing() {
             self.show_help = !self.show_help;
             return false;
         }
@@ -470,18 +626,32 @@ impl App {
         }
 
         if let Some(k) = self.key_to_api_string(key.code) {
-             *self.keyboard.session_heatmap.entry(k).or_insert(0) += 1;
+            let modifiers: Vec<&str> = [
+                (KeyModifiers::CONTROL, "Ctrl"),
+                (KeyModifiers::ALT, "Alt"),
+                (KeyModifiers::SUPER, "Win"),
+                (KeyModifiers::SHIFT, "Shift"),
+            ]
+            .into_iter()
+            .filter(|(m, _)| key.modifiers.contains(*m))
+            .map(|(_, name)| name)
+            .collect();
+            self.keyboard.combos.record_key(&k, &modifiers);
+            *self.keyboard.session_heatmap.entry(k).or_insert(0) += 1;
         }
 
         let pages = get_pages();
         let mut handled = false;
-        if !self.nav.menu_open {
-             if let Some(page) = pages.get(self.nav.current_tab) {
-                 handled = (page.handle_key)(self, key);
-             }
+        if !self.nav.menu_open
+            && let Some(page) = pages.get(self.nav.current_tab)
+        {
+            handled = (page.handle_key)(self, key);
         }
 
-        if !handled && !self.nav.menu_open && self.nav.current_tab == 4 {
+        let on_scroll_tower = pages
+            .get(self.nav.current_tab)
+            .is_some_and(|page| page.title == "Scroll Tower");
+        if !handled && !self.nav.menu_open && on_scroll_tower {
              handled = self.handle_scroll_tower_shortcuts(key).await;
         }
 
@@ -491,6 +661,15 @@ impl App {
         false
     }
 
+    /// Whether keys are going into a text field or the typing test, where
+    /// global shortcuts like `?` must not fire.
+    fn is_typing(&self) -> bool {
+        let on_typing_test = get_pages()
+            .get(self.nav.current_tab)
+            .is_some_and(|page| page.title == "Typing Test");
+        self.is_editing_api_key || on_typing_test && self.typing_test.is_active()
+    }
+
     fn key_to_api_string(&self, code: KeyCode) -> Option<String> {
         match code {
             KeyCode::Char(c) => Some(get_api_key_from_char(c)),
@@ -592,29 +771,23 @@ impl App {
 
         match key.code {
             KeyCode::Esc | KeyCode::Char('q') => {
-                if self.nav.show_quit_confirm {
-                    self.nav.show_quit_confirm = false;
-                } else {
-                    self.nav.show_quit_confirm = true;
-                }
+                self.nav.show_quit_confirm = !self.nav.show_quit_confirm;
             }
             KeyCode::Enter | KeyCode::Char('y') => {
                 if self.nav.show_quit_confirm {
                     return true;
                 }
                 if key.code == KeyCode::Enter {
-                     let current_cat = pages[self.nav.current_tab].category;
-                     if let Some(indices) = category_map.get(current_cat) {
-                         if indices.len() > 1 {
-                             self.nav.menu_open = true;
-                         }
-                     }
+                    let current_cat = pages[self.nav.current_tab].category;
+                    if let Some(indices) = category_map.get(current_cat)
+                        && indices.len() > 1
+                    {
+                        self.nav.menu_open = true;
+                    }
                 }
             }
-            KeyCode::Char('n') => {
-                if self.nav.show_quit_confirm {
-                    self.nav.show_quit_confirm = false;
-                }
+            KeyCode::Char('n') if self.nav.show_quit_confirm => {
+                self.nav.show_quit_confirm = false;
             }
             KeyCode::Char('r') => {
                 self.user_loading = true;
@@ -625,12 +798,12 @@ impl App {
             KeyCode::Right => self.switch_category(&categories, &category_map, 1),
             KeyCode::Left => self.switch_category(&categories, &category_map, -1),
             KeyCode::Down => {
-                 let current_cat = pages[self.nav.current_tab].category;
-                 if let Some(indices) = category_map.get(current_cat) {
-                     if indices.len() > 1 {
-                         self.nav.menu_open = true;
-                     }
-                 }
+                let current_cat = pages[self.nav.current_tab].category;
+                if let Some(indices) = category_map.get(current_cat)
+                    && indices.len() > 1
+                {
+                    self.nav.menu_open = true;
+                }
             }
             _ => {}
         }
@@ -659,10 +832,13 @@ impl App {
     }
 
     fn handle_realtime_update(&mut self, data: RealtimeData) {
-        let profile = self.keyboard.profiles[self.keyboard.profile_index].clone();
-        let _ = self.kinetic_stats.update(&data, &profile);
+        let profile = self.current_profile().clone();
+        let mouse_profile = self.current_mouse_profile().clone();
+        self.kinetic_stats.update(&data, &profile, &mouse_profile);
+        self.wpm.update(&data);
 
         if !data.heatmap.is_empty() {
+            self.keyboard.combos.record_totals(&data.heatmap);
             self.keyboard.session_heatmap = data.heatmap.clone();
         }
 
@@ -822,8 +998,97 @@ pub fn spawn_fetch(client: WhatpulseClient, tx: mpsc::Sender<Action>) {
     spawn_fetch_mouse_heatmap(client.clone(), tx.clone(), "today");
 
     spawn_fetch_mouse_stats(tx.clone());
+    spawn_fetch_key_counts(tx.clone());
     spawn_fetch_app_stats(tx.clone(), "all");
     spawn_fetch_network_stats(tx.clone(), "all");
+    spawn_fetch_rhythm(tx.clone(), "all");
+    spawn_fetch_daily_totals(tx.clone());
+}
+
+/// Feeds hub events into the TUI's action loop until either side closes.
+pub async fn forward_realtime(hub: RealtimeHub, tx: mpsc::Sender<Action>) {
+    use tokio::sync::broadcast::error::RecvError;
+    let (state, mut events) = hub.subscribe_with_state();
+    if state.connected || state.last_error.is_some() {
+        let _ = tx
+            .send(Action::WebSocketStatus(state.connected, state.last_error))
+            .await;
+    }
+    let _ = tx
+        .send(Action::ConnectionLog(state.log, state.reconnects))
+        .await;
+
+    loop {
+        let event = match events.recv().await {
+            Ok(event) => event,
+            Err(RecvError::Lagged(_)) => continue,
+            Err(RecvError::Closed) => break,
+        };
+        if event.log_message().is_some() {
+            let state = hub.state();
+            if tx
+                .send(Action::ConnectionLog(state.log, state.reconnects))
+                .await
+                .is_err()
+            {
+                break;
+            }
+        }
+        let action = match event {
+            RealtimeEvent::Connected => Action::WebSocketStatus(true, None),
+            RealtimeEvent::Disconnected(error) => Action::WebSocketStatus(false, error),
+            RealtimeEvent::Error(error) => Action::WebSocketStatus(true, Some(error)),
+            RealtimeEvent::Update(data) => Action::RealtimeUpdate(data),
+            RealtimeEvent::Notice { message, .. } => Action::ClientNotice(message),
+            RealtimeEvent::Debug(msg) => Action::DebugInfo(msg),
+            RealtimeEvent::Connecting | RealtimeEvent::Reconnecting { .. } => continue,
+        };
+        if tx.send(action).await.is_err() {
+            break;
+        }
+    }
+}
+
+pub fn spawn_fetch_daily_totals(tx: mpsc::Sender<Action>) {
+    tokio::spawn(async move {
+        let res = tokio::task::spawn_blocking(move || -> Result<Vec<DailyTotals>> {
+            let db = crate::db::Database::new()?;
+            db.get_daily_totals("all")
+        })
+        .await;
+
+        match res {
+            Ok(res) => {
+                let _ = tx.send(Action::DailyTotalsLoaded(res)).await;
+            }
+            Err(e) => {
+                let _ = tx.send(Action::DailyTotalsLoaded(Err(e.into()))).await;
+            }
+        }
+    });
+}
+
+pub fn spawn_fetch_key_counts(tx: mpsc::Sender<Action>) {
+    tokio::spawn(async move {
+        let counts = tokio::task::spawn_blocking(move || -> Result<KeyCounts> {
+            let db = crate::db::Database::new()?;
+            Ok(KeyCounts {
+                today: db.get_key_count("today")?,
+                yesterday: db.get_key_count("yesterday")?,
+                all_time: db.get_key_count("all")?,
+            })
+        })
+        .await;
+
+        match counts {
+            Ok(res) => {
+                let _ = tx.send(Action::KeyCountsLoaded(res)).await;
+            }
+            Err(e) => {
+                let _ = tx.send(Action::KeyCountsLoaded(Err(e.into()))).await;
+            }
+        }
+    });
 }
 
 pub fn spawn_fetch_mouse_stats(tx: mpsc::Sender<Action>) {
@@ -901,6 +1166,29 @@ pub fn spawn_fetch_network_stats(tx: mpsc::Sender<Action>, period: &str) {
     });
 }
 
+pub fn spawn_fetch_rhythm(tx: mpsc::Sender<Action>, period: &str) {
+    let period = period.to_string();
+    tokio::spawn(async move {
+        let res = tokio::task::spawn_blocking(move || -> Result<RhythmMatrix> {
+            let db = crate::db::Database::new()?;
+            let rows = db.get_hourly_activity(&period)?;
+            Ok(RhythmMatrix::from_hourly(&rows))
+        })
+        .await;
+
+        match res {
+            Ok(res) => {
+                let _ = tx.send(Action::RhythmLoaded(Box::new(res))).await;
+            }
+            Err(e) => {
+                let _ = tx
+                    .send(Action::RhythmLoaded(Box::new(Err(e.into()))))
+                    .await;
+            }
+        }
+    });
+}
+
 pub fn spawn_fetch_mouse_heatmap(_client: WhatpulseClient, tx: mpsc::Sender<Action>, period: &str) {
     let period = period.to_string();
     tokio::spawn(async move {
@@ -978,8 +1266,9 @@ mod tests {
             unpulsed_scrolls: 0,
             keys_per_second: 5.0,
             heatmap: HashMap::new(),
+            ..Default::default()
         };
-        stats.update(&data1, &profile);
+        stats.update(&data1, &profile, &MouseSwitchProfile::default());
 
         // Delta should be 0 because last_keys was 0 (init logic to avoid huge jump)
         assert_eq!(stats.last_keys, 10);
@@ -996,14 +1285,48 @@ mod tests {
             unpulsed_scrolls: 0,
             keys_per_second: 5.0,
             heatmap: HashMap::new(),
+            ..Default::default()
         };
-        stats.update(&data2, &profile);
+        stats.update(&data2, &profile, &MouseSwitchProfile::default());
 
         // Work = F * d * Delta(10)
         let expected_work = profile.force_newtons * profile.distance_meters * 10.0;
         assert!((stats.accumulated_work_joules - expected_work).abs() < 1e-6);
     }
 
+    #[test]
+    fn test_kinetic_click_and_scroll_rates() {
+        let mut stats = KineticStats::default();
+        let profile = SwitchProfile::default();
+        let mouse = MouseSwitchProfile::new("Test", 100.0, 1.0);
+        let start = chrono::Local::now();
+        let sample = |clicks, scrolls, cps| RealtimeData {
+            unpulsed_keys: 1,
+            unpulsed_clicks: clicks,
+            unpulsed_scrolls: scrolls,
+            clicks_per_second: cps,
+            ..Default::default()
+        };
+
+        stats.update_at(&sample(5, 10, 0.5), &profile, &mouse, start);
+        let later = start + chrono::Duration::seconds(2);
+        stats.update_at(&sample(9, 30, 2.0), &profile, &mouse, later);
+        assert_eq!(stats.session_clicks, 4);
+        assert_eq!(stats.session_scrolls, 20);
+        assert_eq!(stats.scrolls_per_second, 10.0);
+        assert_eq!(stats.peak_clicks_per_second, 2.0);
+        assert_eq!(stats.history_clicks, vec![50, 200]);
+        assert!((stats.click_work_joules - 4.0 * mouse.work_per_click()).abs() < 1e-9);
+        assert!((stats.click_power_watts - 2.0 * mouse.work_per_click()).abs() < 1e-9);
+
+        // A pulse resets the unpulsed counters; nothing is lost or double counted.
+        let after_pulse = later + chrono::Duration::seconds(2);
+        stats.update_at(&sample(1, 0, 0.0), &profile, &mouse, after_pulse);
+        assert_eq!(stats.session_clicks, 4);
+        assert_eq!(stats.scrolls_per_second, 0.0);
+        assert_eq!(stats.peak_scrolls_per_second, 10.0);
+    }
+
     #[test]
     fn test_recalculate_unpulsed() {
         let mut app = App::new(
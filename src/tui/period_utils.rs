@@ -1,13 +1,16 @@
 use crate::tui::app::{
-    App, SelectionStep, TimePeriod, spawn_fetch_app_stats, spawn_fetch_network_stats,
+    App, DatePickerState, SelectionStep, TimePeriod, spawn_fetch_app_stats,
+    spawn_fetch_network_stats, spawn_fetch_rhythm,
 };
-use chrono::{Days, Months};
+use anyhow::Result;
+use chrono::{Days, Local, Months, NaiveDate};
 use crossterm::event::{KeyCode, KeyEvent};
 
 #[derive(Debug, Clone, Copy)]
 pub enum StatsTarget {
     Applications,
     Network,
+    Rhythm,
 }
 
 pub fn get_period_string(period: TimePeriod, app: &App) -> String {
@@ -29,6 +32,98 @@ pub fn get_period_string(period: TimePeriod, app: &App) -> String {
     }
 }
 
+/// Parses a CLI period string (the same format `get_period_string` produces)
+/// back into a `TimePeriod`, carrying custom ranges in a `DatePickerState`.
+pub fn parse_period_string(period: &str) -> Result<(TimePeriod, DatePickerState)> {
+    let mut picker = DatePickerState::default();
+    let parsed = match period {
+        "today" => TimePeriod::Today,
+        "yesterday" => TimePeriod::Yesterday,
+        "week" => TimePeriod::Week,
+        "month" => TimePeriod::Month,
+        "year" => TimePeriod::Year,
+        "all" => TimePeriod::All,
+        p if p.starts_with("custom:") => {
+            let parts: Vec<&str> = p.split(':').collect();
+            if parts.len() != 3 {
+                anyhow::bail!("Custom period must look like custom:YYYY-MM-DD:YYYY-MM-DD");
+            }
+            let start = NaiveDate::parse_from_str(parts[1], "%Y-%m-%d")?;
+            let end = NaiveDate::parse_from_str(parts[2], "%Y-%m-%d")?;
+            picker.start_date = Some(start.min(end));
+            picker.end_date = Some(start.max(end));
+            TimePeriod::Custom
+        }
+        other => anyhow::bail!(
+            "Unknown period '{}' (expected today, yesterday, week, month, year, all or custom:START:END)",
+            other
+        ),
+    };
+    Ok((parsed, picker))
+}
+
+pub fn is_date_in_period(
+    date: NaiveDate,
+    period: TimePeriod,
+    date_picker: &DatePickerState,
+) -> bool {
+    let now = Local::now().date_naive();
+    match period {
+        TimePeriod::Today => date == now,
+        TimePeriod::Yesterday => date == now.pred_opt().unwrap(),
+        TimePeriod::Week => {
+            let week_ago = now.checked_sub_days(Days::new(7)).unwrap();
+            date >= week_ago && date <= now
+        }
+        TimePeriod::Month => {
+            let month_ago = now.checked_sub_months(Months::new(1)).unwrap();
+            date >= month_ago && date <= now
+        }
+        TimePeriod::Year => {
+            let year_ago = now.checked_sub_months(Months::new(12)).unwrap();
+            date >= year_ago && date <= now
+        }
+        TimePeriod::All => true,
+        TimePeriod::Custom => {
+            if let (Some(start), Some(end)) = (date_picker.start_date, date_picker.end_date) {
+                date >= start && date <= end
+            } else {
+                false
+            }
+        }
+    }
+}
+
+/// Inclusive date range covered by a period, matching `is_date_in_period`.
+/// `All` has no fixed bounds and returns `None`, as does an incomplete custom range.
+pub fn period_range(
+    period: TimePeriod,
+    date_picker: &DatePickerState,
+) -> Option<(NaiveDate, NaiveDate)> {
+    let now = Local::now().date_naive();
+    match period {
+        TimePeriod::Today => Some((now, now)),
+        TimePeriod::Yesterday => now.pred_opt().map(|d| (d, d)),
+        TimePeriod::Week => now.checked_sub_days(Days::new(7)).map(|d| (d, now)),
+        TimePeriod::Month => now.checked_sub_months(Months::new(1)).map(|d| (d, now)),
+        TimePeriod::Year => now.checked_sub_months(Months::new(12)).map(|d| (d, now)),
+        TimePeriod::All => None,
+        TimePeriod::Custom => match (date_picker.start_date, date_picker.end_date) {
+            (Some(start), Some(end)) => Some((start, end)),
+            _ => None,
+        },
+    }
+}
+
+/// The range of equal length immediately before `range`.
+pub fn previous_range(range: (NaiveDate, NaiveDate)) -> (NaiveDate, NaiveDate) {
+    let (start, end) = range;
+    let len = (end - start).num_days().max(0) as u64 + 1;
+    let prev_end = start.pred_opt().unwrap_or(start);
+    let prev_start = start.checked_sub_days(Days::new(len)).unwrap_or(start);
+    (prev_start, prev_end)
+}
+
 pub fn get_display_period(period: TimePeriod) -> &'static str {
     match period {
         TimePeriod::Today => "Today",
@@ -66,89 +161,107 @@ pub fn cycle_period_prev(p: TimePeriod) -> TimePeriod {
 }
 
 pub fn fetch_stats(app: &App, target: StatsTarget) {
-    let period = match target {
-        StatsTarget::Applications => app.apps.period,
-        StatsTarget::Network => app.network.period,
-    };
-    let period_str = get_period_string(period, app);
+    let period_str = get_period_string(target_period(app, target), app);
     match target {
         StatsTarget::Applications => spawn_fetch_app_stats(app.tx.clone(), &period_str),
         StatsTarget::Network => spawn_fetch_network_stats(app.tx.clone(), &period_str),
+        StatsTarget::Rhythm => spawn_fetch_rhythm(app.tx.clone(), &period_str),
+    }
+}
+
+fn target_period(app: &App, target: StatsTarget) -> TimePeriod {
+    match target {
+        StatsTarget::Applications => app.apps.period,
+        StatsTarget::Network => app.network.period,
+        StatsTarget::Rhythm => app.rhythm.period,
+    }
+}
+
+fn set_target_period(app: &mut App, target: StatsTarget, period: TimePeriod) {
+    match target {
+        StatsTarget::Applications => app.apps.period = period,
+        StatsTarget::Network => app.network.period = period,
+        StatsTarget::Rhythm => app.rhythm.period = period,
     }
 }
 
+pub fn open_date_picker(picker: &mut DatePickerState) {
+    picker.open = true;
+    picker.selection_step = SelectionStep::Start;
+    picker.current_selection = picker
+        .start_date
+        .unwrap_or_else(|| Local::now().date_naive());
+}
+
 pub fn handle_date_picker_key(app: &mut App, key: KeyEvent) {
+    handle_date_picker_state_key(&mut app.date_picker, key);
+}
+
+pub fn handle_date_picker_state_key(picker: &mut DatePickerState, key: KeyEvent) {
     match key.code {
         KeyCode::Esc => {
-            app.date_picker.open = false;
+            picker.open = false;
         }
         KeyCode::Left => {
-            app.date_picker.current_selection = app
-                .date_picker
+            picker.current_selection = picker
                 .current_selection
                 .checked_sub_days(Days::new(1))
-                .unwrap_or(app.date_picker.current_selection);
+                .unwrap_or(picker.current_selection);
         }
         KeyCode::Right => {
-            app.date_picker.current_selection = app
-                .date_picker
+            picker.current_selection = picker
                 .current_selection
                 .checked_add_days(Days::new(1))
-                .unwrap_or(app.date_picker.current_selection);
+                .unwrap_or(picker.current_selection);
         }
         KeyCode::Up => {
-            app.date_picker.current_selection = app
-                .date_picker
+            picker.current_selection = picker
                 .current_selection
                 .checked_sub_days(Days::new(7))
-                .unwrap_or(app.date_picker.current_selection);
+                .unwrap_or(picker.current_selection);
         }
         KeyCode::Down => {
-            app.date_picker.current_selection = app
-                .date_picker
+            picker.current_selection = picker
                 .current_selection
                 .checked_add_days(Days::new(7))
-                .unwrap_or(app.date_picker.current_selection);
+                .unwrap_or(picker.current_selection);
         }
         KeyCode::PageUp => {
-            app.date_picker.current_selection = app
-                .date_picker
+            picker.current_selection = picker
                 .current_selection
                 .checked_sub_months(Months::new(1))
-                .unwrap_or(app.date_picker.current_selection);
+                .unwrap_or(picker.current_selection);
         }
         KeyCode::PageDown => {
-            app.date_picker.current_selection = app
-                .date_picker
+            picker.current_selection = picker
                 .current_selection
                 .checked_add_months(Months::new(1))
-                .unwrap_or(app.date_picker.current_selection);
+                .unwrap_or(picker.current_selection);
         }
-        KeyCode::Enter => match app.date_picker.selection_step {
+        KeyCode::Enter => match picker.selection_step {
             SelectionStep::Start => {
-                app.date_picker.start_date = Some(app.date_picker.current_selection);
-                app.date_picker.selection_step = SelectionStep::End;
+                picker.start_date = Some(picker.current_selection);
+                picker.selection_step = SelectionStep::End;
 
-                app.date_picker.current_selection = app
-                    .date_picker
+                picker.current_selection = picker
                     .current_selection
                     .checked_add_days(Days::new(1))
-                    .unwrap_or(app.date_picker.current_selection);
+                    .unwrap_or(picker.current_selection);
             }
             SelectionStep::End => {
-                let end = app.date_picker.current_selection;
-                if let Some(start) = app.date_picker.start_date {
+                let end = picker.current_selection;
+                if let Some(start) = picker.start_date {
                     if end >= start {
-                        app.date_picker.end_date = Some(end);
-                        app.date_picker.open = false;
+                        picker.end_date = Some(end);
+                        picker.open = false;
                     } else {
-                        app.date_picker.start_date = Some(end);
-                        app.date_picker.end_date = Some(start);
-                        app.date_picker.open = false;
+                        picker.start_date = Some(end);
+                        picker.end_date = Some(start);
+                        picker.open = false;
                     }
                 } else {
-                    app.date_picker.start_date = Some(end);
-                    app.date_picker.selection_step = SelectionStep::End;
+                    picker.start_date = Some(end);
+                    picker.selection_step = SelectionStep::End;
                 }
             }
         },
@@ -167,15 +280,8 @@ pub fn handle_period_nav(app: &mut App, key: KeyEvent, target: StatsTarget) -> b
 
     match key.code {
         KeyCode::Char('h') => {
-            let current = match target {
-                StatsTarget::Applications => app.apps.period,
-                StatsTarget::Network => app.network.period,
-            };
-            let new_period = cycle_period_prev(current);
-            match target {
-                StatsTarget::Applications => app.apps.period = new_period,
-                StatsTarget::Network => app.network.period = new_period,
-            };
+            let new_period = cycle_period_prev(target_period(app, target));
+            set_target_period(app, target, new_period);
 
             if new_period != TimePeriod::Custom {
                 fetch_stats(app, target);
@@ -183,15 +289,8 @@ pub fn handle_period_nav(app: &mut App, key: KeyEvent, target: StatsTarget) -> b
             true
         }
         KeyCode::Char('l') => {
-            let current = match target {
-                StatsTarget::Applications => app.apps.period,
-                StatsTarget::Network => app.network.period,
-            };
-            let new_period = cycle_period_next(current);
-            match target {
-                StatsTarget::Applications => app.apps.period = new_period,
-                StatsTarget::Network => app.network.period = new_period,
-            };
+            let new_period = cycle_period_next(target_period(app, target));
+            set_target_period(app, target, new_period);
 
             if new_period != TimePeriod::Custom {
                 fetch_stats(app, target);
@@ -199,10 +298,7 @@ pub fn handle_period_nav(app: &mut App, key: KeyEvent, target: StatsTarget) -> b
             true
         }
         KeyCode::Char('/') => {
-            match target {
-                StatsTarget::Applications => app.apps.period = TimePeriod::Custom,
-                StatsTarget::Network => app.network.period = TimePeriod::Custom,
-            };
+            set_target_period(app, target, TimePeriod::Custom);
             app.date_picker.open = true;
             app.date_picker.selection_step = SelectionStep::Start;
             if app.date_picker.start_date.is_none() {
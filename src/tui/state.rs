@@ -1,6 +1,12 @@
-use crate::commands::calorimetry::SwitchProfile;
+use crate::commands::calorimetry::{MouseSwitchProfile, SwitchProfile};
+use crate::commands::forecast::ForecastMethod;
+use crate::commands::keyboard::combos::ComboStats;
+use crate::commands::keyboard::geometry::Geometry;
 use crate::commands::keyboard::layouts::KeyboardLayout;
-use crate::db::{AppStats, MouseStats, NetworkStats};
+use crate::commands::layout_lab::{LabCacheKey, LabRow, LabSort};
+use crate::commands::rhythm::{RhythmMatrix, RhythmMetric};
+use crate::db::{AppStats, DailyTotals, MouseStats, NetworkStats};
+use crate::tui::app::DatePickerState;
 use ratatui::widgets::{ListState, TableState};
 use std::cell::RefCell;
 use std::collections::HashMap;
@@ -24,6 +30,23 @@ pub enum TimePeriod {
     Custom,
 }
 
+/// What the Overview compare mode puts next to the selected period.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum CompareBaseline {
+    #[default]
+    PreviousPeriod,
+    YearAgo,
+    Custom,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CompareState {
+    pub enabled: bool,
+    pub baseline: CompareBaseline,
+    /// Second range for `CompareBaseline::Custom`; the first one is `App::date_picker`.
+    pub picker_b: DatePickerState,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Default)]
 pub enum ScrollMode {
     #[default]
@@ -84,6 +107,9 @@ pub struct MouseState {
     pub scroll_mode: ScrollMode,
     pub session_start_scrolls: Option<u64>,
     pub current_total_scrolls: u64,
+    // Click switch used for click work on the Kinetic page
+    pub switch_profiles: Vec<MouseSwitchProfile>,
+    pub switch_profile_index: usize,
 }
 
 impl Default for MouseState {
@@ -98,20 +124,51 @@ impl Default for MouseState {
             scroll_mode: ScrollMode::default(),
             session_start_scrolls: None,
             current_total_scrolls: 0,
+            switch_profiles: vec![
+                MouseSwitchProfile::omron_d2fc(),
+                MouseSwitchProfile::kailh_gm8(),
+                MouseSwitchProfile::huano_pink_dot(),
+                MouseSwitchProfile::optical(),
+            ],
+            switch_profile_index: 0,
         }
     }
 }
 
+/// Key totals from the local database for the Keyboard page header.
+#[derive(Debug, Clone, Default)]
+pub struct KeyCounts {
+    pub today: u64,
+    pub yesterday: u64,
+    pub all_time: u64,
+}
+
 pub struct KeyboardState {
     pub profiles: Vec<SwitchProfile>,
     pub profile_index: usize,
     pub layout: KeyboardLayout,
+    pub geometry: Geometry,
+    pub panel: KeyboardPanel,
+    /// Layout the analysis compares against.
+    pub baseline: KeyboardLayout,
     pub show_layout_popup: bool,
     pub layout_search_query: String,
     pub layout_list_state: RefCell<ListState>,
     pub heatmap_data: HashMap<String, u64>,
     pub session_heatmap: HashMap<String, u64>,
     pub heatmap_error: Option<String>,
+    /// `None` until loaded, or when the database is unavailable.
+    pub counts: Option<KeyCounts>,
+    pub combos: ComboStats,
+}
+
+/// What the Keyboard page shows under its header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum KeyboardPanel {
+    #[default]
+    Heatmap,
+    FingerLoad,
+    Combos,
 }
 
 impl Default for KeyboardState {
@@ -125,16 +182,28 @@ impl Default for KeyboardState {
             ],
             profile_index: 0,
             layout: KeyboardLayout::Qwerty,
+            geometry: Geometry::default(),
+            panel: KeyboardPanel::default(),
+            baseline: KeyboardLayout::Qwerty,
             show_layout_popup: false,
             layout_search_query: String::new(),
             layout_list_state: RefCell::new(ListState::default()),
             heatmap_data: HashMap::new(),
             session_heatmap: HashMap::new(),
             heatmap_error: None,
+            counts: None,
+            combos: ComboStats::default(),
         }
     }
 }
 
+#[derive(Default)]
+pub struct LayoutLabState {
+    pub sort: LabSort,
+    pub table_state: RefCell<TableState>,
+    pub cache: RefCell<Option<(LabCacheKey, Vec<LabRow>)>>,
+}
+
 pub struct AppsState {
     pub stats: Vec<AppStats>,
     pub period: TimePeriod,
@@ -174,3 +243,48 @@ impl Default for NetworkState {
         }
     }
 }
+
+pub struct RhythmState {
+    /// Hourly matrix from the local DB; `None` means fall back to pulses.
+    pub matrix: Option<RhythmMatrix>,
+    pub period: TimePeriod,
+    pub metric: RhythmMetric,
+    pub error: Option<String>,
+    /// Matrix built from pulse history for a period, rebuilt only when the
+    /// period changes or pulses reload.
+    pub pulse_matrix: RefCell<Option<(PulseMatrixKey, RhythmMatrix)>>,
+}
+
+/// Period and custom range a pulse-history matrix was built for.
+pub type PulseMatrixKey = (
+    TimePeriod,
+    Option<chrono::NaiveDate>,
+    Option<chrono::NaiveDate>,
+);
+
+impl Default for RhythmState {
+    fn default() -> Self {
+        Self {
+            matrix: None,
+            period: TimePeriod::All,
+            metric: RhythmMetric::default(),
+            error: None,
+            pulse_matrix: RefCell::new(None),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ForecastState {
+    /// Daily history from the local DB, used when pulse history is unavailable.
+    pub daily_totals: Vec<DailyTotals>,
+    pub method: ForecastMethod,
+    /// Keys projection for the method and day it was made on. Cleared when
+    /// pulses or daily totals load.
+    pub projection: RefCell<Option<ForecastCache>>,
+}
+
+pub type ForecastCache = (
+    (ForecastMethod, chrono::NaiveDate),
+    Vec<(chrono::NaiveDate, f64)>,
+);
@@ -1,5 +1,5 @@
 use crate::commands::get_pages;
-use crate::tui::app::{App, SelectionStep};
+use crate::tui::app::{App, DatePickerState, SelectionStep};
 use crate::tui::tabs;
 use chrono::{Datelike, Days, NaiveDate};
 use ratatui::{
@@ -153,8 +153,12 @@ fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
 }
 
 pub fn render_date_picker(f: &mut Frame, app: &App, area: Rect) {
+    render_date_picker_state(f, &app.date_picker, " Date Picker ", area);
+}
+
+pub fn render_date_picker_state(f: &mut Frame, picker: &DatePickerState, title: &str, area: Rect) {
     let block = Block::default()
-        .title(" Date Picker ")
+        .title(title.to_string())
         .borders(Borders::ALL)
         .style(Style::default().bg(Color::DarkGray));
     // Use fixed size for calendar (approx 40x16 is good for readability)
@@ -165,12 +169,8 @@ pub fn render_date_picker(f: &mut Frame, app: &App, area: Rect) {
     let inner = block.inner(area);
 
     // Header
-    let current_month = app
-        .date_picker
-        .current_selection
-        .format("%B %Y")
-        .to_string();
-    let step_msg = match app.date_picker.selection_step {
+    let current_month = picker.current_selection.format("%B %Y").to_string();
+    let step_msg = match picker.selection_step {
         SelectionStep::Start => "Select START Date",
         SelectionStep::End => "Select END Date",
     };
@@ -204,7 +204,7 @@ pub fn render_date_picker(f: &mut Frame, app: &App, area: Rect) {
     let grid_area = header_layout[2];
 
     // Calculate calendar days
-    let sel = app.date_picker.current_selection;
+    let sel = picker.current_selection;
     let first_day_of_month = NaiveDate::from_ymd_opt(sel.year(), sel.month(), 1).unwrap();
     // Weekday: Mon=0..Sun=6 in chrono (Datelike::weekday().num_days_from_monday())
     // We want Sun=0..Sat=6.
@@ -227,14 +227,14 @@ pub fn render_date_picker(f: &mut Frame, app: &App, area: Rect) {
 
             // Check if in range
             let mut in_range = false;
-            if let (Some(s), Some(e)) = (app.date_picker.start_date, app.date_picker.end_date) {
+            if let (Some(s), Some(e)) = (picker.start_date, picker.end_date) {
                 if current_date >= s && current_date <= e {
                     in_range = true;
                 }
-            } else if let Some(s) = app.date_picker.start_date {
+            } else if let Some(s) = picker.start_date {
                 // During selection
-                if app.date_picker.selection_step == SelectionStep::End {
-                    if current_date >= s && current_date <= app.date_picker.current_selection {
+                if picker.selection_step == SelectionStep::End {
+                    if current_date >= s && current_date <= picker.current_selection {
                         in_range = true;
                     }
                 } else if current_date == s {
@@ -243,7 +243,7 @@ pub fn render_date_picker(f: &mut Frame, app: &App, area: Rect) {
             }
 
             // Colors
-            if current_date == app.date_picker.current_selection {
+            if current_date == picker.current_selection {
                 style = style.bg(Color::Yellow).fg(Color::Black);
             } else if in_range {
                 style = style.bg(Color::Blue);
@@ -251,10 +251,10 @@ pub fn render_date_picker(f: &mut Frame, app: &App, area: Rect) {
                 style = style.fg(Color::Gray);
             }
 
-            if Some(current_date) == app.date_picker.start_date {
+            if Some(current_date) == picker.start_date {
                 style = style.bg(Color::Green).fg(Color::Black);
             }
-            if Some(current_date) == app.date_picker.end_date {
+            if Some(current_date) == picker.end_date {
                 style = style.bg(Color::Red).fg(Color::Black);
             }
 